@@ -22,6 +22,15 @@ pub enum Command {
         #[clap(default_value_t = String::from("/migrations"))]
         migrations_dir: String,
     },
+    /// Import the foods of a USDA FoodData Central CSV dump
+    ImportFoods {
+        /// The extracted directory containing `food.csv` and `food_nutrient.csv`
+        dataset_dir: String,
+    },
+    /// List ingredients which are likely duplicates of each other
+    ///
+    /// Duplicates can be merged in the frontend.
+    ReportDuplicateIngredients,
     /// Generate new migrations (debug builds only)
     #[cfg(debug_assertions)]
     MakeMigrations {
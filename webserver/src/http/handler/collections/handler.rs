@@ -0,0 +1,319 @@
+use std::collections::HashSet;
+
+use galvyn::core::re_exports::axum::extract::Path;
+use galvyn::core::stuff::api_error::ApiError;
+use galvyn::core::stuff::api_error::ApiResult;
+use galvyn::core::stuff::api_error::FormErrors;
+use galvyn::core::stuff::api_json::ApiJson;
+use galvyn::core::stuff::schema::List;
+use galvyn::core::stuff::schema::SingleUuid;
+use galvyn::core::Module;
+use galvyn::delete;
+use galvyn::get;
+use galvyn::post;
+use galvyn::put;
+use galvyn::rorm::Database;
+use tracing::error;
+use url::Url;
+
+use super::schema::AddRecipeToCollectionRequest;
+use super::schema::CreateOrUpdateCollection;
+use super::schema::CreateOrUpdateCollectionErrors;
+use super::schema::FullCollection;
+use super::schema::ReorderCollectionRequest;
+use super::schema::SimpleCollection;
+use crate::http::handler::recipes::schema::SimpleRecipeWithTags;
+use crate::http::handler::tags::schema::SimpleTag;
+use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::account::Account;
+use crate::models::collections::Collection;
+use crate::models::collections::CollectionUuid;
+use crate::models::households::Household;
+use crate::models::recipes::Recipe;
+use crate::models::recipes::RecipeUuid;
+use crate::models::recipes::RecipeViewer;
+use crate::models::tags::Tag;
+use crate::modules::websockets::WebsocketManager;
+use crate::utils::markdown;
+
+/// Retrieves all collections ordered by their title.
+#[get("/all")]
+pub async fn get_all_collections(
+    household: Household,
+) -> ApiResult<ApiJson<List<SimpleCollection>>> {
+    let collections = Collection::query_all(Database::global(), &household.uuid).await?;
+
+    Ok(ApiJson(List {
+        list: collections
+            .into_iter()
+            .map(SimpleCollection::from)
+            .collect(),
+    }))
+}
+
+/// Retrieves a collection with its recipes in their order.
+#[get("/{collection_uuid}")]
+pub async fn get_collection(
+    viewer: RecipeViewer,
+    Path(collection_uuid): Path<CollectionUuid>,
+) -> ApiResult<ApiJson<FullCollection>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(collection) =
+        Collection::query_by_uuid(&mut tx, &viewer.household, &collection_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid collection uuid"));
+    };
+
+    let mut recipes = Vec::new();
+    for recipe_uuid in collection.query_recipes(&mut tx).await? {
+        let Some(recipe) = Recipe::query_by_uuid(&mut tx, &viewer, &recipe_uuid).await? else {
+            error!(recipe = ?recipe_uuid, "Recipe not found");
+            continue;
+        };
+
+        let tags = Tag::query_by_recipe(&mut tx, &viewer.household, &recipe.uuid).await?;
+
+        recipes.push(SimpleRecipeWithTags {
+            uuid: recipe.uuid,
+            name: recipe.name,
+            description: recipe.description,
+            tags: tags.into_iter().map(SimpleTag::from).collect(),
+            dietary: recipe.dietary,
+        });
+    }
+
+    tx.commit().await?;
+
+    Ok(ApiJson(FullCollection {
+        uuid: collection.uuid,
+        title: collection.title,
+        description_html: markdown::render(&collection.description),
+        description: collection.description,
+        cover_url: collection.cover_url,
+        account: collection.account,
+        recipes,
+    }))
+}
+
+/// Creates an empty collection.
+#[post("/")]
+pub async fn create_collection(
+    user: Account,
+    household: Household,
+    ApiJson(request): ApiJson<CreateOrUpdateCollection>,
+) -> ApiResult<ApiJson<SingleUuid>, CreateOrUpdateCollectionErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let mut errors = FormErrors::<CreateOrUpdateCollectionErrors>::new();
+
+    if Collection::query_by_title(&mut tx, &household.uuid, &request.title)
+        .await?
+        .is_some()
+    {
+        errors.title_already_exists = true;
+    }
+
+    if request.cover_url.as_deref().is_some_and(is_invalid_url) {
+        errors.cover_url_invalid = true;
+    }
+
+    errors.check()?;
+
+    let collection = Collection::create(
+        &mut tx,
+        request.title,
+        request.description,
+        request.cover_url,
+        user.uuid,
+        &household.uuid,
+    )
+    .await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::CollectionsChanged {})
+        .await;
+
+    Ok(ApiJson(SingleUuid {
+        uuid: collection.uuid.0,
+    }))
+}
+
+/// Updates the title, description and cover of a collection.
+#[put("/{collection_uuid}")]
+pub async fn update_collection(
+    household: Household,
+    Path(collection_uuid): Path<CollectionUuid>,
+    ApiJson(request): ApiJson<CreateOrUpdateCollection>,
+) -> ApiResult<(), CreateOrUpdateCollectionErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(collection) =
+        Collection::query_by_uuid(&mut tx, &household.uuid, &collection_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid collection uuid"));
+    };
+
+    let mut errors = FormErrors::<CreateOrUpdateCollectionErrors>::new();
+
+    if collection.title != request.title
+        && Collection::query_by_title(&mut tx, &household.uuid, &request.title)
+            .await?
+            .is_some()
+    {
+        errors.title_already_exists = true;
+    }
+
+    if request.cover_url.as_deref().is_some_and(is_invalid_url) {
+        errors.cover_url_invalid = true;
+    }
+
+    errors.check()?;
+
+    collection
+        .update(
+            &mut tx,
+            request.title,
+            request.description,
+            request.cover_url,
+        )
+        .await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::CollectionsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Deletes a collection.
+///
+/// The recipes in the collection are kept.
+#[delete("/{collection_uuid}")]
+pub async fn delete_collection(
+    household: Household,
+    Path(collection_uuid): Path<CollectionUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(collection) =
+        Collection::query_by_uuid(&mut tx, &household.uuid, &collection_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid collection uuid"));
+    };
+
+    collection.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::CollectionsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Appends a recipe to a collection.
+#[post("/{collection_uuid}/recipes")]
+pub async fn add_recipe_to_collection(
+    viewer: RecipeViewer,
+    Path(collection_uuid): Path<CollectionUuid>,
+    ApiJson(request): ApiJson<AddRecipeToCollectionRequest>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(collection) =
+        Collection::query_by_uuid(&mut tx, &viewer.household, &collection_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid collection uuid"));
+    };
+
+    if Recipe::query_by_uuid(&mut tx, &viewer, &request.recipe)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::bad_request("Invalid recipe uuid"));
+    }
+
+    if !collection.add_recipe(&mut tx, &request.recipe).await? {
+        return Err(ApiError::bad_request("Recipe is already in the collection"));
+    }
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(viewer.household, WsServerMsg::CollectionsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Removes a recipe from a collection.
+#[delete("/{collection_uuid}/recipes/{recipe_uuid}")]
+pub async fn remove_recipe_from_collection(
+    household: Household,
+    Path((collection_uuid, recipe_uuid)): Path<(CollectionUuid, RecipeUuid)>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(collection) =
+        Collection::query_by_uuid(&mut tx, &household.uuid, &collection_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid collection uuid"));
+    };
+
+    collection.remove_recipe(&mut tx, &recipe_uuid).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::CollectionsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Changes the order of the recipes in a collection.
+///
+/// The request has to contain every recipe of the collection exactly once.
+#[put("/{collection_uuid}/order")]
+pub async fn reorder_collection(
+    household: Household,
+    Path(collection_uuid): Path<CollectionUuid>,
+    ApiJson(request): ApiJson<ReorderCollectionRequest>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(collection) =
+        Collection::query_by_uuid(&mut tx, &household.uuid, &collection_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid collection uuid"));
+    };
+
+    let current: HashSet<_> = collection
+        .query_recipes(&mut tx)
+        .await?
+        .into_iter()
+        .collect();
+    let requested: HashSet<_> = request.recipes.iter().copied().collect();
+    if requested.len() != request.recipes.len() || requested != current {
+        return Err(ApiError::bad_request(
+            "The order has to contain every recipe of the collection exactly once",
+        ));
+    }
+
+    collection
+        .reorder_recipes(&mut tx, &request.recipes)
+        .await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::CollectionsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Checks whether a cover url is not an absolute http(s) url.
+fn is_invalid_url(url: &str) -> bool {
+    Url::parse(url).map_or(true, |url| !matches!(url.scheme(), "http" | "https"))
+}
@@ -0,0 +1,12 @@
+use super::schema::SimpleCollection;
+use crate::models::collections::Collection;
+
+impl From<Collection> for SimpleCollection {
+    fn from(value: Collection) -> Self {
+        Self {
+            uuid: value.uuid,
+            title: value.title,
+            cover_url: value.cover_url,
+        }
+    }
+}
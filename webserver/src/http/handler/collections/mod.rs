@@ -0,0 +1,19 @@
+use galvyn::core::GalvynRouter;
+use galvyn::openapi::OpenapiRouterExt;
+
+mod handler;
+mod impls;
+pub mod schema;
+
+pub fn initialize() -> GalvynRouter {
+    GalvynRouter::new()
+        .openapi_tag("Collections")
+        .handler(handler::get_all_collections)
+        .handler(handler::get_collection)
+        .handler(handler::create_collection)
+        .handler(handler::update_collection)
+        .handler(handler::delete_collection)
+        .handler(handler::add_recipe_to_collection)
+        .handler(handler::remove_recipe_from_collection)
+        .handler(handler::reorder_collection)
+}
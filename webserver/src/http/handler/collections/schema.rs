@@ -0,0 +1,85 @@
+//! Represents all collection responses and requests.
+
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::fields::types::MaxStr;
+
+use crate::http::handler::recipes::schema::SimpleRecipeWithTags;
+use crate::models::account::AccountUuid;
+use crate::models::collections::CollectionUuid;
+use crate::models::recipes::RecipeUuid;
+
+/// Represents a collection without its recipes.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleCollection {
+    /// The UUID of the collection.
+    pub uuid: CollectionUuid,
+
+    /// The title of the collection.
+    pub title: MaxStr<255>,
+
+    /// The URL of a cover image.
+    pub cover_url: Option<MaxStr<2048>>,
+}
+
+/// Represents a collection with its recipes in their order.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FullCollection {
+    /// The UUID of the collection.
+    pub uuid: CollectionUuid,
+
+    /// The title of the collection.
+    pub title: MaxStr<255>,
+
+    /// The description of the collection in markdown.
+    pub description: MaxStr<65535>,
+
+    /// The description of the collection rendered to sanitized HTML.
+    pub description_html: String,
+
+    /// The URL of a cover image.
+    pub cover_url: Option<MaxStr<2048>>,
+
+    /// The account which created the collection.
+    pub account: AccountUuid,
+
+    /// The recipes in the collection in their order.
+    pub recipes: Vec<SimpleRecipeWithTags>,
+}
+
+/// Represents the structure for creating or updating a collection.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateOrUpdateCollection {
+    /// The title of the collection.
+    pub title: MaxStr<255>,
+
+    /// The description of the collection in markdown.
+    #[serde(default)]
+    pub description: MaxStr<65535>,
+
+    /// The URL of a cover image.
+    pub cover_url: Option<MaxStr<2048>>,
+}
+
+/// Errors for creating or updating a collection.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CreateOrUpdateCollectionErrors {
+    pub title_already_exists: bool,
+    pub cover_url_invalid: bool,
+}
+
+/// Request to add a recipe to a collection.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AddRecipeToCollectionRequest {
+    /// The recipe to append to the collection.
+    pub recipe: RecipeUuid,
+}
+
+/// Request to change the order of the recipes in a collection.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReorderCollectionRequest {
+    /// All recipes of the collection in their new order.
+    pub recipes: Vec<RecipeUuid>,
+}
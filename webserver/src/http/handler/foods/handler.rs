@@ -0,0 +1,32 @@
+use galvyn::core::stuff::api_error::ApiResult;
+use galvyn::core::stuff::api_json::ApiJson;
+use galvyn::core::stuff::schema::Page;
+use galvyn::core::Module;
+use galvyn::post;
+use galvyn::rorm::Database;
+
+use super::schema::GetAllFoodsRequest;
+use super::schema::SimpleFood;
+use crate::models::foods::Food;
+
+/// Retrieves all foods of the food composition database with pagination support.
+#[post("/all")]
+pub async fn get_all_foods(
+    ApiJson(pagination): ApiJson<GetAllFoodsRequest>,
+) -> ApiResult<ApiJson<Page<SimpleFood>>> {
+    let GetAllFoodsRequest { page, filter_name } = pagination;
+
+    let mut tx = Database::global().start_transaction().await?;
+
+    let items = Food::query_all(&mut tx, &page, filter_name.as_deref()).await?;
+    let total = Food::query_total(&mut tx, filter_name.as_deref()).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiJson(Page {
+        items: items.into_iter().map(SimpleFood::from).collect(),
+        limit: page.limit,
+        offset: page.offset,
+        total,
+    }))
+}
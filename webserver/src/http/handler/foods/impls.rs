@@ -0,0 +1,13 @@
+use super::schema::SimpleFood;
+use crate::models::foods::Food;
+
+impl From<Food> for SimpleFood {
+    fn from(value: Food) -> Self {
+        Self {
+            uuid: value.uuid,
+            fdc_id: value.fdc_id,
+            name: value.name,
+            per_100g: value.per_100g,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Represents all food responses and requests.
+
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::core::stuff::schema::GetPageRequest;
+use galvyn::rorm::fields::types::MaxStr;
+
+use crate::models::foods::FoodUuid;
+use crate::models::foods::Nutrients;
+
+/// Represents an entry of the food composition database.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleFood {
+    /// The UUID for the food.
+    pub uuid: FoodUuid,
+
+    /// The id of the food in the USDA FoodData Central dataset.
+    pub fdc_id: i64,
+
+    /// The description of the food.
+    pub name: MaxStr<255>,
+
+    /// The nutrients per 100 g of the food.
+    pub per_100g: Nutrients,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetAllFoodsRequest {
+    /// Page request
+    #[serde(flatten)]
+    pub page: GetPageRequest,
+    /// Search for food name
+    pub filter_name: Option<String>,
+}
@@ -0,0 +1,239 @@
+use galvyn::core::re_exports::axum::extract::Path;
+use galvyn::core::stuff::api_error::ApiError;
+use galvyn::core::stuff::api_error::ApiResult;
+use galvyn::core::stuff::api_error::FormErrors;
+use galvyn::core::stuff::api_json::ApiJson;
+use galvyn::core::stuff::schema::SingleUuid;
+use galvyn::core::Module;
+use galvyn::delete;
+use galvyn::get;
+use galvyn::post;
+use galvyn::put;
+use galvyn::rorm::Database;
+use tracing::error;
+
+use super::schema::AcceptHouseholdInvitationErrors;
+use super::schema::FullHousehold;
+use super::schema::SetHouseholdRegionRequest;
+use super::schema::SimpleHouseholdInvitation;
+use super::schema::UpdateHouseholdRequest;
+use crate::http::handler::account::schema::SimpleAccount;
+use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::account::Account;
+use crate::models::account::AccountUuid;
+use crate::models::households::Household;
+use crate::models::households::HouseholdInvitation;
+use crate::models::households::HouseholdInvitationUuid;
+use crate::modules::websockets::WebsocketManager;
+
+/// Retrieves the household of the current account with its members and open invitations.
+#[get("/current")]
+pub async fn get_household(household: Household) -> ApiResult<ApiJson<FullHousehold>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let mut members = Vec::new();
+    for account_uuid in household.query_members(&mut tx).await? {
+        let Some(account) = Account::query_by_uuid(&mut tx, &account_uuid).await? else {
+            error!(account = ?account_uuid, "Account not found");
+            continue;
+        };
+        members.push(SimpleAccount::from(account));
+    }
+
+    let invitations = HouseholdInvitation::query_by_household(&mut tx, &household.uuid).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiJson(FullHousehold {
+        uuid: household.uuid,
+        name: household.name,
+        region: household.region,
+        members,
+        invitations: invitations
+            .into_iter()
+            .map(SimpleHouseholdInvitation::from)
+            .collect(),
+    }))
+}
+
+/// Renames the household of the current account.
+#[put("/current")]
+pub async fn update_household(
+    household: Household,
+    ApiJson(request): ApiJson<UpdateHouseholdRequest>,
+) -> ApiResult<()> {
+    household.update(Database::global(), request.name).await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::HouseholdChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Sets or clears the region of the household of the current account.
+///
+/// The seasons of ingredients are looked up for this region.
+#[put("/current/region")]
+pub async fn set_household_region(
+    household: Household,
+    ApiJson(request): ApiJson<SetHouseholdRegionRequest>,
+) -> ApiResult<()> {
+    household
+        .set_region(Database::global(), request.region)
+        .await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::HouseholdChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Invites someone to join the household of the current account.
+///
+/// The returned UUID has to be shared with the invited person and can be accepted for a week.
+#[post("/invitations")]
+pub async fn create_invitation(
+    user: Account,
+    household: Household,
+) -> ApiResult<ApiJson<SingleUuid>> {
+    let invitation =
+        HouseholdInvitation::create(Database::global(), &household.uuid, &user.uuid).await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::HouseholdChanged {})
+        .await;
+
+    Ok(ApiJson(SingleUuid {
+        uuid: invitation.uuid.0,
+    }))
+}
+
+/// Revokes an open invitation of the household.
+#[delete("/invitations/{invitation_uuid}")]
+pub async fn delete_invitation(
+    household: Household,
+    Path(invitation_uuid): Path<HouseholdInvitationUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(invitation) = HouseholdInvitation::query_by_uuid(&mut tx, &invitation_uuid)
+        .await?
+        .filter(|invitation| invitation.household == household.uuid)
+    else {
+        return Err(ApiError::bad_request("Invalid invitation uuid"));
+    };
+
+    invitation.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::HouseholdChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Accepts an invitation and moves the current account to the inviting household.
+///
+/// The previous household is deleted if the account was its last member.
+/// To prevent losing recipes by accident, this is refused as long as that household owns recipes.
+#[post("/invitations/{invitation_uuid}/accept")]
+pub async fn accept_invitation(
+    user: Account,
+    household: Household,
+    Path(invitation_uuid): Path<HouseholdInvitationUuid>,
+) -> ApiResult<(), AcceptHouseholdInvitationErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(invitation) = HouseholdInvitation::query_by_uuid(&mut tx, &invitation_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid invitation uuid"));
+    };
+
+    if invitation.household == household.uuid {
+        return Err(ApiError::bad_request("Already member of the household"));
+    }
+
+    let Some(new_household) = Household::query_by_uuid(&mut tx, &invitation.household).await?
+    else {
+        return Err(ApiError::bad_request("Invalid invitation uuid"));
+    };
+
+    let mut errors = FormErrors::<AcceptHouseholdInvitationErrors>::new();
+
+    let is_last_member = household.query_members(&mut tx).await?.len() <= 1;
+    if is_last_member && household.has_recipes(&mut tx).await? {
+        errors.household_not_empty = true;
+    }
+
+    errors.check()?;
+
+    new_household.add_member(&mut tx, &user.uuid).await?;
+    invitation.delete(&mut tx).await?;
+
+    let old_household = household.uuid;
+    if is_last_member {
+        household.delete(&mut tx).await?;
+    }
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .change_household(user.uuid, new_household.uuid)
+        .await;
+
+    WebsocketManager::global()
+        .send_to_household(old_household, WsServerMsg::HouseholdChanged {})
+        .await;
+
+    WebsocketManager::global()
+        .send_to_household(new_household.uuid, WsServerMsg::HouseholdChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Removes the current account from its household.
+///
+/// Members can only remove themselves to leave the household.
+/// The removed account gets a new, empty household of its own.
+#[delete("/members/{account_uuid}")]
+pub async fn remove_member(
+    user: Account,
+    household: Household,
+    Path(account_uuid): Path<AccountUuid>,
+) -> ApiResult<()> {
+    if account_uuid != user.uuid {
+        return Err(ApiError::bad_request(
+            "Only the account itself can leave the household",
+        ));
+    }
+
+    let mut tx = Database::global().start_transaction().await?;
+
+    if household.query_members(&mut tx).await?.len() <= 1 {
+        return Err(ApiError::bad_request(
+            "The last member can't leave the household",
+        ));
+    }
+
+    Household::remove_member(&mut tx, &user.uuid).await?;
+    let new_household = Household::create(&mut tx, user.display_name, &user.uuid).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .change_household(user.uuid, new_household.uuid)
+        .await;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::HouseholdChanged {})
+        .await;
+
+    WebsocketManager::global()
+        .send_to_household(new_household.uuid, WsServerMsg::HouseholdChanged {})
+        .await;
+
+    Ok(())
+}
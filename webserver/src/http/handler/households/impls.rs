@@ -0,0 +1,12 @@
+use super::schema::SimpleHouseholdInvitation;
+use crate::models::households::HouseholdInvitation;
+
+impl From<HouseholdInvitation> for SimpleHouseholdInvitation {
+    fn from(value: HouseholdInvitation) -> Self {
+        Self {
+            uuid: value.uuid,
+            invited_by: value.invited_by,
+            expires_at: value.expires_at,
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use galvyn::core::GalvynRouter;
+use galvyn::openapi::OpenapiRouterExt;
+
+mod handler;
+mod impls;
+pub mod schema;
+
+pub fn initialize() -> GalvynRouter {
+    GalvynRouter::new()
+        .openapi_tag("Households")
+        .handler(handler::get_household)
+        .handler(handler::update_household)
+        .handler(handler::set_household_region)
+        .handler(handler::create_invitation)
+        .handler(handler::delete_invitation)
+        .handler(handler::accept_invitation)
+        .handler(handler::remove_member)
+}
@@ -0,0 +1,69 @@
+//! Represents all household responses and requests.
+
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::fields::types::MaxStr;
+use time::OffsetDateTime;
+
+use crate::http::handler::account::schema::SimpleAccount;
+use crate::models::account::AccountUuid;
+use crate::models::households::HouseholdInvitationUuid;
+use crate::models::households::HouseholdUuid;
+
+/// Represents a household with its members and open invitations.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FullHousehold {
+    /// The UUID of the household.
+    pub uuid: HouseholdUuid,
+
+    /// The name of the household.
+    pub name: MaxStr<255>,
+
+    /// The region the household lives in.
+    ///
+    /// The seasons of ingredients are looked up for this region.
+    pub region: Option<MaxStr<255>>,
+
+    /// The members of the household.
+    pub members: Vec<SimpleAccount>,
+
+    /// The invitations which haven't been accepted or expired yet.
+    pub invitations: Vec<SimpleHouseholdInvitation>,
+}
+
+/// Represents an open invitation to join a household.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleHouseholdInvitation {
+    /// The UUID of the invitation, shared with the invited person.
+    pub uuid: HouseholdInvitationUuid,
+
+    /// The member who created the invitation.
+    pub invited_by: AccountUuid,
+
+    /// The point in time after which the invitation can't be accepted anymore.
+    #[schemars(with = "String")]
+    pub expires_at: OffsetDateTime,
+}
+
+/// Represents the structure for updating a household.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateHouseholdRequest {
+    /// The name of the household.
+    pub name: MaxStr<255>,
+}
+
+/// Represents the structure for setting the region of a household.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetHouseholdRegionRequest {
+    /// The region the household lives in, `None` to clear it.
+    pub region: Option<MaxStr<255>>,
+}
+
+/// Errors for accepting an invitation to a household.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct AcceptHouseholdInvitationErrors {
+    /// The account is the last member of its household which still owns recipes.
+    pub household_not_empty: bool,
+}
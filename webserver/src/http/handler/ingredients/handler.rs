@@ -1,71 +1,120 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 
+use galvyn::core::re_exports::axum::extract::Path;
+use galvyn::core::re_exports::axum::extract::Query;
+use galvyn::core::stuff::api_error::ApiError;
 use galvyn::core::stuff::api_error::ApiResult;
+use galvyn::core::stuff::api_error::FormErrors;
 use galvyn::core::stuff::api_json::ApiJson;
 use galvyn::core::stuff::schema::List;
 use galvyn::core::stuff::schema::Page;
+use galvyn::core::stuff::schema::SingleUuid;
 use galvyn::core::Module;
+use galvyn::delete;
 use galvyn::get;
 use galvyn::post;
+use galvyn::put;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
 use galvyn::rorm::Database;
 
+use super::schema::AutocompleteIngredientsRequest;
+use super::schema::CreateIngredientPriceErrors;
+use super::schema::CreateIngredientPriceRequest;
+use super::schema::CreateIngredientSubstitutionErrors;
+use super::schema::CreateIngredientSubstitutionRequest;
+use super::schema::CreateOrUpdateIngredientCategory;
+use super::schema::CreateOrUpdateIngredientCategoryErrors;
+use super::schema::DeleteIngredientErrors;
+use super::schema::GetAllIngredientsRequest;
 use super::schema::GetAllRecipesByIngredientsRequest;
+use super::schema::MergeIngredientRequest;
+use super::schema::RenameIngredientErrors;
+use super::schema::RenameIngredientRequest;
+use super::schema::SetIngredientAliasesErrors;
+use super::schema::SetIngredientCategoryRequest;
+use super::schema::SetIngredientDietaryRequest;
+use super::schema::SetIngredientFoodRequest;
+use super::schema::SetIngredientMeasuresErrors;
+use super::schema::SetIngredientSeasonErrors;
+use super::schema::SetIngredientSeasonRequest;
 use super::schema::SimpleIngredient;
+use super::schema::SimpleIngredientCategory;
+use super::schema::SimpleIngredientPrice;
+use super::schema::SimpleIngredientSeason;
+use super::schema::SimpleIngredientSubstitution;
+use super::schema::SimpleIngredientSuggestion;
 use crate::http::handler::recipes::schema::SimpleRecipeWithTags;
 use crate::http::handler::tags::schema::SimpleTag;
+use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::foods::Food;
+use crate::models::households::Household;
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredient_allergens::IngredientAllergen;
+use crate::models::ingredient_categories::IngredientCategory;
+use crate::models::ingredient_categories::IngredientCategoryUuid;
+use crate::models::ingredient_prices::IngredientPrice;
+use crate::models::ingredient_prices::IngredientPriceUuid;
+use crate::models::ingredient_seasons::IngredientSeason;
+use crate::models::ingredient_substitutions::IngredientSubstitution;
+use crate::models::ingredient_substitutions::IngredientSubstitutionUuid;
 use crate::models::ingredients::Ingredient;
+use crate::models::ingredients::IngredientMeasures;
+use crate::models::ingredients::IngredientUuid;
 use crate::models::recipe_ingredients::RecipeIngredient;
 use crate::models::recipes::Recipe;
+use crate::models::recipes::RecipeViewer;
 use crate::models::tags::Tag;
+use crate::modules::websockets::WebsocketManager;
 
 /// Retrieves recipes based on specified ingredients.
 #[post("/recipes")]
 pub async fn get_recipes_by_ingredients(
+    viewer: RecipeViewer,
     ApiJson(request): ApiJson<GetAllRecipesByIngredientsRequest>,
 ) -> ApiResult<ApiJson<Page<SimpleRecipeWithTags>>> {
     let GetAllRecipesByIngredientsRequest {
         page,
         filter_name,
         filter_uuids,
+        include_optional,
     } = request;
 
     let mut tx = Database::global().start_transaction().await?;
 
-    let mut recipes =
-        Recipe::query_by_ingredient(&mut tx, &page, filter_name, &filter_uuids.list).await?;
+    // Ingredients which can be replaced by one of the given ones count as available as well
+    let substitutions: Vec<_> =
+        IngredientSubstitution::query_by_household(&mut tx, &viewer.household)
+            .await?
+            .into_iter()
+            .filter(|substitution| filter_uuids.list.contains(&substitution.substitute))
+            .collect();
 
-    let mut recipe_uuids = HashSet::new();
-    recipes.retain(|recipe| {
-        let is_first = !recipe_uuids.contains(&recipe.uuid);
-        recipe_uuids.insert(recipe.uuid);
-        is_first
-    });
+    let (total, recipes) = Recipe::query_by_ingredient(
+        &mut tx,
+        &viewer,
+        &page,
+        filter_name,
+        &filter_uuids.list,
+        &substitutions,
+        include_optional,
+    )
+    .await?;
 
     let mut result = Vec::new();
-
     for recipe in recipes {
-        let ingredients = RecipeIngredient::query_by_recipe(&mut tx, &recipe.uuid).await?;
-
-        if ingredients.iter().all(|ingredient| {
-            !filter_uuids
-                .list
-                .iter()
-                .any(|uuid| uuid.get_inner() == ingredient.ingredients.get_inner())
-        }) {
-            continue;
-        }
-
-        let tags = Tag::query_by_recipe(&mut tx, &recipe.uuid).await?;
+        let tags = Tag::query_by_recipe(&mut tx, &viewer.household, &recipe.uuid).await?;
 
         result.push(SimpleRecipeWithTags {
             uuid: recipe.uuid,
             tags: tags.into_iter().map(SimpleTag::from).collect(),
             name: recipe.name,
             description: recipe.description,
+            dietary: recipe.dietary,
         })
     }
 
-    let total = result.len().try_into().unwrap_or_default();
+    tx.commit().await?;
 
     Ok(ApiJson(Page {
         items: result,
@@ -77,9 +126,771 @@ pub async fn get_recipes_by_ingredients(
 
 /// Retrieves all ingredients.
 #[get("/all")]
-pub async fn get_all_ingredients() -> ApiResult<ApiJson<List<SimpleIngredient>>> {
-    let items = Ingredient::query_all(Database::global()).await?;
-    let items: Vec<_> = items.into_iter().map(SimpleIngredient::from).collect();
+pub async fn get_all_ingredients(
+    household: Household,
+    Query(request): Query<GetAllIngredientsRequest>,
+) -> ApiResult<ApiJson<List<SimpleIngredient>>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let items = match request.filter_category {
+        Some(category) => {
+            Ingredient::query_by_category(&mut tx, &household.uuid, &category).await?
+        }
+        None => Ingredient::query_all(&mut tx, &household.uuid).await?,
+    };
+    let mut allergens = IngredientAllergen::query_all(&mut tx, &household.uuid).await?;
+
+    tx.commit().await?;
+
+    let items: Vec<_> = items
+        .into_iter()
+        .map(|ingredient| {
+            let allergens = allergens.remove(&ingredient.uuid).unwrap_or_default();
+            SimpleIngredient::from((ingredient, allergens))
+        })
+        .collect();
 
     Ok(ApiJson(List { list: items }))
 }
+
+/// Suggests ingredients whose name starts with, contains or resembles the typed text.
+///
+/// The ingredients used by the most recipes are suggested first.
+#[get("/autocomplete")]
+pub async fn autocomplete_ingredients(
+    household: Household,
+    Query(request): Query<AutocompleteIngredientsRequest>,
+) -> ApiResult<ApiJson<List<SimpleIngredientSuggestion>>> {
+    let limit = request.limit.unwrap_or(10).clamp(1, 50);
+
+    let suggestions =
+        Ingredient::autocomplete(Database::global(), &household.uuid, &request.query, limit)
+            .await?;
+
+    Ok(ApiJson(List {
+        list: suggestions
+            .into_iter()
+            .map(SimpleIngredientSuggestion::from)
+            .collect(),
+    }))
+}
+
+/// Lists the default ingredient categories and the ones of the household.
+#[get("/categories")]
+pub async fn get_ingredient_categories(
+    household: Household,
+) -> ApiResult<ApiJson<List<SimpleIngredientCategory>>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let categories = IngredientCategory::query_all(&mut tx, &household.uuid).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiJson(List {
+        list: categories
+            .into_iter()
+            .map(SimpleIngredientCategory::from)
+            .collect(),
+    }))
+}
+
+/// Creates a custom ingredient category.
+#[post("/categories")]
+pub async fn create_ingredient_category(
+    household: Household,
+    ApiJson(request): ApiJson<CreateOrUpdateIngredientCategory>,
+) -> ApiResult<ApiJson<SingleUuid>, CreateOrUpdateIngredientCategoryErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let mut errors = FormErrors::<CreateOrUpdateIngredientCategoryErrors>::new();
+
+    if IngredientCategory::query_by_name(&mut tx, &household.uuid, &request.name)
+        .await?
+        .is_some()
+    {
+        errors.name_already_exists = true;
+    }
+
+    errors.check()?;
+
+    let category =
+        IngredientCategory::create(&mut tx, &household.uuid, request.name, request.position)
+            .await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(ApiJson(SingleUuid {
+        uuid: category.uuid.0,
+    }))
+}
+
+/// Renames and moves a custom ingredient category.
+#[put("/categories/{category_uuid}")]
+pub async fn update_ingredient_category(
+    household: Household,
+    Path(category_uuid): Path<IngredientCategoryUuid>,
+    ApiJson(request): ApiJson<CreateOrUpdateIngredientCategory>,
+) -> ApiResult<(), CreateOrUpdateIngredientCategoryErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let category = IngredientCategory::query_by_uuid(&mut tx, &household.uuid, &category_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid category uuid"))?;
+
+    if !category.custom {
+        return Err(ApiError::bad_request("Default categories can't be changed"));
+    }
+
+    let mut errors = FormErrors::<CreateOrUpdateIngredientCategoryErrors>::new();
+
+    if IngredientCategory::query_by_name(&mut tx, &household.uuid, &request.name)
+        .await?
+        .is_some_and(|existing| existing.uuid != category.uuid)
+    {
+        errors.name_already_exists = true;
+    }
+
+    errors.check()?;
+
+    category
+        .update(&mut tx, request.name, request.position)
+        .await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Deletes a custom ingredient category.
+///
+/// Its ingredients become uncategorized.
+#[delete("/categories/{category_uuid}")]
+pub async fn delete_ingredient_category(
+    household: Household,
+    Path(category_uuid): Path<IngredientCategoryUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let category = IngredientCategory::query_by_uuid(&mut tx, &household.uuid, &category_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid category uuid"))?;
+
+    if !category.custom {
+        return Err(ApiError::bad_request("Default categories can't be changed"));
+    }
+
+    category.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Renames an ingredient.
+#[put("/{ingredient_uuid}")]
+pub async fn rename_ingredient(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<RenameIngredientRequest>,
+) -> ApiResult<(), RenameIngredientErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let mut errors = FormErrors::<RenameIngredientErrors>::new();
+
+    if Ingredient::query_by_normalized_name(&mut tx, &household.uuid, &request.name)
+        .await?
+        .is_some_and(|existing| existing != ingredient.uuid)
+    {
+        errors.name_already_exists = true;
+    }
+
+    errors.check()?;
+
+    ingredient.rename(&mut tx, request.name).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Lists the alternative names of an ingredient.
+#[get("/{ingredient_uuid}/aliases")]
+pub async fn get_ingredient_aliases(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+) -> ApiResult<ApiJson<List<MaxStr<255>>>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let list = ingredient.query_aliases(&mut tx).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiJson(List { list }))
+}
+
+/// Replaces the alternative names of an ingredient.
+///
+/// Recipes using one of the aliases as ingredient name are linked to the ingredient.
+#[put("/{ingredient_uuid}/aliases")]
+pub async fn set_ingredient_aliases(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<List<MaxStr<255>>>,
+) -> ApiResult<(), SetIngredientAliasesErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let mut errors = FormErrors::<SetIngredientAliasesErrors>::new();
+
+    for alias in &request.list {
+        if alias.trim().is_empty() {
+            errors.alias_empty = true;
+        } else if Ingredient::query_by_normalized_name(&mut tx, &household.uuid, alias)
+            .await?
+            .is_some_and(|existing| existing != ingredient.uuid)
+        {
+            errors.alias_already_used = true;
+        }
+    }
+
+    errors.check()?;
+
+    ingredient.set_aliases(&mut tx, request.list).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Deletes an ingredient which isn't used by any recipe or stocked in the pantry.
+#[delete("/{ingredient_uuid}")]
+pub async fn delete_ingredient(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+) -> ApiResult<(), DeleteIngredientErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let mut errors = FormErrors::<DeleteIngredientErrors>::new();
+
+    if ingredient.is_used(&mut tx).await? {
+        errors.ingredient_in_use = true;
+    }
+
+    errors.check()?;
+
+    ingredient.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Merges an ingredient into another one, e.g. to get rid of a misspelled duplicate.
+///
+/// Every recipe, pantry item, price and shopping list item using the ingredient
+/// is moved to the kept ingredient before the merged one is deleted.
+#[post("/{ingredient_uuid}/merge")]
+pub async fn merge_ingredient(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<MergeIngredientRequest>,
+) -> ApiResult<()> {
+    if ingredient_uuid == request.into {
+        return Err(ApiError::bad_request(
+            "Can't merge an ingredient into itself",
+        ));
+    }
+
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let target = Ingredient::query_by_uuid(&mut tx, &household.uuid, &request.into)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid target ingredient uuid"))?;
+
+    ingredient.merge_into(&mut tx, &target).await?;
+    tx.commit().await?;
+
+    let websockets = WebsocketManager::global();
+    for message in [
+        WsServerMsg::IngredientsChanged {},
+        WsServerMsg::RecipesChanged {},
+        WsServerMsg::PantryChanged {},
+        WsServerMsg::ShoppingListsChanged {},
+    ] {
+        websockets.send_to_household(household.uuid, message).await;
+    }
+
+    Ok(())
+}
+
+/// Assigns an ingredient to a category.
+#[put("/{ingredient_uuid}/category")]
+pub async fn set_ingredient_category(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<SetIngredientCategoryRequest>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    if let Some(category_uuid) = &request.category
+        && IngredientCategory::query_by_uuid(&mut tx, &household.uuid, category_uuid)
+            .await?
+            .is_none()
+    {
+        return Err(ApiError::bad_request("Invalid category uuid"));
+    }
+
+    ingredient.set_category(&mut tx, request.category).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Links an ingredient to an entry of the food composition database.
+#[put("/{ingredient_uuid}/food")]
+pub async fn set_ingredient_food(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<SetIngredientFoodRequest>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    if let Some(food_uuid) = &request.food
+        && Food::query_by_uuid(&mut tx, food_uuid).await?.is_none()
+    {
+        return Err(ApiError::bad_request("Invalid food uuid"));
+    }
+
+    ingredient.set_food(&mut tx, request.food).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Sets the density and piece weight of an ingredient.
+///
+/// They are used to convert amounts between volume, weight and pieces,
+/// e.g. to calculate the nutrients of "2 cups flour".
+#[put("/{ingredient_uuid}/measures")]
+pub async fn set_ingredient_measures(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<IngredientMeasures>,
+) -> ApiResult<(), SetIngredientMeasuresErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let mut errors = FormErrors::<SetIngredientMeasuresErrors>::new();
+
+    if request.density.is_some_and(|density| density <= 0.0) {
+        errors.density_not_positive = true;
+    }
+
+    if request
+        .piece_weight
+        .is_some_and(|piece_weight| piece_weight <= 0.0)
+    {
+        errors.piece_weight_not_positive = true;
+    }
+
+    errors.check()?;
+
+    ingredient.set_measures(&mut tx, request).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Classifies an ingredient by its dietary properties and allergens.
+///
+/// The dietary flags of all recipes using the ingredient are derived again.
+#[put("/{ingredient_uuid}/dietary")]
+pub async fn set_ingredient_dietary(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<SetIngredientDietaryRequest>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    ingredient
+        .set_dietary(&mut tx, request.meat, request.dairy, request.animal_product)
+        .await?;
+    IngredientAllergen::set_for_ingredient(&mut tx, &ingredient.uuid, &request.allergens).await?;
+
+    for recipe_uuid in Recipe::query_uuids_by_ingredient(&mut tx, &ingredient.uuid).await? {
+        Recipe::refresh_dietary(&mut tx, &recipe_uuid).await?;
+    }
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::RecipesChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Lists the months an ingredient is in season in each region.
+#[get("/{ingredient_uuid}/seasons")]
+pub async fn get_ingredient_seasons(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+) -> ApiResult<ApiJson<List<SimpleIngredientSeason>>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let seasons = IngredientSeason::query_by_ingredient(&mut tx, &ingredient.uuid).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiJson(List {
+        list: seasons
+            .into_iter()
+            .map(SimpleIngredientSeason::from)
+            .collect(),
+    }))
+}
+
+/// Sets the months an ingredient is in season in a region.
+///
+/// The seasonality of recipes using the ingredient changes accordingly.
+#[put("/{ingredient_uuid}/seasons")]
+pub async fn set_ingredient_season(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<SetIngredientSeasonRequest>,
+) -> ApiResult<(), SetIngredientSeasonErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let mut errors = FormErrors::<SetIngredientSeasonErrors>::new();
+
+    if request.region.trim().is_empty() {
+        errors.region_empty = true;
+    }
+
+    if request.months.iter().any(|month| !(1..=12).contains(month)) {
+        errors.month_out_of_range = true;
+    }
+
+    errors.check()?;
+
+    IngredientSeason::set_for_ingredient(
+        &mut tx,
+        &ingredient.uuid,
+        request.region,
+        &request.months,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Lists the recorded prices of an ingredient, most recent first.
+#[get("/{ingredient_uuid}/prices")]
+pub async fn get_ingredient_prices(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+) -> ApiResult<ApiJson<List<SimpleIngredientPrice>>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let prices = IngredientPrice::query_by_ingredient(&mut tx, &ingredient.uuid).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiJson(List {
+        list: prices
+            .into_iter()
+            .map(SimpleIngredientPrice::from)
+            .collect(),
+    }))
+}
+
+/// Records a price an ingredient was bought for.
+#[post("/{ingredient_uuid}/prices")]
+pub async fn create_ingredient_price(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<CreateIngredientPriceRequest>,
+) -> ApiResult<ApiJson<SingleUuid>, CreateIngredientPriceErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let mut errors = FormErrors::<CreateIngredientPriceErrors>::new();
+
+    if request.price < 0 {
+        errors.price_negative = true;
+    }
+
+    if request.amount < 1 {
+        errors.amount_not_positive = true;
+    }
+
+    errors.check()?;
+
+    let price = IngredientPrice::create(
+        &mut tx,
+        &ingredient.uuid,
+        request.price,
+        request.amount,
+        request.unit,
+        request.store,
+        request.date,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(ApiJson(SingleUuid { uuid: price.uuid.0 }))
+}
+
+/// Deletes a recorded price of an ingredient.
+#[delete("/prices/{price_uuid}")]
+pub async fn delete_ingredient_price(
+    household: Household,
+    Path(price_uuid): Path<IngredientPriceUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let price = IngredientPrice::query_by_uuid(&mut tx, &household.uuid, &price_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid price uuid"))?;
+
+    price.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Lists the substitutes of an ingredient, including the ones limited to a recipe.
+#[get("/{ingredient_uuid}/substitutions")]
+pub async fn get_ingredient_substitutions(
+    household: Household,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+) -> ApiResult<ApiJson<List<SimpleIngredientSubstitution>>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let substitutions =
+        IngredientSubstitution::query_by_ingredient(&mut tx, &ingredient.uuid).await?;
+    let substitutions = add_substitute_names(&mut tx, &household.uuid, substitutions).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiJson(List {
+        list: substitutions,
+    }))
+}
+
+/// Records an ingredient which can be used in place of another one.
+///
+/// The substitution applies to all recipes of the household unless it is limited to one.
+#[post("/{ingredient_uuid}/substitutions")]
+pub async fn create_ingredient_substitution(
+    viewer: RecipeViewer,
+    Path(ingredient_uuid): Path<IngredientUuid>,
+    ApiJson(request): ApiJson<CreateIngredientSubstitutionRequest>,
+) -> ApiResult<ApiJson<SingleUuid>, CreateIngredientSubstitutionErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &viewer.household, &ingredient_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let substitute = Ingredient::query_by_uuid(&mut tx, &viewer.household, &request.substitute)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid substitute uuid"))?;
+
+    if let Some(recipe_uuid) = &request.recipe {
+        let recipe = Recipe::query_by_uuid(&mut tx, &viewer, recipe_uuid)
+            .await?
+            .filter(|recipe| recipe.household == viewer.household)
+            .ok_or(ApiError::bad_request("Invalid recipe uuid"))?;
+
+        let uses_ingredient = RecipeIngredient::query_by_recipe(&mut tx, &recipe.uuid)
+            .await?
+            .iter()
+            .any(|recipe_ingredient| recipe_ingredient.ingredients == ingredient.uuid);
+        if !uses_ingredient {
+            return Err(ApiError::bad_request(
+                "The recipe doesn't use the ingredient",
+            ));
+        }
+    }
+
+    let mut errors = FormErrors::<CreateIngredientSubstitutionErrors>::new();
+
+    if substitute.uuid == ingredient.uuid {
+        errors.substitute_is_ingredient = true;
+    }
+
+    if !(request.ratio > 0.0 && request.ratio.is_finite()) {
+        errors.ratio_not_positive = true;
+    }
+
+    errors.check()?;
+
+    let substitution = IngredientSubstitution::create(
+        &mut tx,
+        &ingredient.uuid,
+        &substitute.uuid,
+        request.recipe,
+        request.ratio,
+        request.unit,
+        request.substitute_unit,
+        request.note,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(viewer.household, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(ApiJson(SingleUuid {
+        uuid: substitution.uuid.0,
+    }))
+}
+
+/// Deletes a substitute of an ingredient.
+#[delete("/substitutions/{substitution_uuid}")]
+pub async fn delete_ingredient_substitution(
+    household: Household,
+    Path(substitution_uuid): Path<IngredientSubstitutionUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let substitution =
+        IngredientSubstitution::query_by_uuid(&mut tx, &household.uuid, &substitution_uuid)
+            .await?
+            .ok_or(ApiError::bad_request("Invalid substitution uuid"))?;
+
+    substitution.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Adds the names of their substitutes to substitutions.
+async fn add_substitute_names(
+    exe: impl Executor<'_>,
+    household: &HouseholdUuid,
+    substitutions: Vec<IngredientSubstitution>,
+) -> ApiResult<Vec<SimpleIngredientSubstitution>> {
+    let names: HashMap<_, _> = Ingredient::query_all(exe, household)
+        .await?
+        .into_iter()
+        .map(|ingredient| (ingredient.uuid, ingredient.name))
+        .collect();
+
+    Ok(substitutions
+        .into_iter()
+        .filter_map(|substitution| {
+            let name = names.get(&substitution.substitute)?.clone();
+            Some(SimpleIngredientSubstitution::from((substitution, name)))
+        })
+        .collect())
+}
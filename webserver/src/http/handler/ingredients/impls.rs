@@ -1,12 +1,99 @@
+use galvyn::rorm::fields::types::MaxStr;
+
+use super::schema::IngredientDietary;
 use super::schema::SimpleIngredient;
+use super::schema::SimpleIngredientCategory;
+use super::schema::SimpleIngredientPrice;
+use super::schema::SimpleIngredientSeason;
+use super::schema::SimpleIngredientSubstitution;
+use super::schema::SimpleIngredientSuggestion;
+use crate::models::ingredient_allergens::Allergen;
+use crate::models::ingredient_categories::IngredientCategory;
+use crate::models::ingredient_prices::IngredientPrice;
+use crate::models::ingredient_seasons::IngredientSeason;
+use crate::models::ingredient_substitutions::IngredientSubstitution;
 use crate::models::ingredients::Ingredient;
+use crate::models::ingredients::IngredientSuggestion;
+
+impl From<(Ingredient, Vec<Allergen>)> for SimpleIngredient {
+    /// Creates a new `SimpleIngredient` instance from a given `Ingredient` and its allergens.
+    fn from((value, allergens): (Ingredient, Vec<Allergen>)) -> Self {
+        Self {
+            uuid: value.uuid,
+            name: value.name,
+            food: value.food,
+            category: value.category,
+            measures: value.measures,
+            dietary: IngredientDietary {
+                classified: value.classified,
+                meat: value.meat,
+                dairy: value.dairy,
+                animal_product: value.animal_product,
+                allergens,
+            },
+        }
+    }
+}
+
+impl From<IngredientSuggestion> for SimpleIngredientSuggestion {
+    fn from(value: IngredientSuggestion) -> Self {
+        Self {
+            uuid: value.ingredient.uuid,
+            name: value.ingredient.name,
+            category: value.ingredient.category,
+            usage_count: value.usage_count,
+            common_unit: value.common_unit,
+        }
+    }
+}
+
+impl From<IngredientPrice> for SimpleIngredientPrice {
+    fn from(value: IngredientPrice) -> Self {
+        Self {
+            uuid: value.uuid,
+            price: value.price,
+            amount: value.amount,
+            unit: value.unit,
+            store: value.store,
+            date: value.date,
+        }
+    }
+}
 
-impl From<Ingredient> for SimpleIngredient {
-    /// Creates a new `IngredientSearchResponse` instance from a given `Ingredient` instance.
-    fn from(value: Ingredient) -> Self {
+impl From<IngredientSeason> for SimpleIngredientSeason {
+    fn from(value: IngredientSeason) -> Self {
+        Self {
+            region: value.region,
+            months: value.months,
+        }
+    }
+}
+
+impl From<IngredientCategory> for SimpleIngredientCategory {
+    fn from(value: IngredientCategory) -> Self {
         Self {
             uuid: value.uuid,
             name: value.name,
+            position: value.position,
+            custom: value.custom,
+        }
+    }
+}
+
+impl From<(IngredientSubstitution, MaxStr<255>)> for SimpleIngredientSubstitution {
+    /// Creates a new `SimpleIngredientSubstitution` from a substitution and its substitute's name.
+    fn from((value, substitute_name): (IngredientSubstitution, MaxStr<255>)) -> Self {
+        Self {
+            uuid: value.uuid,
+            ingredient: value.ingredient,
+            substitute: value.substitute,
+            substitute_name,
+            recipe: value.recipe,
+            ratio: value.ratio,
+            unit: value.unit,
+            substitute_unit: value.substitute_unit,
+            note: value.note,
+            common: value.common,
         }
     }
 }
@@ -10,4 +10,26 @@ pub fn initialize() -> GalvynRouter {
         .openapi_tag("Ingredients")
         .handler(handler::get_recipes_by_ingredients)
         .handler(handler::get_all_ingredients)
+        .handler(handler::autocomplete_ingredients)
+        .handler(handler::get_ingredient_categories)
+        .handler(handler::create_ingredient_category)
+        .handler(handler::update_ingredient_category)
+        .handler(handler::delete_ingredient_category)
+        .handler(handler::rename_ingredient)
+        .handler(handler::get_ingredient_aliases)
+        .handler(handler::set_ingredient_aliases)
+        .handler(handler::delete_ingredient)
+        .handler(handler::merge_ingredient)
+        .handler(handler::set_ingredient_category)
+        .handler(handler::set_ingredient_food)
+        .handler(handler::set_ingredient_measures)
+        .handler(handler::set_ingredient_dietary)
+        .handler(handler::get_ingredient_seasons)
+        .handler(handler::set_ingredient_season)
+        .handler(handler::get_ingredient_prices)
+        .handler(handler::create_ingredient_price)
+        .handler(handler::delete_ingredient_price)
+        .handler(handler::get_ingredient_substitutions)
+        .handler(handler::create_ingredient_substitution)
+        .handler(handler::delete_ingredient_substitution)
 }
@@ -7,9 +7,17 @@ use galvyn::core::re_exports::serde::Serialize;
 use galvyn::core::stuff::schema::GetPageRequest;
 use galvyn::core::stuff::schema::List;
 use galvyn::rorm::fields::types::MaxStr;
+use time::Date;
 
+use crate::models::foods::FoodUuid;
+use crate::models::ingredient_allergens::Allergen;
+use crate::models::ingredient_categories::IngredientCategoryUuid;
+use crate::models::ingredient_prices::IngredientPriceUuid;
+use crate::models::ingredient_substitutions::IngredientSubstitutionUuid;
+use crate::models::ingredients::IngredientMeasures;
 use crate::models::ingredients::IngredientUuid;
 use crate::models::ingredients::Units;
+use crate::models::recipes::RecipeUuid;
 
 /// Represents the ingredients for a recipe.
 ///
@@ -33,6 +41,29 @@ pub struct FullIngredient {
 
     /// The quantity of the ingredient.
     pub amount: i64,
+
+    /// How the ingredient is prepared for the recipe, e.g. "finely diced".
+    #[serde(default)]
+    pub preparation: Option<MaxStr<255>>,
+
+    /// Whether the recipe can be made without the ingredient, e.g. a garnish.
+    ///
+    /// Optional ingredients are left out when matching recipes and generating shopping lists.
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// Represents an ingredient of a recipe in a response.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FullRecipeIngredient {
+    /// The ingredient with its amount.
+    #[serde(flatten)]
+    pub ingredient: FullIngredient,
+
+    /// The category the ingredient is grouped by.
+    ///
+    /// Only the default categories and the ones of the viewer's household are given.
+    pub category: Option<IngredientCategoryUuid>,
 }
 
 /// Represents the response received after searching for ingredients.
@@ -42,6 +73,277 @@ pub struct SimpleIngredient {
     pub uuid: IngredientUuid,
     /// The name of the ingredient.
     pub name: MaxStr<255>,
+    /// The linked entry of the food composition database.
+    pub food: Option<FoodUuid>,
+    /// The category the ingredient is grouped by.
+    pub category: Option<IngredientCategoryUuid>,
+    /// The factors to convert amounts between volume, weight and pieces.
+    pub measures: IngredientMeasures,
+    /// The dietary properties and allergens of the ingredient.
+    pub dietary: IngredientDietary,
+}
+
+/// Request to list all ingredients.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetAllIngredientsRequest {
+    /// Only list the ingredients of this category
+    pub filter_category: Option<IngredientCategoryUuid>,
+}
+
+/// Request to suggest ingredients while typing their name.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AutocompleteIngredientsRequest {
+    /// The typed part of the name
+    pub query: String,
+    /// The maximum number of suggestions (defaults to 10, at most 50)
+    pub limit: Option<i64>,
+}
+
+/// An ingredient suggested while typing its name.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleIngredientSuggestion {
+    /// The UUID for the ingredient.
+    pub uuid: IngredientUuid,
+    /// The name of the ingredient.
+    pub name: MaxStr<255>,
+    /// The category the ingredient is grouped by.
+    pub category: Option<IngredientCategoryUuid>,
+    /// The number of recipes using the ingredient.
+    pub usage_count: i64,
+    /// The unit the ingredient is measured in most often, `None` if no recipe uses it.
+    pub common_unit: Option<Units>,
+}
+
+/// A category ingredients are grouped by.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleIngredientCategory {
+    /// The UUID of the category.
+    pub uuid: IngredientCategoryUuid,
+    /// The name of the category.
+    pub name: MaxStr<255>,
+    /// The position of the category when ingredients are grouped, lowest first.
+    pub position: i32,
+    /// Whether the category has been created by the household.
+    ///
+    /// Only custom categories can be changed.
+    pub custom: bool,
+}
+
+/// Request to create or update a custom ingredient category.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateOrUpdateIngredientCategory {
+    /// The name of the category.
+    pub name: MaxStr<255>,
+    /// The position of the category when ingredients are grouped, lowest first.
+    pub position: i32,
+}
+
+/// Errors for creating or updating an ingredient category.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CreateOrUpdateIngredientCategoryErrors {
+    pub name_already_exists: bool,
+}
+
+/// Request to assign an ingredient to a category.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetIngredientCategoryRequest {
+    /// The category, `None` removes the ingredient from its category.
+    pub category: Option<IngredientCategoryUuid>,
+}
+
+/// The dietary properties and allergens of an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IngredientDietary {
+    /// Whether the properties have been specified.
+    ///
+    /// Unclassified ingredients prevent a recipe from being flagged as e.g. vegan.
+    pub classified: bool,
+    /// Whether the ingredient is meat, poultry, fish or seafood.
+    pub meat: bool,
+    /// Whether the ingredient is a dairy product.
+    pub dairy: bool,
+    /// Whether the ingredient is or contains any other animal product (e.g. eggs, honey, gelatine).
+    pub animal_product: bool,
+    /// The allergens contained in the ingredient.
+    pub allergens: Vec<Allergen>,
+}
+
+/// Request to rename an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RenameIngredientRequest {
+    /// The new name of the ingredient.
+    pub name: MaxStr<255>,
+}
+
+/// Errors for renaming an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct RenameIngredientErrors {
+    pub name_already_exists: bool,
+}
+
+/// Errors for setting the aliases of an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct SetIngredientAliasesErrors {
+    pub alias_empty: bool,
+    /// An alias matches the name or an alias of another ingredient.
+    pub alias_already_used: bool,
+}
+
+/// Errors for deleting an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct DeleteIngredientErrors {
+    /// The ingredient is still used by a recipe, stocked in the pantry, on a shopping list,
+    /// part of a substitution or has seasons.
+    pub ingredient_in_use: bool,
+}
+
+/// Request to merge an ingredient into another one.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MergeIngredientRequest {
+    /// The ingredient which is kept.
+    pub into: IngredientUuid,
+}
+
+/// Request to classify an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetIngredientDietaryRequest {
+    /// Whether the ingredient is meat, poultry, fish or seafood.
+    pub meat: bool,
+    /// Whether the ingredient is a dairy product.
+    pub dairy: bool,
+    /// Whether the ingredient is or contains any other animal product (e.g. eggs, honey, gelatine).
+    pub animal_product: bool,
+    /// The allergens contained in the ingredient.
+    pub allergens: Vec<Allergen>,
+}
+
+/// Request to link an ingredient to an entry of the food composition database.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetIngredientFoodRequest {
+    /// The food to link, `None` removes the link.
+    pub food: Option<FoodUuid>,
+}
+
+/// Errors for setting the measures of an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct SetIngredientMeasuresErrors {
+    pub density_not_positive: bool,
+    pub piece_weight_not_positive: bool,
+}
+
+/// The months an ingredient is in season in a region.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleIngredientSeason {
+    /// The region the season applies to.
+    pub region: MaxStr<255>,
+    /// The months from 1 (January) to 12 (December).
+    pub months: Vec<i16>,
+}
+
+/// Request to set the months an ingredient is in season in a region.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetIngredientSeasonRequest {
+    /// The region the season applies to.
+    pub region: MaxStr<255>,
+    /// The months from 1 (January) to 12 (December), none removes the season.
+    pub months: Vec<i16>,
+}
+
+/// Errors for setting the season of an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct SetIngredientSeasonErrors {
+    pub region_empty: bool,
+    pub month_out_of_range: bool,
+}
+
+/// A price an ingredient was bought for.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleIngredientPrice {
+    /// The UUID of the price.
+    pub uuid: IngredientPriceUuid,
+    /// The price in the smallest unit of the currency (e.g. cents).
+    pub price: i64,
+    /// The quantity the price refers to.
+    pub amount: i64,
+    /// The unit of the quantity.
+    pub unit: Units,
+    /// The store the ingredient was bought at.
+    pub store: Option<MaxStr<255>>,
+    /// The day the price was recorded (`YYYY-MM-DD`).
+    #[schemars(with = "String")]
+    pub date: Date,
+}
+
+/// Request to record a price of an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateIngredientPriceRequest {
+    /// The price in the smallest unit of the currency (e.g. cents).
+    pub price: i64,
+    /// The quantity the price refers to.
+    pub amount: i64,
+    /// The unit of the quantity.
+    pub unit: Units,
+    /// The store the ingredient was bought at.
+    pub store: Option<MaxStr<255>>,
+    /// The day the price was recorded (`YYYY-MM-DD`).
+    #[schemars(with = "String")]
+    pub date: Date,
+}
+
+/// Errors for recording a price of an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CreateIngredientPriceErrors {
+    pub price_negative: bool,
+    pub amount_not_positive: bool,
+}
+
+/// An ingredient which can be used in place of another one.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleIngredientSubstitution {
+    /// The UUID of the substitution.
+    pub uuid: IngredientSubstitutionUuid,
+    /// The ingredient which is replaced.
+    pub ingredient: IngredientUuid,
+    /// The ingredient used instead.
+    pub substitute: IngredientUuid,
+    /// The name of the ingredient used instead.
+    pub substitute_name: MaxStr<255>,
+    /// The recipe the substitution is limited to, `None` if it applies to all recipes.
+    pub recipe: Option<RecipeUuid>,
+    /// The quantity of the substitute in `substitute_unit` used per `unit` of the replaced ingredient.
+    pub ratio: f64,
+    /// The unit of the replaced ingredient the ratio refers to.
+    pub unit: Units,
+    /// The unit of the substitute's quantity.
+    pub substitute_unit: Units,
+    /// Additional notes like further ingredients needed (e.g. "plus 3 tbsp water").
+    pub note: Option<MaxStr<255>>,
+    /// Whether this is a well-known substitution which can't be deleted.
+    pub common: bool,
+}
+
+/// Request to record a substitute for an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateIngredientSubstitutionRequest {
+    /// The ingredient used instead.
+    pub substitute: IngredientUuid,
+    /// The recipe to limit the substitution to, `None` to apply it to all recipes.
+    pub recipe: Option<RecipeUuid>,
+    /// The quantity of the substitute in `substitute_unit` used per `unit` of the replaced ingredient.
+    pub ratio: f64,
+    /// The unit of the replaced ingredient the ratio refers to.
+    pub unit: Units,
+    /// The unit of the substitute's quantity.
+    pub substitute_unit: Units,
+    /// Additional notes like further ingredients needed (e.g. "plus 3 tbsp water").
+    pub note: Option<MaxStr<255>>,
+}
+
+/// Errors for recording a substitute for an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CreateIngredientSubstitutionErrors {
+    pub substitute_is_ingredient: bool,
+    pub ratio_not_positive: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -53,4 +355,7 @@ pub struct GetAllRecipesByIngredientsRequest {
     pub filter_name: Option<String>,
     /// List of ingredients to filter for
     pub filter_uuids: List<IngredientUuid>,
+    /// Whether optional ingredients of the recipes are matched as well
+    #[serde(default)]
+    pub include_optional: bool,
 }
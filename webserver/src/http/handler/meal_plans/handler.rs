@@ -0,0 +1,205 @@
+use galvyn::core::re_exports::axum::extract::Path;
+use galvyn::core::stuff::api_error::ApiError;
+use galvyn::core::stuff::api_error::ApiResult;
+use galvyn::core::stuff::api_error::FormErrors;
+use galvyn::core::stuff::api_json::ApiJson;
+use galvyn::core::stuff::schema::List;
+use galvyn::core::stuff::schema::SingleUuid;
+use galvyn::core::Module;
+use galvyn::delete;
+use galvyn::get;
+use galvyn::post;
+use galvyn::put;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::Database;
+
+use super::schema::CreateOrUpdateMealPlanEntry;
+use super::schema::CreateOrUpdateMealPlanEntryErrors;
+use super::schema::GetMealPlanRequest;
+use super::schema::SimpleMealPlanEntry;
+use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::account::Account;
+use crate::models::households::Household;
+use crate::models::meal_plans::MealPlanEntry;
+use crate::models::meal_plans::MealPlanEntryUuid;
+use crate::models::recipes::Recipe;
+use crate::models::recipes::RecipeViewer;
+use crate::modules::websockets::WebsocketManager;
+
+/// Retrieves the meals planned in a range of days.
+#[post("/range")]
+pub async fn get_meal_plan(
+    viewer: RecipeViewer,
+    ApiJson(request): ApiJson<GetMealPlanRequest>,
+) -> ApiResult<ApiJson<List<SimpleMealPlanEntry>>> {
+    let GetMealPlanRequest {
+        from,
+        to,
+        filter_account,
+    } = request;
+
+    if from > to {
+        return Err(ApiError::bad_request("Invalid range"));
+    }
+
+    let mut tx = Database::global().start_transaction().await?;
+
+    let entries =
+        MealPlanEntry::query_range(&mut tx, &viewer.household, from, to, filter_account).await?;
+
+    let mut list = Vec::new();
+    for entry in entries {
+        let recipe = match &entry.recipe {
+            Some(recipe_uuid) => Recipe::query_by_uuid(&mut tx, &viewer, recipe_uuid).await?,
+            None => None,
+        };
+        list.push(SimpleMealPlanEntry::from((entry, recipe)));
+    }
+
+    tx.commit().await?;
+
+    Ok(ApiJson(List { list }))
+}
+
+/// Retrieves a planned meal by its UUID.
+#[get("/{entry_uuid}")]
+pub async fn get_meal_plan_entry(
+    viewer: RecipeViewer,
+    Path(entry_uuid): Path<MealPlanEntryUuid>,
+) -> ApiResult<ApiJson<SimpleMealPlanEntry>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(entry) = MealPlanEntry::query_by_uuid(&mut tx, &viewer.household, &entry_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid meal plan entry uuid"));
+    };
+
+    let recipe = match &entry.recipe {
+        Some(recipe_uuid) => Recipe::query_by_uuid(&mut tx, &viewer, recipe_uuid).await?,
+        None => None,
+    };
+
+    tx.commit().await?;
+
+    Ok(ApiJson(SimpleMealPlanEntry::from((entry, recipe))))
+}
+
+/// Plans a meal.
+#[post("/")]
+pub async fn create_meal_plan_entry(
+    user: Account,
+    viewer: RecipeViewer,
+    ApiJson(request): ApiJson<CreateOrUpdateMealPlanEntry>,
+) -> ApiResult<ApiJson<SingleUuid>, CreateOrUpdateMealPlanEntryErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    validate_request(&mut tx, &viewer, &request).await?;
+
+    let entry = MealPlanEntry::create(
+        &mut tx,
+        request.date,
+        request.slot,
+        request.recipe,
+        request.text,
+        request.servings,
+        user.uuid,
+        &viewer.household,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(viewer.household, WsServerMsg::MealPlanChanged {})
+        .await;
+
+    Ok(ApiJson(SingleUuid { uuid: entry.uuid.0 }))
+}
+
+/// Updates a planned meal.
+#[put("/{entry_uuid}")]
+pub async fn update_meal_plan_entry(
+    viewer: RecipeViewer,
+    Path(entry_uuid): Path<MealPlanEntryUuid>,
+    ApiJson(request): ApiJson<CreateOrUpdateMealPlanEntry>,
+) -> ApiResult<(), CreateOrUpdateMealPlanEntryErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(entry) = MealPlanEntry::query_by_uuid(&mut tx, &viewer.household, &entry_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid meal plan entry uuid"));
+    };
+
+    validate_request(&mut tx, &viewer, &request).await?;
+
+    entry
+        .update(
+            &mut tx,
+            request.date,
+            request.slot,
+            request.recipe,
+            request.text,
+            request.servings,
+        )
+        .await?;
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(viewer.household, WsServerMsg::MealPlanChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Deletes a planned meal.
+#[delete("/{entry_uuid}")]
+pub async fn delete_meal_plan_entry(
+    household: Household,
+    Path(entry_uuid): Path<MealPlanEntryUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(entry) = MealPlanEntry::query_by_uuid(&mut tx, &household.uuid, &entry_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid meal plan entry uuid"));
+    };
+
+    entry.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::MealPlanChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Validates the request to create or update a planned meal.
+async fn validate_request(
+    exe: impl Executor<'_>,
+    viewer: &RecipeViewer,
+    request: &CreateOrUpdateMealPlanEntry,
+) -> ApiResult<(), CreateOrUpdateMealPlanEntryErrors> {
+    let mut errors = FormErrors::<CreateOrUpdateMealPlanEntryErrors>::new();
+
+    if request.recipe.is_none() && request.text.is_none() {
+        errors.recipe_or_text_missing = true;
+    }
+
+    if request.servings < 1 {
+        errors.servings_not_positive = true;
+    }
+
+    errors.check()?;
+
+    if let Some(recipe_uuid) = &request.recipe
+        && Recipe::query_by_uuid(exe, viewer, recipe_uuid)
+            .await?
+            .is_none()
+    {
+        return Err(ApiError::bad_request("Invalid recipe uuid"));
+    }
+
+    Ok(())
+}
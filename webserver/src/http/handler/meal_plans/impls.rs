@@ -0,0 +1,22 @@
+use super::schema::MealPlanRecipe;
+use super::schema::SimpleMealPlanEntry;
+use crate::models::meal_plans::MealPlanEntry;
+use crate::models::recipes::Recipe;
+
+impl From<(MealPlanEntry, Option<Recipe>)> for SimpleMealPlanEntry {
+    /// Creates a new `SimpleMealPlanEntry` from a `MealPlanEntry` and its planned recipe.
+    fn from((value, recipe): (MealPlanEntry, Option<Recipe>)) -> Self {
+        Self {
+            uuid: value.uuid,
+            date: value.date,
+            slot: value.slot,
+            recipe: recipe.map(|recipe| MealPlanRecipe {
+                uuid: recipe.uuid,
+                name: recipe.name,
+            }),
+            text: value.text,
+            servings: value.servings,
+            account: value.account,
+        }
+    }
+}
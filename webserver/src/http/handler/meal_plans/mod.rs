@@ -0,0 +1,16 @@
+use galvyn::core::GalvynRouter;
+use galvyn::openapi::OpenapiRouterExt;
+
+mod handler;
+mod impls;
+pub mod schema;
+
+pub fn initialize() -> GalvynRouter {
+    GalvynRouter::new()
+        .openapi_tag("Meal plans")
+        .handler(handler::get_meal_plan)
+        .handler(handler::get_meal_plan_entry)
+        .handler(handler::create_meal_plan_entry)
+        .handler(handler::update_meal_plan_entry)
+        .handler(handler::delete_meal_plan_entry)
+}
@@ -0,0 +1,93 @@
+//! Represents all meal plan responses and requests.
+
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::fields::types::MaxStr;
+use time::Date;
+
+use crate::models::account::AccountUuid;
+use crate::models::meal_plans::MealPlanEntryUuid;
+use crate::models::meal_plans::MealSlot;
+use crate::models::recipes::RecipeUuid;
+
+/// Represents a planned meal.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleMealPlanEntry {
+    /// The UUID of the entry.
+    pub uuid: MealPlanEntryUuid,
+
+    /// The day the meal is planned for (`YYYY-MM-DD`).
+    #[schemars(with = "String")]
+    pub date: Date,
+
+    /// The meal of the day.
+    pub slot: MealSlot,
+
+    /// The planned recipe.
+    pub recipe: Option<MealPlanRecipe>,
+
+    /// A free text describing the meal if no recipe is planned.
+    pub text: Option<MaxStr<255>>,
+
+    /// The number of servings to cook.
+    pub servings: i16,
+
+    /// The account which planned the meal.
+    pub account: AccountUuid,
+}
+
+/// The recipe planned for a meal.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MealPlanRecipe {
+    /// The UUID of the recipe.
+    pub uuid: RecipeUuid,
+
+    /// The name of the recipe.
+    pub name: MaxStr<255>,
+}
+
+/// Represents the structure for creating or updating a planned meal.
+///
+/// Either `recipe` or `text` has to be set.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateOrUpdateMealPlanEntry {
+    /// The day the meal is planned for (`YYYY-MM-DD`).
+    #[schemars(with = "String")]
+    pub date: Date,
+
+    /// The meal of the day.
+    pub slot: MealSlot,
+
+    /// The planned recipe.
+    pub recipe: Option<RecipeUuid>,
+
+    /// A free text describing the meal if no recipe is planned.
+    pub text: Option<MaxStr<255>>,
+
+    /// The number of servings to cook.
+    pub servings: i16,
+}
+
+/// Errors for creating or updating a planned meal.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CreateOrUpdateMealPlanEntryErrors {
+    pub recipe_or_text_missing: bool,
+    pub servings_not_positive: bool,
+}
+
+/// Request to retrieve the meals planned in a range of days.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetMealPlanRequest {
+    /// The first day of the range (`YYYY-MM-DD`, inclusive).
+    #[schemars(with = "String")]
+    pub from: Date,
+
+    /// The last day of the range (`YYYY-MM-DD`, inclusive).
+    #[schemars(with = "String")]
+    pub to: Date,
+
+    /// Only retrieve the meals planned by this account.
+    pub filter_account: Option<AccountUuid>,
+}
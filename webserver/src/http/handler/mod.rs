@@ -3,9 +3,15 @@ use galvyn::core::GalvynRouter;
 use crate::http::middleware::auth_required_layer::AuthRequiredLayer;
 
 pub mod account;
+pub mod collections;
+pub mod foods;
+pub mod households;
 pub mod ingredients;
+pub mod meal_plans;
 pub mod oidc;
+pub mod pantry;
 pub mod recipes;
+pub mod shopping_lists;
 pub mod tags;
 pub mod websockets;
 
@@ -17,6 +23,12 @@ pub fn initialize() -> GalvynRouter {
         .nest("/account", account::initialize())
         .nest("/tags", tags::initialize())
         .nest("/ingredients", ingredients::initialize())
+        .nest("/foods", foods::initialize())
+        .nest("/meal-plans", meal_plans::initialize())
+        .nest("/shopping-lists", shopping_lists::initialize())
+        .nest("/pantry", pantry::initialize())
+        .nest("/collections", collections::initialize())
+        .nest("/households", households::initialize())
         .nest("/websocket", websockets::initialize());
 
     without_auth.merge(with_auth.wrap(AuthRequiredLayer))
@@ -13,6 +13,7 @@ use tracing::trace;
 
 use crate::http::handler::oidc::schema::FinishOidcLoginRequest;
 use crate::models::account::Account;
+use crate::models::households::Household;
 use crate::modules::oidc::OidcRequestState;
 use crate::modules::oidc::OpenIdConnect;
 
@@ -81,6 +82,7 @@ pub async fn finish_oidc_login(
     } else {
         let account = Account::create(&mut tx, display_name, email).await?;
         Account::create_oidc(&mut tx, account.uuid, issuer, subject).await?;
+        Household::create(&mut tx, account.display_name.clone(), &account.uuid).await?;
         account
     };
 
@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+
+use galvyn::core::re_exports::axum::extract::Path;
+use galvyn::core::stuff::api_error::ApiError;
+use galvyn::core::stuff::api_error::ApiResult;
+use galvyn::core::stuff::api_error::FormErrors;
+use galvyn::core::stuff::api_json::ApiJson;
+use galvyn::core::stuff::schema::List;
+use galvyn::core::stuff::schema::SingleUuid;
+use galvyn::core::Module;
+use galvyn::delete;
+use galvyn::post;
+use galvyn::put;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::Database;
+use time::Duration;
+use time::OffsetDateTime;
+use tracing::error;
+
+use super::schema::AdjustPantryItemRequest;
+use super::schema::CookRecipeFromPantryErrors;
+use super::schema::CookRecipeFromPantryRequest;
+use super::schema::CookRecipeFromPantryResponse;
+use super::schema::CreateOrUpdatePantryItemErrors;
+use super::schema::CreatePantryItemRequest;
+use super::schema::GetExpiringPantryItemsRequest;
+use super::schema::GetPantryRequest;
+use super::schema::MissingStock;
+use super::schema::SimplePantryItem;
+use super::schema::UpdatePantryItemRequest;
+use super::schema::UsedSubstitute;
+use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::households::Household;
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredient_substitutions::IngredientSubstitution;
+use crate::models::ingredients::Ingredient;
+use crate::models::pantry_items::PantryItem;
+use crate::models::pantry_items::PantryItemUuid;
+use crate::models::recipe_cooks::RecipeCook;
+use crate::models::recipe_ingredients::RecipeIngredient;
+use crate::models::recipes::Recipe;
+use crate::models::recipes::RecipeViewer;
+use crate::modules::websockets::WebsocketManager;
+
+/// Retrieves all items in the pantry, earliest best-before date first.
+#[post("/all")]
+pub async fn get_pantry(
+    household: Household,
+    ApiJson(request): ApiJson<GetPantryRequest>,
+) -> ApiResult<ApiJson<List<SimplePantryItem>>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let items = PantryItem::query_all(&mut tx, &household.uuid, request.filter_location).await?;
+    let list = add_ingredient_names(&mut tx, &household.uuid, items).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiJson(List { list }))
+}
+
+/// Retrieves all items which expire soon or have already expired, earliest first.
+#[post("/expiring")]
+pub async fn get_expiring_pantry_items(
+    household: Household,
+    ApiJson(request): ApiJson<GetExpiringPantryItemsRequest>,
+) -> ApiResult<ApiJson<List<SimplePantryItem>>> {
+    let until = OffsetDateTime::now_utc().date() + Duration::days(i64::from(request.days));
+
+    let mut tx = Database::global().start_transaction().await?;
+
+    let items = PantryItem::query_expiring(&mut tx, &household.uuid, until).await?;
+    let list = add_ingredient_names(&mut tx, &household.uuid, items).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiJson(List { list }))
+}
+
+/// Adds a stock of an ingredient to the pantry.
+#[post("/")]
+pub async fn create_pantry_item(
+    household: Household,
+    ApiJson(request): ApiJson<CreatePantryItemRequest>,
+) -> ApiResult<ApiJson<SingleUuid>, CreateOrUpdatePantryItemErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let ingredient = Ingredient::query_by_uuid(&mut tx, &household.uuid, &request.ingredient)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?;
+
+    let mut errors = FormErrors::<CreateOrUpdatePantryItemErrors>::new();
+
+    if request.amount <= 0.0 {
+        errors.amount_not_positive = true;
+    }
+
+    errors.check()?;
+
+    let item = PantryItem::create(
+        &mut tx,
+        &ingredient.uuid,
+        request.amount,
+        request.unit,
+        request.location,
+        request.best_before,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::PantryChanged {})
+        .await;
+
+    Ok(ApiJson(SingleUuid { uuid: item.uuid.0 }))
+}
+
+/// Updates a stock of an ingredient.
+#[put("/{item_uuid}")]
+pub async fn update_pantry_item(
+    household: Household,
+    Path(item_uuid): Path<PantryItemUuid>,
+    ApiJson(request): ApiJson<UpdatePantryItemRequest>,
+) -> ApiResult<(), CreateOrUpdatePantryItemErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(item) = PantryItem::query_by_uuid(&mut tx, &household.uuid, &item_uuid).await? else {
+        return Err(ApiError::bad_request("Invalid pantry item uuid"));
+    };
+
+    let mut errors = FormErrors::<CreateOrUpdatePantryItemErrors>::new();
+
+    if request.amount <= 0.0 {
+        errors.amount_not_positive = true;
+    }
+
+    errors.check()?;
+
+    item.update(
+        &mut tx,
+        request.amount,
+        request.unit,
+        request.location,
+        request.best_before,
+    )
+    .await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::PantryChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Adds to or removes from a stock of an ingredient.
+///
+/// The item is deleted once it is used up.
+#[post("/{item_uuid}/adjust")]
+pub async fn adjust_pantry_item(
+    household: Household,
+    Path(item_uuid): Path<PantryItemUuid>,
+    ApiJson(request): ApiJson<AdjustPantryItemRequest>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(item) = PantryItem::query_by_uuid(&mut tx, &household.uuid, &item_uuid).await? else {
+        return Err(ApiError::bad_request("Invalid pantry item uuid"));
+    };
+
+    let measures = Ingredient::query_by_uuid(&mut tx, &household.uuid, &item.ingredient)
+        .await?
+        .map(|ingredient| ingredient.measures)
+        .unwrap_or_default();
+
+    if item
+        .adjust(&mut tx, request.delta, request.unit, measures)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::bad_request("Unit is not convertible"));
+    }
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::PantryChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Removes a stock of an ingredient from the pantry.
+#[delete("/{item_uuid}")]
+pub async fn delete_pantry_item(
+    household: Household,
+    Path(item_uuid): Path<PantryItemUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(item) = PantryItem::query_by_uuid(&mut tx, &household.uuid, &item_uuid).await? else {
+        return Err(ApiError::bad_request("Invalid pantry item uuid"));
+    };
+
+    item.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::PantryChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Removes the ingredients of a cooked recipe from the pantry.
+///
+/// The stocks with the earliest best-before date are used first.
+/// Substitutes in stock are used for ingredients which ran out.
+/// Quantities which weren't in stock are reported back.
+#[post("/cook")]
+pub async fn cook_recipe_from_pantry(
+    viewer: RecipeViewer,
+    ApiJson(request): ApiJson<CookRecipeFromPantryRequest>,
+) -> ApiResult<ApiJson<CookRecipeFromPantryResponse>, CookRecipeFromPantryErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(recipe) = Recipe::query_by_uuid(&mut tx, &viewer, &request.recipe).await? else {
+        return Err(ApiError::bad_request("Invalid recipe uuid"));
+    };
+
+    let mut errors = FormErrors::<CookRecipeFromPantryErrors>::new();
+
+    if request.servings.is_some_and(|servings| servings < 1) {
+        errors.servings_not_positive = true;
+    }
+
+    errors.check()?;
+
+    let factor = request
+        .servings
+        .map(|servings| f64::from(servings) / f64::from(recipe.servings.max(1)))
+        .unwrap_or(1.0);
+
+    let substitutions =
+        IngredientSubstitution::query_by_recipe(&mut tx, &viewer.household, &recipe.uuid).await?;
+
+    let mut missing = Vec::new();
+    let mut substituted = Vec::new();
+    for recipe_ingredient in RecipeIngredient::query_by_recipe(&mut tx, &recipe.uuid).await? {
+        let Some(ingredient) =
+            Ingredient::query_by_uuid(&mut tx, &recipe.household, &recipe_ingredient.ingredients)
+                .await?
+        else {
+            error!(recipe_ingredient = ?recipe_ingredient.ingredients, "Ingredient not found");
+            continue;
+        };
+
+        let amount = recipe_ingredient.amount as f64 * factor;
+
+        // Recipes of other households use our ingredient of the same name.
+        // Without one, it can't be in stock.
+        let ingredient_uuid = if recipe.household == viewer.household {
+            Some(ingredient.uuid)
+        } else {
+            Ingredient::query_by_normalized_name(&mut tx, &viewer.household, &ingredient.name)
+                .await?
+        };
+        let Some(ingredient_uuid) = ingredient_uuid else {
+            if !recipe_ingredient.optional {
+                missing.push(MissingStock {
+                    ingredient: None,
+                    name: ingredient.name,
+                    amount,
+                    unit: recipe_ingredient.unit,
+                });
+            }
+            continue;
+        };
+
+        let mut remaining = PantryItem::consume(
+            &mut tx,
+            &ingredient_uuid,
+            amount,
+            recipe_ingredient.unit,
+            ingredient.measures,
+        )
+        .await?;
+
+        // Cover the rest with substitutes which are in stock
+        for substitution in substitutions
+            .iter()
+            .filter(|substitution| substitution.ingredient == ingredient_uuid)
+        {
+            if remaining <= 0.0 {
+                break;
+            }
+
+            let Some(substitute) =
+                Ingredient::query_by_uuid(&mut tx, &viewer.household, &substitution.substitute)
+                    .await?
+            else {
+                continue;
+            };
+
+            // The ratio refers to the substitution's unit
+            let Some(replaced) =
+                recipe_ingredient
+                    .unit
+                    .convert(remaining, substitution.unit, ingredient.measures)
+            else {
+                continue;
+            };
+
+            let amount = replaced * substitution.ratio;
+            let left = PantryItem::consume(
+                &mut tx,
+                &substitute.uuid,
+                amount,
+                substitution.substitute_unit,
+                substitute.measures,
+            )
+            .await?;
+
+            if left < amount {
+                substituted.push(UsedSubstitute {
+                    ingredient: ingredient_uuid,
+                    name: ingredient.name.clone(),
+                    substitute: substitute.uuid,
+                    substitute_name: substitute.name,
+                    amount: amount - left,
+                    unit: substitution.substitute_unit,
+                    note: substitution.note.clone(),
+                });
+                remaining = substitution
+                    .unit
+                    .convert(
+                        left / substitution.ratio,
+                        recipe_ingredient.unit,
+                        ingredient.measures,
+                    )
+                    .unwrap_or(remaining);
+            }
+        }
+
+        // The recipe can be cooked without optional ingredients which are out of stock
+        if remaining > 0.0 && !recipe_ingredient.optional {
+            missing.push(MissingStock {
+                ingredient: Some(ingredient_uuid),
+                name: ingredient.name,
+                amount: remaining,
+                unit: recipe_ingredient.unit,
+            });
+        }
+    }
+
+    RecipeCook::create(&mut tx, &recipe.uuid, viewer.account, &viewer.household).await?;
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(viewer.household, WsServerMsg::PantryChanged {})
+        .await;
+
+    Ok(ApiJson(CookRecipeFromPantryResponse {
+        missing,
+        substituted,
+    }))
+}
+
+/// Adds the names of their ingredients to pantry items.
+async fn add_ingredient_names(
+    exe: impl Executor<'_>,
+    household: &HouseholdUuid,
+    items: Vec<PantryItem>,
+) -> ApiResult<Vec<SimplePantryItem>> {
+    let names: HashMap<_, _> = Ingredient::query_all(exe, household)
+        .await?
+        .into_iter()
+        .map(|ingredient| (ingredient.uuid, ingredient.name))
+        .collect();
+
+    Ok(items
+        .into_iter()
+        .filter_map(|item| {
+            let name = names.get(&item.ingredient)?.clone();
+            Some(SimplePantryItem::from((item, name)))
+        })
+        .collect())
+}
@@ -0,0 +1,19 @@
+use galvyn::rorm::fields::types::MaxStr;
+
+use super::schema::SimplePantryItem;
+use crate::models::pantry_items::PantryItem;
+
+impl From<(PantryItem, MaxStr<255>)> for SimplePantryItem {
+    /// Creates a new `SimplePantryItem` from a `PantryItem` and the name of its ingredient.
+    fn from((value, name): (PantryItem, MaxStr<255>)) -> Self {
+        Self {
+            uuid: value.uuid,
+            ingredient: value.ingredient,
+            name,
+            amount: value.amount,
+            unit: value.unit,
+            location: value.location,
+            best_before: value.best_before,
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use galvyn::core::GalvynRouter;
+use galvyn::openapi::OpenapiRouterExt;
+
+mod handler;
+mod impls;
+pub mod schema;
+
+pub fn initialize() -> GalvynRouter {
+    GalvynRouter::new()
+        .openapi_tag("Pantry")
+        .handler(handler::get_pantry)
+        .handler(handler::get_expiring_pantry_items)
+        .handler(handler::create_pantry_item)
+        .handler(handler::update_pantry_item)
+        .handler(handler::adjust_pantry_item)
+        .handler(handler::delete_pantry_item)
+        .handler(handler::cook_recipe_from_pantry)
+}
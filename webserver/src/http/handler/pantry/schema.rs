@@ -0,0 +1,182 @@
+//! Represents all pantry responses and requests.
+
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::fields::types::MaxStr;
+use time::Date;
+
+use crate::models::ingredients::IngredientUuid;
+use crate::models::ingredients::Units;
+use crate::models::pantry_items::PantryItemUuid;
+use crate::models::pantry_items::PantryLocation;
+use crate::models::recipes::RecipeUuid;
+
+/// Represents a stock of an ingredient in the pantry.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimplePantryItem {
+    /// The UUID of the item.
+    pub uuid: PantryItemUuid,
+
+    /// The stored ingredient.
+    pub ingredient: IngredientUuid,
+
+    /// The name of the stored ingredient.
+    pub name: MaxStr<255>,
+
+    /// The stored quantity.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// Where the ingredient is stored.
+    pub location: PantryLocation,
+
+    /// The best-before date of the ingredient (`YYYY-MM-DD`).
+    #[schemars(with = "Option<String>")]
+    pub best_before: Option<Date>,
+}
+
+/// Request to retrieve the pantry.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetPantryRequest {
+    /// Only retrieve the items stored at this location.
+    pub filter_location: Option<PantryLocation>,
+}
+
+/// Request to retrieve the items which expire soon.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetExpiringPantryItemsRequest {
+    /// The number of days from today in which the items expire.
+    ///
+    /// Items which have already expired are included as well.
+    pub days: u16,
+}
+
+/// Request to add a stock of an ingredient to the pantry.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreatePantryItemRequest {
+    /// The stored ingredient.
+    pub ingredient: IngredientUuid,
+
+    /// The stored quantity.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// Where the ingredient is stored.
+    pub location: PantryLocation,
+
+    /// The best-before date of the ingredient (`YYYY-MM-DD`).
+    #[schemars(with = "Option<String>")]
+    pub best_before: Option<Date>,
+}
+
+/// Request to update a stock of an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdatePantryItemRequest {
+    /// The stored quantity.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// Where the ingredient is stored.
+    pub location: PantryLocation,
+
+    /// The best-before date of the ingredient (`YYYY-MM-DD`).
+    #[schemars(with = "Option<String>")]
+    pub best_before: Option<Date>,
+}
+
+/// Errors for creating or updating a stock of an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CreateOrUpdatePantryItemErrors {
+    pub amount_not_positive: bool,
+}
+
+/// Request to add to or remove from a stock of an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdjustPantryItemRequest {
+    /// The quantity to add, negative values remove from the stock.
+    ///
+    /// The item is deleted once it is used up.
+    pub delta: f64,
+
+    /// The unit of the quantity, must be convertible to the unit of the item.
+    pub unit: Units,
+}
+
+/// Request to remove the ingredients of a cooked recipe from the pantry.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CookRecipeFromPantryRequest {
+    /// The cooked recipe.
+    pub recipe: RecipeUuid,
+
+    /// The number of servings cooked.
+    ///
+    /// Defaults to the servings of the recipe.
+    pub servings: Option<i16>,
+}
+
+/// Errors for removing the ingredients of a cooked recipe from the pantry.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CookRecipeFromPantryErrors {
+    pub servings_not_positive: bool,
+}
+
+/// The result of removing the ingredients of a cooked recipe from the pantry.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CookRecipeFromPantryResponse {
+    /// The quantities which weren't in stock.
+    pub missing: Vec<MissingStock>,
+
+    /// The substitutes used for ingredients which weren't in stock.
+    pub substituted: Vec<UsedSubstitute>,
+}
+
+/// A quantity of an ingredient which wasn't in stock.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MissingStock {
+    /// The identifier of the ingredient.
+    ///
+    /// `None` if the ingredient is only known to the household owning the recipe.
+    pub ingredient: Option<IngredientUuid>,
+
+    /// The name of the ingredient.
+    pub name: MaxStr<255>,
+
+    /// The missing quantity.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+}
+
+/// A substitute used in place of an ingredient which wasn't in stock.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UsedSubstitute {
+    /// The identifier of the replaced ingredient.
+    pub ingredient: IngredientUuid,
+
+    /// The name of the replaced ingredient.
+    pub name: MaxStr<255>,
+
+    /// The identifier of the substitute.
+    pub substitute: IngredientUuid,
+
+    /// The name of the substitute.
+    pub substitute_name: MaxStr<255>,
+
+    /// The quantity of the substitute removed from the pantry.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// Additional notes like further ingredients needed (e.g. "plus 3 tbsp water").
+    pub note: Option<MaxStr<255>>,
+}
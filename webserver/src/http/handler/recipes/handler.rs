@@ -1,8 +1,12 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
 use galvyn::core::re_exports::axum::extract::Path;
 use galvyn::core::stuff::api_error::ApiError;
 use galvyn::core::stuff::api_error::ApiResult;
 use galvyn::core::stuff::api_error::FormErrors;
 use galvyn::core::stuff::api_json::ApiJson;
+use galvyn::core::stuff::schema::List;
 use galvyn::core::stuff::schema::Page;
 use galvyn::core::stuff::schema::SingleUuid;
 use galvyn::core::Module;
@@ -10,49 +14,145 @@ use galvyn::delete;
 use galvyn::get;
 use galvyn::post;
 use galvyn::put;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
 use galvyn::rorm::Database;
 use tracing::error;
+use url::Url;
+use uuid::Uuid;
 
 use super::schema::CreateOrUpdateRecipe;
 use super::schema::CreateOrUpdateRecipeErrors;
 use super::schema::GetAllRecipesRequest;
+use super::schema::MissingCost;
+use super::schema::MissingCostReason;
+use super::schema::MissingNutrition;
+use super::schema::MissingNutritionReason;
+use super::schema::RecipeCost;
+use super::schema::RecipeNutrition;
+use super::schema::RecipesBySource;
+use super::schema::RecommendedRecipe;
+use super::schema::SetRecipeFavoriteRequest;
+use super::schema::SetRecipeNoteRequest;
+use super::schema::SetRecipeRatingErrors;
+use super::schema::SetRecipeRatingRequest;
+use super::schema::SourcedRecipe;
+use super::schema::SurpriseMeErrors;
+use super::schema::SurpriseMeRequest;
+use super::schema::SurpriseMeResponse;
 use crate::http::handler::account::schema::SimpleAccount;
+use crate::http::handler::collections::schema::SimpleCollection;
 use crate::http::handler::ingredients::schema::FullIngredient;
+use crate::http::handler::ingredients::schema::FullRecipeIngredient;
+use crate::http::handler::ingredients::schema::SimpleIngredientCategory;
+use crate::http::handler::ingredients::schema::SimpleIngredientSubstitution;
 use crate::http::handler::recipes::schema::FullRecipe;
 use crate::http::handler::recipes::schema::SimpleRecipeWithTags;
 use crate::http::handler::recipes::schema::Step;
 use crate::http::handler::tags::schema::SimpleTag;
 use crate::http::handler::websockets::schema::WsServerMsg;
 use crate::models::account::Account;
+use crate::models::account::AccountUuid;
+use crate::models::collections::Collection;
+use crate::models::foods::Food;
+use crate::models::foods::Nutrients;
+use crate::models::households::Household;
+use crate::models::ingredient_allergens::IngredientAllergen;
+use crate::models::ingredient_categories::IngredientCategory;
+use crate::models::ingredient_prices::IngredientPrice;
+use crate::models::ingredient_seasons::current_month;
+use crate::models::ingredient_seasons::IngredientSeason;
+use crate::models::ingredient_substitutions::IngredientSubstitution;
 use crate::models::ingredients::Ingredient;
+use crate::models::jobs::Job;
+use crate::models::jobs::JobKind;
+use crate::models::recipe_cooks::RecipeCook;
 use crate::models::recipe_ingredients::RecipeIngredient;
+use crate::models::recipe_notes::RecipeNote;
+use crate::models::recipe_ratings::RecipeRating;
+use crate::models::recipe_ratings::MAX_RATING;
+use crate::models::recipe_ratings::MIN_RATING;
 use crate::models::recipe_steps::RecipeStep;
+use crate::models::recipes::RandomRecipeFilter;
 use crate::models::recipes::Recipe;
+use crate::models::recipes::RecipeSource;
+use crate::models::recipes::RecipeSourceKind;
 use crate::models::recipes::RecipeUuid;
+use crate::models::recipes::RecipeViewer;
+use crate::models::recipes::RecipeVisibility;
+use crate::models::recommendations::Recommendation;
 use crate::models::tags::Tag;
+use crate::modules::jobs::JobQueue;
 use crate::modules::websockets::WebsocketManager;
+use crate::utils::markdown;
 
 /// Retrieves all recipes with pagination support and associated tags.
 #[post("/all")]
 pub async fn get_all_recipes(
+    viewer: RecipeViewer,
     ApiJson(pagination): ApiJson<GetAllRecipesRequest>,
 ) -> ApiResult<ApiJson<Page<SimpleRecipeWithTags>>> {
-    let GetAllRecipesRequest { page, filter_name } = pagination;
+    let GetAllRecipesRequest {
+        page,
+        filter_name,
+        filter_diet,
+        filter_in_season,
+        sort_by_season,
+    } = pagination;
 
     let mut tx = Database::global().start_transaction().await?;
 
-    let recipes = Recipe::query_all(&mut tx, &page, filter_name).await?;
-    let total = Recipe::query_total(&mut tx).await?;
+    let (recipes, total) = if filter_in_season || sort_by_season {
+        let Some(region) = query_region(&mut tx, &viewer).await? else {
+            return Err(ApiError::bad_request("The household has no region"));
+        };
+        let scores =
+            IngredientSeason::query_recipe_scores(&mut tx, &region, current_month(), None).await?;
+
+        let mut uuids =
+            Recipe::query_all_uuids(&mut tx, &viewer, filter_name, &filter_diet).await?;
+
+        if filter_in_season {
+            // Recipes without seasonal ingredients can be cooked all year
+            uuids.retain(|uuid| scores.get(uuid).is_none_or(|score| *score >= 1.0));
+        }
+
+        if sort_by_season {
+            // The sort is stable, so recipes with the same score stay ordered by name.
+            // Recipes without seasonal ingredients come last.
+            uuids.sort_by(|a, b| {
+                scores
+                    .get(b)
+                    .partial_cmp(&scores.get(a))
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        let total = uuids.len().try_into().unwrap_or_default();
+        let uuids: Vec<_> = uuids
+            .into_iter()
+            .skip(page.offset as usize)
+            .take(page.limit as usize)
+            .collect();
+        let recipes = Recipe::query_by_uuids(&mut tx, &viewer, &uuids).await?;
+        (recipes, total)
+    } else {
+        let total =
+            Recipe::query_total(&mut tx, &viewer, filter_name.as_deref(), &filter_diet).await?;
+        let recipes = Recipe::query_all(&mut tx, &viewer, &page, filter_name, &filter_diet).await?;
+        (recipes, total)
+    };
 
     let mut result = Vec::new();
     for recipe in recipes {
-        let tags = Tag::query_by_recipe(&mut tx, &recipe.uuid).await?;
+        let tags = Tag::query_by_recipe(&mut tx, &viewer.household, &recipe.uuid).await?;
 
         result.push(SimpleRecipeWithTags {
             uuid: recipe.uuid,
             name: recipe.name,
             description: recipe.description,
             tags: tags.into_iter().map(SimpleTag::from).collect(),
+            dietary: recipe.dietary,
         })
     }
 
@@ -66,75 +166,408 @@ pub async fn get_all_recipes(
     }))
 }
 
+/// The maximum number of recipes which can be picked at once.
+const SURPRISE_ME_MAX_COUNT: i64 = 50;
+
+/// The number of recipes recommended at once.
+const RECOMMENDATIONS_COUNT: usize = 20;
+
+/// Picks random recipes matching some constraints.
+///
+/// The same seed picks the same recipes as long as they don't change.
+#[post("/surprise")]
+pub async fn surprise_me(
+    viewer: RecipeViewer,
+    ApiJson(request): ApiJson<SurpriseMeRequest>,
+) -> ApiResult<ApiJson<SurpriseMeResponse>, SurpriseMeErrors> {
+    let mut errors = FormErrors::<SurpriseMeErrors>::new();
+
+    if !(1..=SURPRISE_ME_MAX_COUNT).contains(&request.count) {
+        errors.count_out_of_range = true;
+    }
+
+    if request.max_time_minutes.is_some_and(|time| time < 1) {
+        errors.max_time_not_positive = true;
+    }
+
+    if request.not_cooked_within_days.is_some_and(|days| days < 1) {
+        errors.days_not_positive = true;
+    }
+
+    errors.check()?;
+
+    let seed = request
+        .seed
+        .unwrap_or_else(|| Uuid::new_v4().as_u64_pair().0 as i64);
+
+    let filter = RandomRecipeFilter {
+        tags: request.filter_tags,
+        max_time_minutes: request.max_time_minutes,
+        excluded_ingredients: request.exclude_ingredients,
+        not_cooked_within_days: request.not_cooked_within_days,
+    };
+
+    let mut tx = Database::global().start_transaction().await?;
+
+    let mut recipes = Vec::new();
+    for recipe in Recipe::query_random(&mut tx, &viewer, &filter, seed, request.count).await? {
+        let tags = Tag::query_by_recipe(&mut tx, &viewer.household, &recipe.uuid).await?;
+
+        recipes.push(SimpleRecipeWithTags {
+            uuid: recipe.uuid,
+            name: recipe.name,
+            description: recipe.description,
+            tags: tags.into_iter().map(SimpleTag::from).collect(),
+            dietary: recipe.dietary,
+        })
+    }
+
+    tx.commit().await?;
+
+    Ok(ApiJson(SurpriseMeResponse { seed, recipes }))
+}
+
+/// Recommends recipes based on the favorites, ratings and cooking history
+/// of the logged-in account.
+///
+/// Each recommendation explains why it has been made.
+#[get("/recommendations")]
+pub async fn get_recommendations(
+    viewer: RecipeViewer,
+) -> ApiResult<ApiJson<List<RecommendedRecipe>>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let mut list = Vec::new();
+    for recommendation in Recommendation::query(&mut tx, &viewer, RECOMMENDATIONS_COUNT).await? {
+        let recipe = recommendation.recipe;
+        let tags = Tag::query_by_recipe(&mut tx, &viewer.household, &recipe.uuid).await?;
+
+        list.push(RecommendedRecipe {
+            recipe: SimpleRecipeWithTags {
+                uuid: recipe.uuid,
+                name: recipe.name,
+                description: recipe.description,
+                tags: tags.into_iter().map(SimpleTag::from).collect(),
+                dietary: recipe.dietary,
+            },
+            score: recommendation.score,
+            reasons: recommendation.reasons,
+        })
+    }
+
+    tx.commit().await?;
+
+    Ok(ApiJson(List { list }))
+}
+
 /// Retrieves a recipe by its UUID.
 #[get("/{recipe_uuid}")]
-pub async fn get_recipe(Path(recipe_uuid): Path<RecipeUuid>) -> ApiResult<ApiJson<FullRecipe>> {
+pub async fn get_recipe(
+    viewer: RecipeViewer,
+    Path(recipe_uuid): Path<RecipeUuid>,
+) -> ApiResult<ApiJson<FullRecipe>> {
     let mut tx = Database::global().start_transaction().await?;
 
-    let Some(recipe) = Recipe::query_by_uuid(&mut tx, &recipe_uuid).await? else {
+    let Some(recipe) = Recipe::query_by_uuid(&mut tx, &viewer, &recipe_uuid).await? else {
         return Err(ApiError::bad_request("Recipe not found"));
     };
 
     let recipe_ingredients = RecipeIngredient::query_by_recipe(&mut tx, &recipe.uuid).await?;
 
+    // Custom categories of other households are not shown
+    let available_categories = IngredientCategory::query_all(&mut tx, &viewer.household).await?;
+
+    let substitutions =
+        IngredientSubstitution::query_by_recipe(&mut tx, &recipe.household, &recipe.uuid).await?;
+
+    let ingredient_uuids: Vec<_> = recipe_ingredients
+        .iter()
+        .map(|recipe_ingredient| recipe_ingredient.ingredients)
+        .chain(
+            substitutions
+                .iter()
+                .map(|substitution| substitution.substitute),
+        )
+        .collect();
+    let ingredients =
+        Ingredient::query_by_uuids(&mut tx, &recipe.household, &ingredient_uuids).await?;
+    let ingredient_allergens =
+        IngredientAllergen::query_by_ingredients(&mut tx, &ingredient_uuids).await?;
+    let ingredient_prices =
+        IngredientPrice::query_by_ingredients(&mut tx, &ingredient_uuids).await?;
+    let food_uuids: Vec<_> = ingredients
+        .values()
+        .filter_map(|ingredient| ingredient.food)
+        .collect();
+    let foods = Food::query_by_uuids(&mut tx, &food_uuids).await?;
+
+    let substitutions = substitutions
+        .into_iter()
+        .filter_map(|substitution| {
+            if !recipe_ingredients
+                .iter()
+                .any(|recipe_ingredient| recipe_ingredient.ingredients == substitution.ingredient)
+            {
+                return None;
+            }
+
+            let Some(substitute) = ingredients.get(&substitution.substitute) else {
+                error!(substitute = ?substitution.substitute, "Ingredient not found");
+                return None;
+            };
+
+            let name = substitute.name.clone();
+            Some(SimpleIngredientSubstitution::from((substitution, name)))
+        })
+        .collect();
+
     let mut full_ingredients = Vec::new();
+    let mut total_nutrients = Nutrients::ZERO;
+    let mut missing_nutrition = Vec::new();
+    let mut total_cost = 0.0;
+    let mut missing_cost = Vec::new();
+    let mut allergens = Vec::new();
     for recipe_ingredient in recipe_ingredients {
-        let Some(ingredient) =
-            Ingredient::query_by_uuid(&mut tx, &recipe_ingredient.ingredients).await?
-        else {
+        let Some(ingredient) = ingredients.get(&recipe_ingredient.ingredients).cloned() else {
             error!(recipe_ingredient = ?recipe_ingredient.ingredients, "Ingredient not found");
             continue;
         };
-        full_ingredients.push(FullIngredient {
-            uuid: Some(recipe_ingredient.ingredients),
-            name: ingredient.name,
-            amount: recipe_ingredient.amount,
-            unit: recipe_ingredient.unit,
+
+        if let Some(ingredient_allergens) = ingredient_allergens.get(&ingredient.uuid) {
+            allergens.extend(ingredient_allergens.iter().copied());
+        }
+
+        let food = ingredient.food.and_then(|food| foods.get(&food));
+        match calculate_nutrients(food, &ingredient, &recipe_ingredient) {
+            Ok(nutrients) => total_nutrients = total_nutrients + nutrients,
+            Err(reason) => missing_nutrition.push(MissingNutrition {
+                ingredient: ingredient.uuid,
+                name: ingredient.name.clone(),
+                reason,
+            }),
+        }
+
+        let prices = ingredient_prices
+            .get(&ingredient.uuid)
+            .map_or(&[][..], Vec::as_slice);
+        match calculate_cost(prices, &ingredient, &recipe_ingredient) {
+            Ok(cost) => total_cost += cost,
+            Err(reason) => missing_cost.push(MissingCost {
+                ingredient: ingredient.uuid,
+                name: ingredient.name.clone(),
+                reason,
+            }),
+        }
+
+        full_ingredients.push(FullRecipeIngredient {
+            ingredient: FullIngredient {
+                uuid: Some(recipe_ingredient.ingredients),
+                name: ingredient.name,
+                amount: recipe_ingredient.amount,
+                unit: recipe_ingredient.unit,
+                preparation: recipe_ingredient.preparation,
+                optional: recipe_ingredient.optional,
+            },
+            category: ingredient.category.filter(|category| {
+                available_categories
+                    .iter()
+                    .any(|available| available.uuid == *category)
+            }),
         })
     }
 
+    let categories = available_categories
+        .into_iter()
+        .filter(|category| {
+            full_ingredients
+                .iter()
+                .any(|ingredient| ingredient.category == Some(category.uuid))
+        })
+        .map(SimpleIngredientCategory::from)
+        .collect();
+
     let Some(account) = Account::query_by_uuid(&mut tx, &recipe.user).await? else {
         return Err(ApiError::bad_request("Account not found"));
     };
 
-    let tags = Tag::query_by_recipe(&mut tx, &recipe.uuid).await?;
+    let tags = Tag::query_by_recipe(&mut tx, &viewer.household, &recipe.uuid).await?;
+    let collections = Collection::query_by_recipe(&mut tx, &viewer.household, &recipe.uuid).await?;
     let steps = RecipeStep::query_by_recipe(&mut tx, &recipe.uuid).await?;
+    let notes = RecipeNote::query_by_recipe(&mut tx, &viewer.account, &recipe.uuid).await?;
+    let shared_with = recipe.query_shares(&mut tx).await?;
+    let favorite = Recipe::query_favorites(&mut tx, &viewer.account)
+        .await?
+        .contains(&recipe.uuid);
+    let rating = RecipeRating::query(&mut tx, &viewer.account, &recipe.uuid).await?;
+    let seasonality = match query_region(&mut tx, &viewer).await? {
+        Some(region) => IngredientSeason::query_recipe_scores(
+            &mut tx,
+            &region,
+            current_month(),
+            Some(&recipe.uuid),
+        )
+        .await?
+        .remove(&recipe.uuid),
+        None => None,
+    };
 
     tx.commit().await?;
 
+    allergens.sort();
+    allergens.dedup();
+
     let full_recipe = FullRecipe {
         uuid: recipe.uuid,
         name: recipe.name,
+        description_html: markdown::render(&recipe.description),
         description: recipe.description,
+        notes_html: markdown::render(&recipe.notes),
+        notes: recipe.notes,
+        servings: recipe.servings,
+        time_minutes: recipe.time_minutes,
+        visibility: recipe.visibility,
+        shared_with,
+        source: recipe.source,
         user: SimpleAccount::from(account),
         ingredients: full_ingredients,
+        categories,
+        substitutions,
         tags: tags.into_iter().map(SimpleTag::from).collect(),
-        steps: steps.into_iter().map(Step::from).collect(),
+        collections: collections
+            .into_iter()
+            .map(SimpleCollection::from)
+            .collect(),
+        personal_note: notes
+            .iter()
+            .find(|note| note.step.is_none())
+            .map(|note| note.note.clone()),
+        favorite,
+        rating: rating.map(|rating| rating.rating),
+        steps: steps
+            .into_iter()
+            .map(|step| {
+                let personal_note = notes
+                    .iter()
+                    .find(|note| note.step == Some(step.uuid))
+                    .map(|note| note.note.clone());
+                Step {
+                    personal_note,
+                    ..Step::from(step)
+                }
+            })
+            .collect(),
+        nutrition: RecipeNutrition {
+            total: total_nutrients,
+            per_serving: total_nutrients.scale(1.0 / f64::from(recipe.servings.max(1))),
+            missing: missing_nutrition,
+        },
+        cost: RecipeCost {
+            total: total_cost,
+            per_serving: total_cost / f64::from(recipe.servings.max(1)),
+            missing: missing_cost,
+        },
+        seasonality,
+        dietary: recipe.dietary,
+        allergens,
     };
 
     Ok(ApiJson(full_recipe))
 }
 
+/// Retrieves all recipes with a source grouped by their source.
+#[get("/sources")]
+pub async fn get_recipes_by_source(
+    viewer: RecipeViewer,
+) -> ApiResult<ApiJson<List<RecipesBySource>>> {
+    let recipes = Recipe::query_with_source(Database::global(), &viewer).await?;
+
+    let mut list: Vec<RecipesBySource> = Vec::new();
+    let mut groups: HashMap<_, usize> = HashMap::new();
+    for recipe in recipes {
+        let Some(source) = recipe.source else {
+            continue;
+        };
+
+        let sourced = SourcedRecipe {
+            uuid: recipe.uuid,
+            name: recipe.name,
+            page: source.page,
+            url: source.url,
+        };
+
+        let key = (source.kind, source.title, source.author);
+        match groups.get(&key) {
+            Some(&index) => list[index].recipes.push(sourced),
+            None => {
+                groups.insert(key.clone(), list.len());
+                list.push(RecipesBySource {
+                    kind: key.0,
+                    title: key.1,
+                    author: key.2,
+                    recipes: vec![sourced],
+                });
+            }
+        }
+    }
+
+    Ok(ApiJson(List { list }))
+}
+
 /// Creates a new recipe.
 #[post("/")]
 pub async fn create_recipe(
     user: Account,
+    household: Household,
     ApiJson(request): ApiJson<CreateOrUpdateRecipe>,
 ) -> ApiResult<ApiJson<SingleUuid>, CreateOrUpdateRecipeErrors> {
     let mut tx = Database::global().start_transaction().await?;
 
     let mut errors = FormErrors::<CreateOrUpdateRecipeErrors>::new();
 
-    if Recipe::query_by_name(&mut tx, &*request.name)
+    if Recipe::query_by_name(&mut tx, &household.uuid, &request.name)
         .await?
         .is_some()
     {
         errors.name_already_exists = true;
     }
 
+    if request.servings < 1 {
+        errors.servings_not_positive = true;
+    }
+
+    if request.time_minutes.is_some_and(|time| time < 1) {
+        errors.time_not_positive = true;
+    }
+
+    if let Some(source) = &request.source {
+        validate_source(source, &mut errors);
+    }
+
     errors.check()?;
 
-    let recipe = Recipe::create(&mut tx, request.name, request.description, user.uuid).await?;
+    validate_shares(
+        &mut tx,
+        &user.uuid,
+        request.visibility,
+        &request.shared_with,
+    )
+    .await?;
+
+    let recipe = Recipe::create(
+        &mut tx,
+        request.name,
+        request.description,
+        request.notes,
+        request.servings,
+        request.time_minutes,
+        user.uuid,
+        &household.uuid,
+        request.visibility,
+        request.source,
+    )
+    .await?;
+
+    recipe.set_shares(&mut tx, &request.shared_with).await?;
 
     RecipeStep::delete_by_recipe(&mut tx, &recipe.uuid).await?;
     for step in request.steps {
@@ -143,30 +576,39 @@ pub async fn create_recipe(
 
     Tag::remove_from_recipe(&mut tx, recipe.uuid).await?;
     for tag in request.tags {
+        if Tag::query_by_uuid(&mut tx, &household.uuid, &tag)
+            .await?
+            .is_none()
+        {
+            return Err(ApiError::bad_request("Invalid tag uuid"));
+        }
         Tag::add_to_recipe(&mut tx, &recipe.uuid, &tag).await?;
     }
 
     RecipeIngredient::delete_by_recipe(&mut tx, &recipe.uuid).await?;
     for ingredient in request.ingredients {
-        let uuid = Ingredient::get_uuid_or_create(&mut tx, ingredient.name).await?;
+        let uuid =
+            Ingredient::get_uuid_or_create(&mut tx, &household.uuid, ingredient.name).await?;
         RecipeIngredient::create(
             &mut tx,
             recipe.uuid,
             uuid,
             ingredient.amount,
             ingredient.unit,
+            ingredient.preparation,
+            ingredient.optional,
         )
         .await?;
     }
 
+    Recipe::refresh_dietary(&mut tx, &recipe.uuid).await?;
+
     tx.commit().await?;
 
-    WebsocketManager::global()
-        .send_to_all(WsServerMsg::RecipesChanged {})
-        .await;
+    send_recipes_changed(&recipe, request.shared_with).await;
 
     WebsocketManager::global()
-        .send_to_all(WsServerMsg::IngredientsChanged {})
+        .send_to_household(household.uuid, WsServerMsg::IngredientsChanged {})
         .await;
 
     Ok(ApiJson(SingleUuid {
@@ -177,6 +619,7 @@ pub async fn create_recipe(
 /// Updates an existing recipe based on its UUID.
 #[put("/{recipe_uuid}")]
 pub async fn update_recipe(
+    viewer: RecipeViewer,
     Path(recipe_uuid): Path<RecipeUuid>,
     ApiJson(request): ApiJson<CreateOrUpdateRecipe>,
 ) -> ApiResult<(), CreateOrUpdateRecipeErrors> {
@@ -184,74 +627,411 @@ pub async fn update_recipe(
 
     let mut errors = FormErrors::<CreateOrUpdateRecipeErrors>::new();
 
-    let recipe = Recipe::query_by_uuid(&mut tx, &recipe_uuid)
+    let recipe = Recipe::query_by_uuid(&mut tx, &viewer, &recipe_uuid)
         .await?
+        .filter(|recipe| recipe.household == viewer.household)
         .ok_or(ApiError::bad_request("Invalid recipe uuid"))?;
 
     if request.name != recipe.name
-        && Recipe::query_by_name(&mut tx, &request.name)
+        && Recipe::query_by_name(&mut tx, &recipe.household, &request.name)
             .await?
             .is_some()
     {
         errors.name_already_exists = true;
     }
 
+    if request.servings < 1 {
+        errors.servings_not_positive = true;
+    }
+
+    if request.time_minutes.is_some_and(|time| time < 1) {
+        errors.time_not_positive = true;
+    }
+
+    if let Some(source) = &request.source {
+        validate_source(source, &mut errors);
+    }
+
     errors.check()?;
 
+    validate_shares(
+        &mut tx,
+        &recipe.user,
+        request.visibility,
+        &request.shared_with,
+    )
+    .await?;
+
+    // Steps are recreated, so their notes have to be moved to the new steps
+    let step_notes = RecipeNote::query_step_notes(&mut tx, &recipe.uuid).await?;
+    let mut replaced_steps = HashMap::new();
+
     RecipeStep::delete_by_recipe(&mut tx, &recipe.uuid).await?;
     for step in request.steps {
-        RecipeStep::create(&mut tx, recipe.uuid, step.step, step.index).await?;
+        let created = RecipeStep::create(&mut tx, recipe.uuid, step.step, step.index).await?;
+        if let Some(old) = step.uuid {
+            replaced_steps.insert(old, created.uuid);
+        }
     }
 
+    RecipeNote::move_to_steps(&mut tx, step_notes, &replaced_steps).await?;
+
     Tag::remove_from_recipe(&mut tx, recipe.uuid).await?;
     for tag in request.tags {
+        if Tag::query_by_uuid(&mut tx, &recipe.household, &tag)
+            .await?
+            .is_none()
+        {
+            return Err(ApiError::bad_request("Invalid tag uuid"));
+        }
         Tag::add_to_recipe(&mut tx, &recipe.uuid, &tag).await?;
     }
 
     RecipeIngredient::delete_by_recipe(&mut tx, &recipe.uuid).await?;
     for ingredient in request.ingredients {
-        let uuid = Ingredient::get_uuid_or_create(&mut tx, ingredient.name).await?;
+        let uuid =
+            Ingredient::get_uuid_or_create(&mut tx, &recipe.household, ingredient.name).await?;
         RecipeIngredient::create(
             &mut tx,
             recipe.uuid,
             uuid,
             ingredient.amount,
             ingredient.unit,
+            ingredient.preparation,
+            ingredient.optional,
         )
         .await?;
     }
 
+    Recipe::refresh_dietary(&mut tx, &recipe.uuid).await?;
+
     recipe
-        .update(&mut tx, request.name, request.description)
+        .update(
+            &mut tx,
+            request.name,
+            request.description,
+            request.notes,
+            request.servings,
+            request.time_minutes,
+            request.visibility,
+            request.source,
+        )
         .await?;
+
+    let old_shares = recipe.query_shares(&mut tx).await?;
+    recipe.set_shares(&mut tx, &request.shared_with).await?;
+
     tx.commit().await?;
 
-    WebsocketManager::global()
-        .send_to_all(WsServerMsg::RecipesChanged {})
-        .await;
+    // Accounts which lost access have to be notified as well
+    send_recipes_changed(&recipe, old_shares).await;
+    let updated = Recipe {
+        visibility: request.visibility,
+        ..recipe
+    };
+    send_recipes_changed(&updated, request.shared_with).await;
 
     WebsocketManager::global()
-        .send_to_all(WsServerMsg::IngredientsChanged {})
+        .send_to_household(updated.household, WsServerMsg::IngredientsChanged {})
         .await;
 
     Ok(())
 }
 
+/// Sets the personal note of the logged-in account on a recipe or one of its steps.
+///
+/// The note is only visible for the account itself. An empty note removes it.
+#[put("/{recipe_uuid}/notes")]
+pub async fn set_recipe_note(
+    viewer: RecipeViewer,
+    Path(recipe_uuid): Path<RecipeUuid>,
+    ApiJson(request): ApiJson<SetRecipeNoteRequest>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let recipe = Recipe::query_by_uuid(&mut tx, &viewer, &recipe_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid recipe uuid"))?;
+
+    if let Some(step) = request.step {
+        let steps = RecipeStep::query_by_recipe(&mut tx, &recipe.uuid).await?;
+        if !steps.iter().any(|existing| existing.uuid == step) {
+            return Err(ApiError::bad_request("Invalid step uuid"));
+        }
+    }
+
+    RecipeNote::set(
+        &mut tx,
+        &viewer.account,
+        &recipe.uuid,
+        request.step,
+        request.note,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Records that the logged-in account has just cooked a recipe.
+#[post("/{recipe_uuid}/cooked")]
+pub async fn mark_recipe_cooked(
+    viewer: RecipeViewer,
+    Path(recipe_uuid): Path<RecipeUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let recipe = Recipe::query_by_uuid(&mut tx, &viewer, &recipe_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid recipe uuid"))?;
+
+    RecipeCook::create(&mut tx, &recipe.uuid, viewer.account, &viewer.household).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Marks or unmarks a recipe as favorite of the logged-in account.
+#[put("/{recipe_uuid}/favorite")]
+pub async fn set_recipe_favorite(
+    viewer: RecipeViewer,
+    Path(recipe_uuid): Path<RecipeUuid>,
+    ApiJson(request): ApiJson<SetRecipeFavoriteRequest>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let recipe = Recipe::query_by_uuid(&mut tx, &viewer, &recipe_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid recipe uuid"))?;
+
+    recipe
+        .set_favorite(&mut tx, &viewer.account, request.favorite)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Rates a recipe for the logged-in account.
+///
+/// A missing rating removes the existing one.
+#[put("/{recipe_uuid}/rating")]
+pub async fn set_recipe_rating(
+    viewer: RecipeViewer,
+    Path(recipe_uuid): Path<RecipeUuid>,
+    ApiJson(request): ApiJson<SetRecipeRatingRequest>,
+) -> ApiResult<(), SetRecipeRatingErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let recipe = Recipe::query_by_uuid(&mut tx, &viewer, &recipe_uuid)
+        .await?
+        .ok_or(ApiError::bad_request("Invalid recipe uuid"))?;
+
+    let mut errors = FormErrors::<SetRecipeRatingErrors>::new();
+
+    if request
+        .rating
+        .is_some_and(|rating| !(MIN_RATING..=MAX_RATING).contains(&rating))
+    {
+        errors.rating_out_of_range = true;
+    }
+
+    errors.check()?;
+
+    RecipeRating::set(&mut tx, &viewer.account, &recipe.uuid, request.rating).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
 /// Deletes a recipe by its UUID.
 #[delete("/{recipe_uuid}")]
-pub async fn delete_recipe(Path(recipe_uuid): Path<RecipeUuid>) -> ApiResult<()> {
+pub async fn delete_recipe(
+    viewer: RecipeViewer,
+    Path(recipe_uuid): Path<RecipeUuid>,
+) -> ApiResult<()> {
     let mut tx = Database::global().start_transaction().await?;
 
-    let recipe = Recipe::query_by_uuid(&mut tx, &recipe_uuid)
+    let recipe = Recipe::query_by_uuid(&mut tx, &viewer, &recipe_uuid)
         .await?
+        .filter(|recipe| recipe.household == viewer.household)
         .ok_or(ApiError::bad_request("Invalid recipe uuid"))?;
 
+    let shares = recipe.query_shares(&mut tx).await?;
     recipe.delete(&mut tx).await?;
+    // Its ingredients might not be used anymore
+    Job::enqueue(&mut tx, JobKind::PurgeUnusedIngredients).await?;
     tx.commit().await?;
 
+    send_recipes_changed(&recipe, shares).await;
+
+    // Planned meals of the recipe are deleted as well
+    WebsocketManager::global()
+        .send_to_household(recipe.household, WsServerMsg::MealPlanChanged {})
+        .await;
+
+    // So is the recipe's membership in collections
     WebsocketManager::global()
-        .send_to_all(WsServerMsg::RecipesChanged {})
+        .send_to_household(recipe.household, WsServerMsg::CollectionsChanged {})
         .await;
 
+    JobQueue::global().wake_up();
+
+    Ok(())
+}
+
+/// Looks up the region of the viewer's household.
+async fn query_region(
+    exe: impl Executor<'_>,
+    viewer: &RecipeViewer,
+) -> ApiResult<Option<MaxStr<255>>> {
+    Ok(Household::query_by_uuid(exe, &viewer.household)
+        .await?
+        .and_then(|household| household.region))
+}
+
+/// Checks the source of a recipe.
+fn validate_source(source: &RecipeSource, errors: &mut CreateOrUpdateRecipeErrors) {
+    if source.title.trim().is_empty() {
+        errors.source_title_empty = true;
+    }
+
+    match &source.url {
+        Some(url) => {
+            let valid = Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+            if !valid {
+                errors.source_url_invalid = true;
+            }
+        }
+        None if source.kind == RecipeSourceKind::Url => errors.source_url_missing = true,
+        None => {}
+    }
+
+    if source.page.is_some_and(|page| page < 1) {
+        errors.source_page_not_positive = true;
+    }
+}
+
+/// Checks the accounts a recipe should be shared with.
+///
+/// Recipes are only shared with existing accounts other than their author,
+/// and only if their visibility is [`RecipeVisibility::Shared`].
+async fn validate_shares(
+    exe: impl Executor<'_>,
+    author: &AccountUuid,
+    visibility: RecipeVisibility,
+    accounts: &[AccountUuid],
+) -> ApiResult<(), CreateOrUpdateRecipeErrors> {
+    if accounts.is_empty() {
+        return Ok(());
+    }
+
+    if visibility != RecipeVisibility::Shared {
+        return Err(ApiError::bad_request(
+            "Only recipes with shared visibility can be shared with accounts",
+        ));
+    }
+
+    if accounts.contains(author) {
+        return Err(ApiError::bad_request(
+            "A recipe can't be shared with its author",
+        ));
+    }
+
+    if accounts
+        .iter()
+        .enumerate()
+        .any(|(index, account)| accounts[..index].contains(account))
+    {
+        return Err(ApiError::bad_request("Duplicate account uuid"));
+    }
+
+    let mut guard = exe.ensure_transaction().await?;
+
+    for account in accounts {
+        if Account::query_by_uuid(guard.get_transaction(), account)
+            .await?
+            .is_none()
+        {
+            return Err(ApiError::bad_request("Invalid account uuid"));
+        }
+    }
+
+    guard.commit().await?;
     Ok(())
 }
+
+/// Notifies all accounts which are able to see a recipe that the recipes have changed.
+async fn send_recipes_changed(recipe: &Recipe, mut shares: Vec<AccountUuid>) {
+    let websockets = WebsocketManager::global();
+    let message = WsServerMsg::RecipesChanged {};
+
+    match recipe.visibility {
+        RecipeVisibility::Private => {
+            websockets
+                .send_to_accounts(vec![recipe.user], message)
+                .await
+        }
+        RecipeVisibility::Shared => {
+            shares.push(recipe.user);
+            websockets.send_to_accounts(shares, message).await
+        }
+        RecipeVisibility::Household => {
+            websockets
+                .send_to_household(recipe.household, message)
+                .await
+        }
+        RecipeVisibility::Everyone => websockets.send_to_all(message).await,
+    }
+}
+
+/// Calculates the nutrients of a single ingredient of a recipe.
+///
+/// Returns the reason if the ingredient can't be included in the calculation.
+fn calculate_nutrients(
+    food: Option<&Food>,
+    ingredient: &Ingredient,
+    recipe_ingredient: &RecipeIngredient,
+) -> Result<Nutrients, MissingNutritionReason> {
+    let Some(food) = food else {
+        return Err(MissingNutritionReason::NoFoodLinked);
+    };
+
+    let Some(grams) = recipe_ingredient
+        .unit
+        .to_grams(recipe_ingredient.amount as f64, ingredient.measures)
+    else {
+        return Err(MissingNutritionReason::UnitNotConvertible);
+    };
+
+    Ok(food.per_100g.scale(grams / 100.0))
+}
+
+/// Estimates the cost of a single ingredient of a recipe.
+///
+/// The most recent price whose unit the amount can be converted to is used.
+/// Returns the reason if the ingredient can't be included in the calculation.
+fn calculate_cost(
+    prices: &[IngredientPrice],
+    ingredient: &Ingredient,
+    recipe_ingredient: &RecipeIngredient,
+) -> Result<f64, MissingCostReason> {
+    if prices.is_empty() {
+        return Err(MissingCostReason::NoPrice);
+    }
+
+    prices
+        .iter()
+        .find_map(|price| {
+            price.estimate(
+                recipe_ingredient.amount as f64,
+                recipe_ingredient.unit,
+                ingredient.measures,
+            )
+        })
+        .ok_or(MissingCostReason::UnitNotConvertible)
+}
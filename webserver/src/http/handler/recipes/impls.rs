@@ -1,13 +1,16 @@
 use super::schema::Step;
 use crate::models::recipe_steps::RecipeStep;
+use crate::utils::markdown;
 
 impl From<RecipeStep> for Step {
     /// Creates a new `RecipeSteps` instance from a given `Steps` instance.
     fn from(value: RecipeStep) -> Self {
         Self {
             uuid: Some(value.uuid),
+            step_html: Some(markdown::render(&value.step)),
             step: value.step,
             index: value.index,
+            personal_note: None,
         }
     }
 }
@@ -11,8 +11,15 @@ pub fn initialize() -> GalvynRouter {
     GalvynRouter::new()
         .openapi_tag("Recipes")
         .handler(recipes::handler::get_all_recipes)
+        .handler(recipes::handler::get_recommendations)
         .handler(recipes::handler::get_recipe)
+        .handler(recipes::handler::get_recipes_by_source)
+        .handler(recipes::handler::surprise_me)
         .handler(recipes::handler::create_recipe)
         .handler(recipes::handler::update_recipe)
+        .handler(recipes::handler::set_recipe_note)
+        .handler(recipes::handler::mark_recipe_cooked)
+        .handler(recipes::handler::set_recipe_favorite)
+        .handler(recipes::handler::set_recipe_rating)
         .handler(recipes::handler::delete_recipe)
 }
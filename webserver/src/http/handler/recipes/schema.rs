@@ -8,11 +8,24 @@ use galvyn::core::stuff::schema::GetPageRequest;
 use galvyn::rorm::fields::types::MaxStr;
 
 use crate::http::handler::account::schema::SimpleAccount;
+use crate::http::handler::collections::schema::SimpleCollection;
 use crate::http::handler::ingredients::schema::FullIngredient;
+use crate::http::handler::ingredients::schema::FullRecipeIngredient;
+use crate::http::handler::ingredients::schema::SimpleIngredientCategory;
+use crate::http::handler::ingredients::schema::SimpleIngredientSubstitution;
 use crate::http::handler::tags::schema::SimpleTag;
 use crate::models::account::AccountUuid;
+use crate::models::foods::Nutrients;
+use crate::models::ingredient_allergens::Allergen;
+use crate::models::ingredients::IngredientUuid;
 use crate::models::recipe_steps::RecipeStepUuid;
+use crate::models::recipes::Diet;
+use crate::models::recipes::DietaryFlags;
+use crate::models::recipes::RecipeSource;
+use crate::models::recipes::RecipeSourceKind;
 use crate::models::recipes::RecipeUuid;
+use crate::models::recipes::RecipeVisibility;
+use crate::models::recommendations::RecommendationReason;
 use crate::models::tags::TagUuid;
 
 /// Represents a simple recipe with associated tags.
@@ -27,11 +40,14 @@ pub struct SimpleRecipeWithTags {
     /// The name of the recipe (string, maximum length 255).
     pub name: MaxStr<255>,
 
-    /// The description of the recipe (string, maximum length 1024).
-    pub description: MaxStr<255>,
+    /// The description of the recipe in markdown.
+    pub description: MaxStr<65535>,
 
     /// A vector of `SimpleTag` objects representing the tags associated with the recipe.
     pub tags: Vec<SimpleTag>,
+
+    /// The dietary flags derived from the ingredients.
+    pub dietary: DietaryFlags,
 }
 
 /// Represents a complete recipe with all associated details.
@@ -46,8 +62,34 @@ pub struct FullRecipe {
     /// The name of the recipe (string, maximum length 255).
     pub name: MaxStr<255>,
 
-    /// The description of the recipe (string, maximum length 1024).
-    pub description: MaxStr<255>,
+    /// The description of the recipe in markdown.
+    pub description: MaxStr<65535>,
+
+    /// The description of the recipe rendered to sanitized HTML.
+    pub description_html: String,
+
+    /// Additional notes on the recipe in markdown.
+    pub notes: MaxStr<65535>,
+
+    /// The notes of the recipe rendered to sanitized HTML.
+    pub notes_html: String,
+
+    /// The number of servings the recipe yields.
+    pub servings: i16,
+
+    /// The total time it takes to prepare the recipe in minutes.
+    pub time_minutes: Option<i32>,
+
+    /// Who is allowed to see the recipe.
+    pub visibility: RecipeVisibility,
+
+    /// The accounts the recipe is shared with.
+    ///
+    /// Only relevant for [`RecipeVisibility::Shared`].
+    pub shared_with: Vec<AccountUuid>,
+
+    /// Where the recipe has been taken from.
+    pub source: Option<RecipeSource>,
 
     /// An optional reference to a simple user object associated with the recipe.
     pub user: SimpleAccount,
@@ -55,12 +97,127 @@ pub struct FullRecipe {
     /// A vector of `SimpleTag` objects representing the tags associated with the recipe.
     pub tags: Vec<SimpleTag>,
 
+    /// The collections containing the recipe.
+    pub collections: Vec<SimpleCollection>,
+
     /// A vector of `RecipeIngredients` objects representing
     /// the ingredients associated with the recipe.
-    pub ingredients: Vec<FullIngredient>,
+    pub ingredients: Vec<FullRecipeIngredient>,
+
+    /// The categories of the ingredients ordered by position.
+    ///
+    /// Used to group the ingredients, uncategorized ones come last.
+    pub categories: Vec<SimpleIngredientCategory>,
+
+    /// The substitutes for the ingredients.
+    ///
+    /// Substitutions limited to this recipe come first.
+    pub substitutions: Vec<SimpleIngredientSubstitution>,
+
+    /// The personal note of the logged-in account on the recipe in markdown.
+    pub personal_note: Option<MaxStr<65535>>,
+
+    /// Whether the logged-in account has marked the recipe as favorite.
+    pub favorite: bool,
+
+    /// The rating of the logged-in account on the recipe.
+    pub rating: Option<i16>,
 
     /// A vector of `Steps` objects representing the steps associated with the recipe.
     pub steps: Vec<Step>,
+
+    /// The nutrition facts calculated from the ingredients.
+    pub nutrition: RecipeNutrition,
+
+    /// The estimated cost calculated from the ingredient prices.
+    pub cost: RecipeCost,
+
+    /// The share of the seasonal ingredients which are in season this month, from 0 to 1.
+    ///
+    /// Missing if the household has no region or the recipe has no seasonal ingredients.
+    pub seasonality: Option<f64>,
+
+    /// The dietary flags derived from the ingredients.
+    pub dietary: DietaryFlags,
+
+    /// The allergens contained in any of the ingredients.
+    pub allergens: Vec<Allergen>,
+}
+
+/// Nutrition facts of a recipe calculated from its ingredients.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecipeNutrition {
+    /// The nutrients of the whole recipe.
+    pub total: Nutrients,
+
+    /// The nutrients of a single serving.
+    pub per_serving: Nutrients,
+
+    /// The ingredients which couldn't be included in the calculation.
+    ///
+    /// If this list is not empty, the nutrients are incomplete.
+    pub missing: Vec<MissingNutrition>,
+}
+
+/// An ingredient of a recipe which couldn't be included in the nutrition calculation.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MissingNutrition {
+    /// The identifier of the ingredient.
+    pub ingredient: IngredientUuid,
+
+    /// The name of the ingredient.
+    pub name: MaxStr<255>,
+
+    /// The reason the ingredient couldn't be included.
+    pub reason: MissingNutritionReason,
+}
+
+/// Reasons why an ingredient couldn't be included in the nutrition calculation.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum MissingNutritionReason {
+    /// The ingredient is not linked to an entry of the food database.
+    NoFoodLinked,
+
+    /// The amount of the ingredient can't be converted to grams.
+    UnitNotConvertible,
+}
+
+/// Estimated cost of a recipe calculated from the most recent prices of its ingredients.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecipeCost {
+    /// The cost of the whole recipe in the smallest unit of the currency (e.g. cents).
+    pub total: f64,
+
+    /// The cost of a single serving in the smallest unit of the currency (e.g. cents).
+    pub per_serving: f64,
+
+    /// The ingredients which couldn't be included in the calculation.
+    ///
+    /// If this list is not empty, the cost is incomplete.
+    pub missing: Vec<MissingCost>,
+}
+
+/// An ingredient of a recipe which couldn't be included in the cost calculation.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MissingCost {
+    /// The identifier of the ingredient.
+    pub ingredient: IngredientUuid,
+
+    /// The name of the ingredient.
+    pub name: MaxStr<255>,
+
+    /// The reason the ingredient couldn't be included.
+    pub reason: MissingCostReason,
+}
+
+/// Reasons why an ingredient couldn't be included in the cost calculation.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum MissingCostReason {
+    /// No price has been recorded for the ingredient.
+    NoPrice,
+
+    /// The amount of the ingredient can't be converted to the unit of any recorded price.
+    UnitNotConvertible,
 }
 
 /// Represents a single step in a process.
@@ -77,11 +234,23 @@ pub struct Step {
     /// In case of a response: The uuid must be set.
     pub uuid: Option<RecipeStepUuid>,
 
-    /// The actual value of the step (string, maximum length 255).
-    pub step: MaxStr<255>,
+    /// The actual value of the step in markdown.
+    pub step: MaxStr<65535>,
+
+    /// The step rendered to sanitized HTML.
+    ///
+    /// In case of a request: The value is ignored.
+    ///
+    /// In case of a response: The html must be set.
+    pub step_html: Option<String>,
 
     /// Representing the step's position in the sequence.
     pub index: i16,
+
+    /// The personal note of the logged-in account on the step in markdown.
+    ///
+    /// In case of a request: The value is ignored, notes are set separately.
+    pub personal_note: Option<MaxStr<65535>>,
 }
 
 /// Represents the request body for creating a new recipe.
@@ -90,8 +259,33 @@ pub struct CreateOrUpdateRecipe {
     /// The name of the recipe
     pub name: MaxStr<255>,
 
-    /// the description of the recipe
-    pub description: MaxStr<255>,
+    /// the description of the recipe in markdown
+    pub description: MaxStr<65535>,
+
+    /// additional notes on the recipe in markdown
+    #[serde(default)]
+    pub notes: MaxStr<65535>,
+
+    /// the number of servings the recipe yields
+    pub servings: i16,
+
+    /// the total time it takes to prepare the recipe in minutes
+    pub time_minutes: Option<i32>,
+
+    /// who is allowed to see the recipe
+    ///
+    /// Defaults to [`RecipeVisibility::Household`].
+    #[serde(default)]
+    pub visibility: RecipeVisibility,
+
+    /// the accounts the recipe is shared with
+    ///
+    /// Only relevant for [`RecipeVisibility::Shared`].
+    #[serde(default)]
+    pub shared_with: Vec<AccountUuid>,
+
+    /// where the recipe has been taken from
+    pub source: Option<RecipeSource>,
 
     /// Optional user which is associated with the recipe.
     ///
@@ -109,10 +303,58 @@ pub struct CreateOrUpdateRecipe {
     pub steps: Vec<Step>,
 }
 
+/// The request to set a personal note on a recipe or one of its steps.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetRecipeNoteRequest {
+    /// The step to attach the note to, `None` for a note on the whole recipe.
+    pub step: Option<RecipeStepUuid>,
+
+    /// The text of the note in markdown.
+    pub note: MaxStr<65535>,
+}
+
 /// Errors for create or update a recipe.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct CreateOrUpdateRecipeErrors {
     pub name_already_exists: bool,
+    pub servings_not_positive: bool,
+    pub time_not_positive: bool,
+    pub source_title_empty: bool,
+    pub source_url_missing: bool,
+    pub source_url_invalid: bool,
+    pub source_page_not_positive: bool,
+}
+
+/// The recipes taken from a single source.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecipesBySource {
+    /// The kind of the source.
+    pub kind: RecipeSourceKind,
+
+    /// The title of the book or website or the name of the person.
+    pub title: MaxStr<255>,
+
+    /// The author of the book or website.
+    pub author: Option<MaxStr<255>>,
+
+    /// The recipes taken from the source ordered by their name.
+    pub recipes: Vec<SourcedRecipe>,
+}
+
+/// A recipe with its location in a source.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SourcedRecipe {
+    /// The identifier for the recipe.
+    pub uuid: RecipeUuid,
+
+    /// The name of the recipe.
+    pub name: MaxStr<255>,
+
+    /// The page of the book the recipe is printed on.
+    pub page: Option<i32>,
+
+    /// The address of the website the recipe is published on.
+    pub url: Option<MaxStr<2048>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -123,4 +365,97 @@ pub struct GetAllRecipesRequest {
 
     /// Search for recipe name
     pub filter_name: Option<String>,
+
+    /// Only list recipes suitable for all of these diets
+    #[serde(default)]
+    pub filter_diet: Vec<Diet>,
+
+    /// Only list recipes whose seasonal ingredients are all in season this month
+    ///
+    /// Requires the household to have a region. Only supported when listing all recipes.
+    #[serde(default)]
+    pub filter_in_season: bool,
+
+    /// List the most seasonal recipes first instead of ordering by name
+    ///
+    /// Requires the household to have a region. Only supported when listing all recipes.
+    #[serde(default)]
+    pub sort_by_season: bool,
+}
+
+/// The request to pick random recipes.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SurpriseMeRequest {
+    /// The number of recipes to pick (at most 50)
+    pub count: i64,
+
+    /// The seed to shuffle the recipes with.
+    ///
+    /// The same seed picks the same recipes, a random one is used if none is given.
+    pub seed: Option<i64>,
+
+    /// Only pick recipes with all of these tags
+    #[serde(default)]
+    pub filter_tags: Vec<TagUuid>,
+
+    /// Only pick recipes which can be prepared within this many minutes
+    pub max_time_minutes: Option<i32>,
+
+    /// Don't pick recipes using any of these ingredients
+    #[serde(default)]
+    pub exclude_ingredients: Vec<IngredientUuid>,
+
+    /// Don't pick recipes which have been cooked within this many days
+    pub not_cooked_within_days: Option<i32>,
+}
+
+/// Errors for picking random recipes.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct SurpriseMeErrors {
+    pub count_out_of_range: bool,
+    pub max_time_not_positive: bool,
+    pub days_not_positive: bool,
+}
+
+/// The randomly picked recipes.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SurpriseMeResponse {
+    /// The seed the recipes have been picked with.
+    pub seed: i64,
+
+    /// The picked recipes.
+    pub recipes: Vec<SimpleRecipeWithTags>,
+}
+
+/// The request to mark or unmark a recipe as favorite.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetRecipeFavoriteRequest {
+    /// Whether the recipe is a favorite.
+    pub favorite: bool,
+}
+
+/// The request to rate a recipe.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetRecipeRatingRequest {
+    /// The number of stars from 1 to 5, `None` to remove the rating.
+    pub rating: Option<i16>,
+}
+
+/// Errors for rating a recipe.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct SetRecipeRatingErrors {
+    pub rating_out_of_range: bool,
+}
+
+/// A recipe recommended for the logged-in account.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecommendedRecipe {
+    /// The recommended recipe.
+    pub recipe: SimpleRecipeWithTags,
+
+    /// How well the recipe matches the account's taste, higher is better.
+    pub score: f64,
+
+    /// The most important reasons for the recommendation.
+    pub reasons: Vec<RecommendationReason>,
 }
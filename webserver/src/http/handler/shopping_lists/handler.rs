@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+
+use galvyn::core::re_exports::axum::extract::Path;
+use galvyn::core::stuff::api_error::ApiError;
+use galvyn::core::stuff::api_error::ApiResult;
+use galvyn::core::stuff::api_error::FormErrors;
+use galvyn::core::stuff::api_json::ApiJson;
+use galvyn::core::stuff::schema::List;
+use galvyn::core::stuff::schema::SingleUuid;
+use galvyn::core::Module;
+use galvyn::delete;
+use galvyn::get;
+use galvyn::post;
+use galvyn::put;
+use galvyn::rorm::Database;
+use tracing::error;
+
+use super::schema::CreateShoppingListItem;
+use super::schema::CreateShoppingListItemErrors;
+use super::schema::CreateShoppingListRequest;
+use super::schema::FullShoppingList;
+use super::schema::GenerateShoppingListErrors;
+use super::schema::GenerateShoppingListRequest;
+use super::schema::SetShoppingListItemCheckedRequest;
+use super::schema::SimpleShoppingList;
+use super::schema::SimpleShoppingListItem;
+use super::schema::UpdateShoppingListItem;
+use super::schema::UpdateShoppingListItemErrors;
+use crate::http::handler::ingredients::schema::SimpleIngredientCategory;
+use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::account::Account;
+use crate::models::households::Household;
+use crate::models::ingredient_categories::IngredientCategory;
+use crate::models::ingredients::Ingredient;
+use crate::models::meal_plans::MealPlanEntry;
+use crate::models::recipe_ingredients::RecipeIngredient;
+use crate::models::recipes::Recipe;
+use crate::models::recipes::RecipeViewer;
+use crate::models::shopping_lists::NewShoppingListItem;
+use crate::models::shopping_lists::ShoppingList;
+use crate::models::shopping_lists::ShoppingListItem;
+use crate::models::shopping_lists::ShoppingListItemUuid;
+use crate::models::shopping_lists::ShoppingListUuid;
+use crate::modules::websockets::WebsocketManager;
+
+/// Retrieves all shopping lists, most recent first.
+#[get("/all")]
+pub async fn get_all_shopping_lists(
+    household: Household,
+) -> ApiResult<ApiJson<List<SimpleShoppingList>>> {
+    let lists = ShoppingList::query_all(Database::global(), &household.uuid).await?;
+
+    Ok(ApiJson(List {
+        list: lists.into_iter().map(SimpleShoppingList::from).collect(),
+    }))
+}
+
+/// Retrieves a shopping list with all its items.
+#[get("/{list_uuid}")]
+pub async fn get_shopping_list(
+    household: Household,
+    Path(list_uuid): Path<ShoppingListUuid>,
+) -> ApiResult<ApiJson<FullShoppingList>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(list) = ShoppingList::query_by_uuid(&mut tx, &household.uuid, &list_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid shopping list uuid"));
+    };
+
+    let items = ShoppingListItem::query_by_list(&mut tx, &list.uuid).await?;
+    let ingredient_categories: HashMap<_, _> = Ingredient::query_all(&mut tx, &household.uuid)
+        .await?
+        .into_iter()
+        .filter_map(|ingredient| Some((ingredient.uuid, ingredient.category?)))
+        .collect();
+    let categories = IngredientCategory::query_all(&mut tx, &household.uuid).await?;
+
+    tx.commit().await?;
+
+    let items: Vec<_> = items
+        .into_iter()
+        .map(|item| {
+            let category = item
+                .ingredient
+                .and_then(|ingredient| ingredient_categories.get(&ingredient).copied());
+            SimpleShoppingListItem::from((item, category))
+        })
+        .collect();
+
+    Ok(ApiJson(FullShoppingList {
+        uuid: list.uuid,
+        name: list.name,
+        account: list.account,
+        categories: categories
+            .into_iter()
+            .filter(|category| {
+                items
+                    .iter()
+                    .any(|item| item.category == Some(category.uuid))
+            })
+            .map(SimpleIngredientCategory::from)
+            .collect(),
+        items,
+    }))
+}
+
+/// Creates an empty shopping list.
+#[post("/")]
+pub async fn create_shopping_list(
+    user: Account,
+    household: Household,
+    ApiJson(request): ApiJson<CreateShoppingListRequest>,
+) -> ApiResult<ApiJson<SingleUuid>> {
+    let list = ShoppingList::create(
+        Database::global(),
+        request.name,
+        user.uuid,
+        &household.uuid,
+        Vec::new(),
+    )
+    .await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::ShoppingListsChanged {})
+        .await;
+
+    Ok(ApiJson(SingleUuid { uuid: list.uuid.0 }))
+}
+
+/// Generates a shopping list from recipes and planned meals.
+///
+/// The ingredients are scaled by the requested servings and merged per ingredient
+/// as long as their units can be converted into each other.
+#[post("/generate")]
+pub async fn generate_shopping_list(
+    user: Account,
+    viewer: RecipeViewer,
+    ApiJson(request): ApiJson<GenerateShoppingListRequest>,
+) -> ApiResult<ApiJson<SingleUuid>, GenerateShoppingListErrors> {
+    let GenerateShoppingListRequest {
+        name,
+        recipes,
+        meal_plan,
+        include_optional,
+    } = request;
+
+    let mut tx = Database::global().start_transaction().await?;
+
+    let mut errors = FormErrors::<GenerateShoppingListErrors>::new();
+
+    let mut selected: Vec<_> = recipes
+        .into_iter()
+        .map(|recipe| (recipe.recipe, recipe.servings))
+        .collect();
+
+    if let Some(meal_plan) = meal_plan {
+        if meal_plan.from > meal_plan.to {
+            return Err(ApiError::bad_request("Invalid range"));
+        }
+
+        let entries = MealPlanEntry::query_range(
+            &mut tx,
+            &viewer.household,
+            meal_plan.from,
+            meal_plan.to,
+            meal_plan.filter_account,
+        )
+        .await?;
+        selected.extend(
+            entries
+                .into_iter()
+                .filter_map(|entry| Some((entry.recipe?, Some(entry.servings)))),
+        );
+    }
+
+    if selected.is_empty() {
+        errors.no_recipes_selected = true;
+    }
+
+    if selected
+        .iter()
+        .any(|(_, servings)| servings.is_some_and(|servings| servings < 1))
+    {
+        errors.servings_not_positive = true;
+    }
+
+    errors.check()?;
+
+    let mut items = Vec::new();
+    let mut measures = HashMap::new();
+    for (recipe_uuid, servings) in selected {
+        let Some(recipe) = Recipe::query_by_uuid(&mut tx, &viewer, &recipe_uuid).await? else {
+            return Err(ApiError::bad_request("Invalid recipe uuid"));
+        };
+
+        let factor = servings
+            .map(|servings| f64::from(servings) / f64::from(recipe.servings.max(1)))
+            .unwrap_or(1.0);
+
+        for recipe_ingredient in RecipeIngredient::query_by_recipe(&mut tx, &recipe.uuid).await? {
+            if recipe_ingredient.optional && !include_optional {
+                continue;
+            }
+
+            let Some(ingredient) = Ingredient::query_by_uuid(
+                &mut tx,
+                &recipe.household,
+                &recipe_ingredient.ingredients,
+            )
+            .await?
+            else {
+                error!(recipe_ingredient = ?recipe_ingredient.ingredients, "Ingredient not found");
+                continue;
+            };
+
+            // Recipes of other households use our ingredient of the same name if we have one,
+            // otherwise the item only has a name
+            let ingredient_uuid = if recipe.household == viewer.household {
+                Some(ingredient.uuid)
+            } else {
+                Ingredient::query_by_normalized_name(&mut tx, &viewer.household, &ingredient.name)
+                    .await?
+            };
+
+            if let Some(ingredient_uuid) = ingredient_uuid {
+                measures.insert(ingredient_uuid, ingredient.measures);
+            }
+            items.push(NewShoppingListItem {
+                ingredient: ingredient_uuid,
+                name: ingredient.name,
+                amount: recipe_ingredient.amount as f64 * factor,
+                unit: recipe_ingredient.unit,
+            });
+        }
+    }
+
+    let list = ShoppingList::create(
+        &mut tx,
+        name,
+        user.uuid,
+        &viewer.household,
+        NewShoppingListItem::aggregate(items, &measures),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(viewer.household, WsServerMsg::ShoppingListsChanged {})
+        .await;
+
+    Ok(ApiJson(SingleUuid { uuid: list.uuid.0 }))
+}
+
+/// Deletes a shopping list with all its items.
+#[delete("/{list_uuid}")]
+pub async fn delete_shopping_list(
+    household: Household,
+    Path(list_uuid): Path<ShoppingListUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(list) = ShoppingList::query_by_uuid(&mut tx, &household.uuid, &list_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid shopping list uuid"));
+    };
+
+    list.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::ShoppingListsChanged {})
+        .await;
+
+    Ok(())
+}
+
+/// Adds an item to the end of a shopping list.
+#[post("/{list_uuid}/items")]
+pub async fn create_shopping_list_item(
+    household: Household,
+    Path(list_uuid): Path<ShoppingListUuid>,
+    ApiJson(request): ApiJson<CreateShoppingListItem>,
+) -> ApiResult<ApiJson<SingleUuid>, CreateShoppingListItemErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(list) = ShoppingList::query_by_uuid(&mut tx, &household.uuid, &list_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid shopping list uuid"));
+    };
+
+    let ingredient = match &request.ingredient {
+        Some(ingredient_uuid) => Some(
+            Ingredient::query_by_uuid(&mut tx, &household.uuid, ingredient_uuid)
+                .await?
+                .ok_or(ApiError::bad_request("Invalid ingredient uuid"))?,
+        ),
+        None => None,
+    };
+
+    let mut errors = FormErrors::<CreateShoppingListItemErrors>::new();
+
+    if request.amount < 0.0 {
+        errors.amount_negative = true;
+    }
+
+    let Some(name) = request.name.or_else(|| {
+        ingredient
+            .as_ref()
+            .map(|ingredient| ingredient.name.clone())
+    }) else {
+        errors.name_missing = true;
+        errors.check()?;
+        unreachable!("a missing name is always reported as form error");
+    };
+
+    errors.check()?;
+
+    let item = ShoppingListItem::create(
+        &mut tx,
+        &list.uuid,
+        NewShoppingListItem {
+            ingredient: request.ingredient,
+            name,
+            amount: request.amount,
+            unit: request.unit,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(
+            household.uuid,
+            WsServerMsg::ShoppingListChanged { list: list.uuid },
+        )
+        .await;
+
+    Ok(ApiJson(SingleUuid { uuid: item.uuid.0 }))
+}
+
+/// Updates the name and quantity of an item on a shopping list.
+#[put("/items/{item_uuid}")]
+pub async fn update_shopping_list_item(
+    household: Household,
+    Path(item_uuid): Path<ShoppingListItemUuid>,
+    ApiJson(request): ApiJson<UpdateShoppingListItem>,
+) -> ApiResult<(), UpdateShoppingListItemErrors> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(item) = ShoppingListItem::query_by_uuid(&mut tx, &household.uuid, &item_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid shopping list item uuid"));
+    };
+
+    let mut errors = FormErrors::<UpdateShoppingListItemErrors>::new();
+
+    if request.amount < 0.0 {
+        errors.amount_negative = true;
+    }
+
+    errors.check()?;
+
+    item.update(&mut tx, request.name, request.amount, request.unit)
+        .await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(
+            household.uuid,
+            WsServerMsg::ShoppingListChanged { list: item.list },
+        )
+        .await;
+
+    Ok(())
+}
+
+/// Checks or unchecks an item on a shopping list.
+///
+/// The checked state is set rather than toggled, so concurrent requests of
+/// multiple shoppers checking the same item don't revert each other.
+/// Other clients are only notified if the state has actually changed.
+#[put("/items/{item_uuid}/checked")]
+pub async fn set_shopping_list_item_checked(
+    household: Household,
+    Path(item_uuid): Path<ShoppingListItemUuid>,
+    ApiJson(request): ApiJson<SetShoppingListItemCheckedRequest>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(item) = ShoppingListItem::query_by_uuid(&mut tx, &household.uuid, &item_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid shopping list item uuid"));
+    };
+
+    let changed = item.set_checked(&mut tx, request.checked).await?;
+    tx.commit().await?;
+
+    if changed {
+        WebsocketManager::global()
+            .send_to_household(
+                household.uuid,
+                WsServerMsg::ShoppingListChanged { list: item.list },
+            )
+            .await;
+    }
+
+    Ok(())
+}
+
+/// Removes an item from a shopping list.
+#[delete("/items/{item_uuid}")]
+pub async fn delete_shopping_list_item(
+    household: Household,
+    Path(item_uuid): Path<ShoppingListItemUuid>,
+) -> ApiResult<()> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(item) = ShoppingListItem::query_by_uuid(&mut tx, &household.uuid, &item_uuid).await?
+    else {
+        return Err(ApiError::bad_request("Invalid shopping list item uuid"));
+    };
+
+    let list = item.list;
+    item.delete(&mut tx).await?;
+    tx.commit().await?;
+
+    WebsocketManager::global()
+        .send_to_household(household.uuid, WsServerMsg::ShoppingListChanged { list })
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use super::schema::SimpleShoppingList;
+use super::schema::SimpleShoppingListItem;
+use crate::models::ingredient_categories::IngredientCategoryUuid;
+use crate::models::shopping_lists::ShoppingList;
+use crate::models::shopping_lists::ShoppingListItem;
+
+impl From<ShoppingList> for SimpleShoppingList {
+    fn from(value: ShoppingList) -> Self {
+        Self {
+            uuid: value.uuid,
+            name: value.name,
+            account: value.account,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<(ShoppingListItem, Option<IngredientCategoryUuid>)> for SimpleShoppingListItem {
+    fn from((value, category): (ShoppingListItem, Option<IngredientCategoryUuid>)) -> Self {
+        Self {
+            uuid: value.uuid,
+            index: value.index,
+            ingredient: value.ingredient,
+            name: value.name,
+            amount: value.amount,
+            unit: value.unit,
+            checked: value.checked,
+            category,
+        }
+    }
+}
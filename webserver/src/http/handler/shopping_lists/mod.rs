@@ -0,0 +1,20 @@
+use galvyn::core::GalvynRouter;
+use galvyn::openapi::OpenapiRouterExt;
+
+mod handler;
+mod impls;
+pub mod schema;
+
+pub fn initialize() -> GalvynRouter {
+    GalvynRouter::new()
+        .openapi_tag("Shopping lists")
+        .handler(handler::get_all_shopping_lists)
+        .handler(handler::get_shopping_list)
+        .handler(handler::create_shopping_list)
+        .handler(handler::generate_shopping_list)
+        .handler(handler::delete_shopping_list)
+        .handler(handler::create_shopping_list_item)
+        .handler(handler::update_shopping_list_item)
+        .handler(handler::set_shopping_list_item_checked)
+        .handler(handler::delete_shopping_list_item)
+}
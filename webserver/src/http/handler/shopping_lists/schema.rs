@@ -0,0 +1,183 @@
+//! Represents all shopping list responses and requests.
+
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::fields::types::MaxStr;
+use time::OffsetDateTime;
+
+use crate::http::handler::ingredients::schema::SimpleIngredientCategory;
+use crate::http::handler::meal_plans::schema::GetMealPlanRequest;
+use crate::models::account::AccountUuid;
+use crate::models::ingredient_categories::IngredientCategoryUuid;
+use crate::models::ingredients::IngredientUuid;
+use crate::models::ingredients::Units;
+use crate::models::recipes::RecipeUuid;
+use crate::models::shopping_lists::ShoppingListItemUuid;
+use crate::models::shopping_lists::ShoppingListUuid;
+
+/// Represents a shopping list without its items.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleShoppingList {
+    /// The UUID of the list.
+    pub uuid: ShoppingListUuid,
+
+    /// The name of the list.
+    pub name: MaxStr<255>,
+
+    /// The account which created the list.
+    pub account: AccountUuid,
+
+    /// The point in time the list was created.
+    #[schemars(with = "String")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Represents a shopping list with all its items.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FullShoppingList {
+    /// The UUID of the list.
+    pub uuid: ShoppingListUuid,
+
+    /// The name of the list.
+    pub name: MaxStr<255>,
+
+    /// The account which created the list.
+    pub account: AccountUuid,
+
+    /// The items ordered by their position on the list.
+    pub items: Vec<SimpleShoppingListItem>,
+
+    /// The categories of the items ordered by position.
+    ///
+    /// Used to group the items, uncategorized ones come last.
+    pub categories: Vec<SimpleIngredientCategory>,
+}
+
+/// Represents an item on a shopping list.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimpleShoppingListItem {
+    /// The UUID of the item.
+    pub uuid: ShoppingListItemUuid,
+
+    /// The position of the item on the list.
+    pub index: i32,
+
+    /// The ingredient to buy.
+    pub ingredient: Option<IngredientUuid>,
+
+    /// The name of the item.
+    pub name: MaxStr<255>,
+
+    /// The quantity to buy.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// Whether the item has already been bought.
+    pub checked: bool,
+
+    /// The category of the ingredient to buy.
+    pub category: Option<IngredientCategoryUuid>,
+}
+
+/// Request to create an empty shopping list.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateShoppingListRequest {
+    /// The name of the list.
+    pub name: MaxStr<255>,
+}
+
+/// Request to generate a shopping list from recipes and planned meals.
+///
+/// The ingredients of all selected recipes are merged where possible.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GenerateShoppingListRequest {
+    /// The name of the list.
+    pub name: MaxStr<255>,
+
+    /// The recipes to buy the ingredients for.
+    #[serde(default)]
+    pub recipes: Vec<ShoppingListRecipe>,
+
+    /// The range of the meal plan whose recipes should be included.
+    pub meal_plan: Option<GetMealPlanRequest>,
+
+    /// Whether the optional ingredients of the recipes should be bought as well.
+    #[serde(default)]
+    pub include_optional: bool,
+}
+
+/// A recipe to buy the ingredients for.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ShoppingListRecipe {
+    /// The UUID of the recipe.
+    pub recipe: RecipeUuid,
+
+    /// The number of servings to buy for.
+    ///
+    /// Defaults to the servings of the recipe.
+    pub servings: Option<i16>,
+}
+
+/// Errors for generating a shopping list.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct GenerateShoppingListErrors {
+    pub no_recipes_selected: bool,
+    pub servings_not_positive: bool,
+}
+
+/// Request to update an item on a shopping list.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateShoppingListItem {
+    /// The name of the item.
+    pub name: MaxStr<255>,
+
+    /// The quantity to buy.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+}
+
+/// Errors for updating an item on a shopping list.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct UpdateShoppingListItemErrors {
+    pub amount_negative: bool,
+}
+
+/// Request to add an item to a shopping list.
+///
+/// The item is either linked to an ingredient or a free text.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateShoppingListItem {
+    /// The ingredient to buy.
+    pub ingredient: Option<IngredientUuid>,
+
+    /// The name of the item.
+    ///
+    /// Defaults to the name of the ingredient.
+    pub name: Option<MaxStr<255>>,
+
+    /// The quantity to buy.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+}
+
+/// Errors for adding an item to a shopping list.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CreateShoppingListItemErrors {
+    pub name_missing: bool,
+    pub amount_negative: bool,
+}
+
+/// Request to check or uncheck an item on a shopping list.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetShoppingListItemCheckedRequest {
+    /// Whether the item has been bought.
+    pub checked: bool,
+}
@@ -18,7 +18,9 @@ use crate::http::handler::tags::schema::CreateOrUpdateTagErrors;
 use crate::http::handler::tags::schema::GetAllTagsRequest;
 use crate::http::handler::tags::schema::SimpleTag;
 use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::households::Household;
 use crate::models::recipes::Recipe;
+use crate::models::recipes::RecipeViewer;
 use crate::models::tags::Tag;
 use crate::models::tags::TagUuid;
 use crate::modules::websockets::WebsocketManager;
@@ -26,12 +28,13 @@ use crate::modules::websockets::WebsocketManager;
 /// Retrieves all tags with pagination support.
 #[post("/all")]
 pub async fn get_all_tags(
+    household: Household,
     ApiJson(pagination): ApiJson<GetAllTagsRequest>,
 ) -> ApiResult<ApiJson<Page<SimpleTag>>> {
     let GetAllTagsRequest { page, filter_name } = pagination;
 
-    let result = Tag::query_all(Database::global(), &page, filter_name).await?;
-    let total = Tag::query_total(Database::global()).await?;
+    let result = Tag::query_all(Database::global(), &household.uuid, &page, filter_name).await?;
+    let total = Tag::query_total(Database::global(), &household.uuid).await?;
 
     Ok(ApiJson(Page {
         items: result.into_iter().map(SimpleTag::from).collect(),
@@ -44,29 +47,57 @@ pub async fn get_all_tags(
 /// Retrieves a paginated list of recipes associated with a specific tag.
 #[post("/{tag_uuid}/recipes")]
 pub async fn get_recipes_by_tag(
+    viewer: RecipeViewer,
     Path(tag_uuid): Path<TagUuid>,
     ApiJson(pagination): ApiJson<GetAllRecipesRequest>,
 ) -> ApiResult<ApiJson<Page<SimpleRecipeWithTags>>> {
-    let GetAllRecipesRequest { page, filter_name } = pagination;
+    let GetAllRecipesRequest {
+        page,
+        filter_name,
+        filter_diet,
+        filter_in_season,
+        sort_by_season,
+    } = pagination;
+
+    if filter_in_season || sort_by_season {
+        return Err(ApiError::bad_request(
+            "Seasons are only supported when listing all recipes",
+        ));
+    }
 
     let mut tx = Database::global().start_transaction().await?;
 
-    let recipes = Recipe::query_by_tag(&mut tx, &tag_uuid, &page, filter_name).await?;
+    let total = Recipe::query_total_by_tag(
+        &mut tx,
+        &viewer,
+        &tag_uuid,
+        filter_name.as_deref(),
+        &filter_diet,
+    )
+    .await?;
+    let recipes = Recipe::query_by_tag(
+        &mut tx,
+        &viewer,
+        &tag_uuid,
+        &page,
+        filter_name,
+        &filter_diet,
+    )
+    .await?;
 
     let mut result = Vec::new();
     for recipe in recipes {
-        let tags = Tag::query_by_recipe(&mut tx, &recipe.uuid).await?;
+        let tags = Tag::query_by_recipe(&mut tx, &viewer.household, &recipe.uuid).await?;
 
         result.push(SimpleRecipeWithTags {
             uuid: recipe.uuid,
             name: recipe.name,
             description: recipe.description,
             tags: tags.into_iter().map(SimpleTag::from).collect(),
+            dietary: recipe.dietary,
         })
     }
 
-    let total = Recipe::query_total(&mut tx).await?;
-
     tx.commit().await?;
 
     Ok(ApiJson(Page {
@@ -80,23 +111,27 @@ pub async fn get_recipes_by_tag(
 /// Creates a tag.
 #[post("/")]
 pub async fn create_tag(
+    household: Household,
     ApiJson(request): ApiJson<CreateOrUpdateTag>,
 ) -> ApiResult<ApiJson<SingleUuid>, CreateOrUpdateTagErrors> {
     let mut tx = Database::global().start_transaction().await?;
 
     let mut errors = FormErrors::<CreateOrUpdateTagErrors>::new();
 
-    if Tag::query_by_name(&mut tx, &*request.name).await?.is_some() {
+    if Tag::query_by_name(&mut tx, &household.uuid, &request.name)
+        .await?
+        .is_some()
+    {
         errors.name_already_exists = true;
     }
 
     errors.check()?;
 
-    let tag = Tag::create(&mut tx, request.name, request.color).await?;
+    let tag = Tag::create(&mut tx, &household.uuid, request.name, request.color).await?;
     tx.commit().await?;
 
     WebsocketManager::global()
-        .send_to_all(WsServerMsg::TagsChanged {})
+        .send_to_household(household.uuid, WsServerMsg::TagsChanged {})
         .await;
 
     Ok(ApiJson(SingleUuid { uuid: tag.uuid.0 }))
@@ -105,6 +140,7 @@ pub async fn create_tag(
 /// Update a tag.
 #[put("/{tag_uuid}")]
 pub async fn update_tag(
+    household: Household,
     Path(tag_uuid): Path<TagUuid>,
     ApiJson(request): ApiJson<CreateOrUpdateTag>,
 ) -> ApiResult<(), CreateOrUpdateTagErrors> {
@@ -112,11 +148,15 @@ pub async fn update_tag(
 
     let mut errors = FormErrors::<CreateOrUpdateTagErrors>::new();
 
-    let Some(tag) = Tag::query_by_uuid(&mut tx, &tag_uuid).await? else {
+    let Some(tag) = Tag::query_by_uuid(&mut tx, &household.uuid, &tag_uuid).await? else {
         return Err(ApiError::bad_request("Invalid tag uuid"));
     };
 
-    if tag.name != request.name && Tag::query_by_name(&mut tx, &*request.name).await?.is_some() {
+    if tag.name != request.name
+        && Tag::query_by_name(&mut tx, &household.uuid, &request.name)
+            .await?
+            .is_some()
+    {
         errors.name_already_exists = true;
     }
 
@@ -126,7 +166,7 @@ pub async fn update_tag(
     tx.commit().await?;
 
     WebsocketManager::global()
-        .send_to_all(WsServerMsg::TagsChanged {})
+        .send_to_household(household.uuid, WsServerMsg::TagsChanged {})
         .await;
 
     Ok(())
@@ -134,10 +174,10 @@ pub async fn update_tag(
 
 /// Delete a tag.
 #[delete("/{tag_uuid}")]
-pub async fn delete_tag(Path(tag_uuid): Path<TagUuid>) -> ApiResult<()> {
+pub async fn delete_tag(household: Household, Path(tag_uuid): Path<TagUuid>) -> ApiResult<()> {
     let mut tx = Database::global().start_transaction().await?;
 
-    let Some(tag) = Tag::query_by_uuid(&mut tx, &tag_uuid).await? else {
+    let Some(tag) = Tag::query_by_uuid(&mut tx, &household.uuid, &tag_uuid).await? else {
         return Err(ApiError::bad_request("Invalid tag uuid"));
     };
 
@@ -145,7 +185,7 @@ pub async fn delete_tag(Path(tag_uuid): Path<TagUuid>) -> ApiResult<()> {
     tx.commit().await?;
 
     WebsocketManager::global()
-        .send_to_all(WsServerMsg::TagsChanged {})
+        .send_to_household(household.uuid, WsServerMsg::TagsChanged {})
         .await;
 
     Ok(())
@@ -9,11 +9,13 @@ use galvyn::core::re_exports::axum::extract::WebSocketUpgrade;
 use galvyn::core::re_exports::axum::response::IntoResponse;
 use galvyn::core::re_exports::axum::response::Response;
 use galvyn::core::re_exports::serde_json;
+use galvyn::core::session::Id;
 use galvyn::core::session::Session;
 use galvyn::core::stuff::api_error::ApiError;
 use galvyn::core::stuff::api_error::ApiResult;
 use galvyn::core::Module;
 use galvyn::get;
+use galvyn::rorm::Database;
 use tokio::select;
 use tokio::sync::mpsc::channel;
 use tokio::sync::mpsc::Receiver;
@@ -25,12 +27,24 @@ use tracing::debug;
 use tracing::error;
 use tracing::trace;
 
+use crate::http::handler::websockets::schema::WsClientMsg;
 use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::recipe_ingredients::RecipeIngredient;
+use crate::models::recipe_steps::RecipeStep;
+use crate::models::recipes::Recipe;
+use crate::models::recipes::RecipeUuid;
+use crate::models::recipes::RecipeViewer;
+use crate::modules::cook_sessions::CookRecipe;
 use crate::modules::websockets::WebsocketManager;
+use crate::modules::websockets::WEBSOCKET_BUFFER;
 
 /// Open a websocket to the frontend.
 #[get("/")]
-pub async fn open_websocket(ws: WebSocketUpgrade, session: Session) -> ApiResult<WsResponse> {
+pub async fn open_websocket(
+    ws: WebSocketUpgrade,
+    session: Session,
+    viewer: RecipeViewer,
+) -> ApiResult<WsResponse> {
     let id = session
         .id()
         .ok_or(ApiError::server_error("The session should have an id"))?;
@@ -38,9 +52,12 @@ pub async fn open_websocket(ws: WebSocketUpgrade, session: Session) -> ApiResult
     error!(id = ?id, "Session id");
 
     let on_upgrade = move |ws| async move {
-        let (server_tx, server_rx) = channel(1);
-        WebsocketManager::global().register(id, server_tx).await;
-        handle_ws(ws, server_rx).await;
+        let (server_tx, server_rx) = channel(WEBSOCKET_BUFFER);
+        WebsocketManager::global()
+            .register(id, viewer.account, viewer.household, server_tx)
+            .await;
+        handle_ws(ws, id, viewer, server_rx).await;
+        WebsocketManager::global().disconnected(id).await;
     };
 
     Ok(WsResponse(ws.on_upgrade(on_upgrade)))
@@ -54,7 +71,12 @@ impl IntoResponse for WsResponse {
     }
 }
 
-async fn handle_ws(mut ws: WebSocket, mut server: Receiver<WsServerMsg>) {
+async fn handle_ws(
+    mut ws: WebSocket,
+    session: Id,
+    viewer: RecipeViewer,
+    mut server: Receiver<WsServerMsg>,
+) {
     let mut heartbeat = interval(Duration::from_secs(10));
     heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
@@ -84,6 +106,18 @@ async fn handle_ws(mut ws: WebSocket, mut server: Receiver<WsServerMsg>) {
                 match msg {
                     Message::Text(text) => {
                         trace!(%text, "Received text");
+
+                        match serde_json::from_str::<WsClientMsg>(&text) {
+                            Ok(client_msg) => {
+                                if !handle_client_msg(session, &viewer, client_msg).await {
+                                    let _ = ws.send(Message::Close(None)).await;
+                                    return;
+                                }
+                            }
+                            Err(error) => {
+                                debug!(error.display = %error, error.debug = ?error, "Failed to parse client message");
+                            }
+                        }
                     }
                     Message::Binary(binary) => {
                         trace!(?binary, "Received binary");
@@ -131,3 +165,51 @@ async fn handle_ws(mut ws: WebSocket, mut server: Receiver<WsServerMsg>) {
         }
     }
 }
+
+/// Validates a message received from the client and forwards it to the websocket manager.
+///
+/// Joining a cook session looks up the recipe, so the other messages can be validated against it.
+///
+/// Returns `false` if the websocket manager can't keep up with the client, which is disconnected then.
+async fn handle_client_msg(session: Id, viewer: &RecipeViewer, client_msg: WsClientMsg) -> bool {
+    let WsClientMsg::JoinCookSession { recipe } = &client_msg else {
+        return WebsocketManager::global().handle_client_msg(session, client_msg);
+    };
+
+    match query_cook_recipe(viewer, recipe).await {
+        Ok(Some(recipe)) => WebsocketManager::global().join_cook_session(session, recipe),
+        Ok(None) => {
+            debug!(recipe = ?recipe, "Tried to join cook session of unknown recipe");
+            true
+        }
+        Err(error) => {
+            error!(error.display = %error, error.debug = ?error, "Failed to query recipe");
+            true
+        }
+    }
+}
+
+/// Looks up the steps and ingredients of a recipe visible to the viewer.
+async fn query_cook_recipe(
+    viewer: &RecipeViewer,
+    recipe: &RecipeUuid,
+) -> anyhow::Result<Option<CookRecipe>> {
+    let mut tx = Database::global().start_transaction().await?;
+
+    let Some(recipe) = Recipe::query_by_uuid(&mut tx, viewer, recipe).await? else {
+        return Ok(None);
+    };
+    let steps = RecipeStep::query_by_recipe(&mut tx, &recipe.uuid).await?;
+    let ingredients = RecipeIngredient::query_by_recipe(&mut tx, &recipe.uuid).await?;
+
+    tx.commit().await?;
+
+    Ok(Some(CookRecipe {
+        uuid: recipe.uuid,
+        steps: steps.len().try_into().unwrap_or(i16::MAX),
+        ingredients: ingredients
+            .into_iter()
+            .map(|ingredient| ingredient.ingredients)
+            .collect(),
+    }))
+}
@@ -3,9 +3,109 @@ use galvyn::core::re_exports::schemars::JsonSchema;
 use galvyn::core::re_exports::serde::Deserialize;
 use galvyn::core::re_exports::serde::Serialize;
 
+use crate::models::ingredients::IngredientUuid;
+use crate::models::recipes::RecipeUuid;
+use crate::models::shopping_lists::ShoppingListUuid;
+use crate::modules::cook_sessions::CookSessionUuid;
+use crate::modules::cook_sessions::CookTimerUuid;
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum WsServerMsg {
     RecipesChanged,
     TagsChanged,
     IngredientsChanged,
+    MealPlanChanged,
+    /// A shopping list has been created or deleted
+    ShoppingListsChanged,
+    /// An item of a shopping list has been added, changed, checked or removed
+    ShoppingListChanged {
+        list: ShoppingListUuid,
+    },
+    PantryChanged,
+    CollectionsChanged,
+    /// The household has been renamed or its members or invitations changed
+    HouseholdChanged,
+    /// The state of a cook session the client has joined changed
+    CookSessionChanged {
+        state: CookSessionState,
+    },
+}
+
+/// Messages sent from the client to the server.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum WsClientMsg {
+    /// Join the cook session of a recipe
+    ///
+    /// A new cook session is opened if nobody is cooking the recipe yet.
+    JoinCookSession { recipe: RecipeUuid },
+
+    /// Leave a cook session
+    LeaveCookSession { cook_session: CookSessionUuid },
+
+    /// Move the cook session to another step
+    SetCookStep {
+        cook_session: CookSessionUuid,
+        /// The index of the step
+        step: i16,
+    },
+
+    /// Check or uncheck an ingredient
+    CheckCookIngredient {
+        cook_session: CookSessionUuid,
+        ingredient: IngredientUuid,
+        checked: bool,
+    },
+
+    /// Start a new timer
+    StartCookTimer {
+        cook_session: CookSessionUuid,
+        /// Label shown to all cooks, e.g. "pasta"
+        label: String,
+        /// The duration of the timer in seconds
+        duration_secs: u32,
+    },
+
+    /// Stop a running timer
+    StopCookTimer {
+        cook_session: CookSessionUuid,
+        timer: CookTimerUuid,
+    },
+}
+
+/// The synchronized state of a cook session.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CookSessionState {
+    /// The identifier of the cook session
+    pub uuid: CookSessionUuid,
+
+    /// The recipe being cooked
+    pub recipe: RecipeUuid,
+
+    /// The number of sessions which have joined
+    pub participants: usize,
+
+    /// The index of the current step
+    pub current_step: i16,
+
+    /// The ingredients which have already been checked off
+    pub checked_ingredients: Vec<IngredientUuid>,
+
+    /// All running timers
+    pub timers: Vec<CookTimer>,
+}
+
+/// A timer running in a cook session.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CookTimer {
+    /// The identifier of the timer
+    pub uuid: CookTimerUuid,
+
+    /// Label of the timer
+    pub label: String,
+
+    /// The duration of the timer in seconds
+    pub duration_secs: u32,
+
+    /// The point in time the timer was started at as unix timestamp (in seconds)
+    pub started_at: i64,
 }
@@ -7,6 +7,7 @@ use std::error::Error;
 use std::net::IpAddr;
 use std::net::Ipv4Addr;
 use std::net::SocketAddr;
+use std::path::Path;
 
 use clap::Parser;
 use galvyn::Galvyn;
@@ -16,6 +17,7 @@ use galvyn::core::re_exports::rorm;
 use galvyn::rorm::Database;
 use galvyn::rorm::DatabaseConfiguration;
 use galvyn::tracing::opentelemetry::OpenTelemetrySetup;
+use tracing::info;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::Layer;
@@ -26,6 +28,9 @@ use crate::cli::Cli;
 use crate::cli::Command;
 use crate::config::DB;
 use crate::config::OTEL_EXPORTER_OTLP_ENDPOINT;
+use crate::models::foods::Food;
+use crate::models::ingredients::Ingredient;
+use crate::modules::jobs::JobQueue;
 use crate::modules::oidc::OpenIdConnect;
 use crate::modules::websockets::WebsocketManager;
 
@@ -34,6 +39,7 @@ mod config;
 mod http;
 mod models;
 mod modules;
+mod utils;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -72,8 +78,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 false,
                 None,
             )
-            .await?
+            .await?;
+            normalize_ingredient_names().await?
         }
+        Command::ImportFoods { dataset_dir } => import_foods(dataset_dir).await?,
+        Command::ReportDuplicateIngredients => report_duplicate_ingredients().await?,
         #[cfg(debug_assertions)]
         Command::MakeMigrations { migrations_dir } => make_migrations(migrations_dir)?,
     }
@@ -89,6 +98,7 @@ async fn start() -> Result<(), Box<dyn Error>> {
         )))
         .register_module::<WebsocketManager>(())
         .register_module::<OpenIdConnect>(())
+        .register_module::<JobQueue>(())
         .init_modules()
         .await?
         .add_routes(http::initialize())
@@ -98,6 +108,49 @@ async fn start() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+async fn import_foods(dataset_dir: String) -> Result<(), Box<dyn Error>> {
+    let foods = utils::fdc::read_dataset(Path::new(&dataset_dir))?;
+    info!(foods = foods.len(), "Read food dataset");
+
+    let db = Database::connect(DatabaseConfiguration::new(DB.clone())).await?;
+    let (inserted, updated) = Food::import(&db, foods).await?;
+    db.close().await;
+
+    info!(inserted, updated, "Imported foods");
+    Ok(())
+}
+
+async fn normalize_ingredient_names() -> Result<(), Box<dyn Error>> {
+    // The normalization can't be expressed in SQL, so it runs after the migrations
+    let db = Database::connect(DatabaseConfiguration::new(DB.clone())).await?;
+    let (ingredients, aliases) = Ingredient::backfill_normalized_names(&db).await?;
+    db.close().await;
+
+    info!(ingredients, aliases, "Normalized ingredient names");
+    Ok(())
+}
+
+async fn report_duplicate_ingredients() -> Result<(), Box<dyn Error>> {
+    let db = Database::connect(DatabaseConfiguration::new(DB.clone())).await?;
+    let duplicates = Ingredient::query_likely_duplicates(&db).await?;
+    db.close().await;
+
+    for (household, ingredients) in &duplicates {
+        let names: Vec<_> = ingredients
+            .iter()
+            .map(|ingredient| &*ingredient.name)
+            .collect();
+        let uuids: Vec<_> = ingredients
+            .iter()
+            .map(|ingredient| ingredient.uuid.get_inner())
+            .collect();
+        info!(household = %household.0, ?names, ?uuids, "Likely duplicate ingredients");
+    }
+
+    info!(groups = duplicates.len(), "Reported duplicate ingredients");
+    Ok(())
+}
+
 #[cfg(debug_assertions)]
 fn make_migrations(migrations_dir: String) -> Result<(), Box<dyn Error>> {
     use std::io::Write;
@@ -34,7 +34,7 @@ pub struct Account {
 }
 
 /// Wrapper type to give stronger typing to account identifiers.
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
 pub struct AccountUuid(Uuid);
 
 impl AccountUuid {
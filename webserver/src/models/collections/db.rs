@@ -0,0 +1,56 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::models::account::db::AccountModel;
+use crate::models::households::db::HouseholdModel;
+use crate::models::recipes::db::RecipeModel;
+
+/// Represents a curated collection of recipes like a cookbook.
+#[derive(Model)]
+#[rorm(rename = "collection")]
+pub struct CollectionModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// The title of the collection.
+    pub title: MaxStr<255>,
+
+    /// A description of the collection in markdown.
+    #[rorm(default = "")]
+    pub description: MaxStr<65535>,
+
+    /// The URL of a cover image.
+    pub cover_url: Option<MaxStr<2048>>,
+
+    /// A foreign key referencing the household owning the collection.
+    #[rorm(on_delete = "Cascade")]
+    pub household: ForeignModel<HouseholdModel>,
+
+    /// The account which created the collection.
+    #[rorm(on_delete = "Cascade")]
+    pub account: ForeignModel<AccountModel>,
+
+    pub created_at: OffsetDateTime,
+}
+
+/// Represents the membership of a recipe in a collection.
+#[derive(Model)]
+#[rorm(rename = "collection_recipe")]
+pub struct CollectionRecipeModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the collection.
+    #[rorm(on_delete = "Cascade")]
+    pub collection: ForeignModel<CollectionModel>,
+
+    /// A foreign key referencing the recipe in the collection.
+    #[rorm(on_delete = "Cascade")]
+    pub recipe: ForeignModel<RecipeModel>,
+
+    /// The position of the recipe in the collection.
+    pub index: i32,
+}
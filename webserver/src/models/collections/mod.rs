@@ -0,0 +1,279 @@
+//! Curated, ordered collections of recipes.
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::executor::All;
+use galvyn::rorm::db::sql::value::Value;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModelByField;
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::account::AccountUuid;
+use crate::models::collections::db::CollectionModel;
+use crate::models::collections::db::CollectionRecipeModel;
+use crate::models::households::HouseholdUuid;
+use crate::models::recipes::RecipeUuid;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of a collection of recipes.
+#[derive(Debug, Clone)]
+pub struct Collection {
+    /// Stable identifier for this collection.
+    pub uuid: CollectionUuid,
+
+    /// The title of the collection. Must be unique within the household.
+    pub title: MaxStr<255>,
+
+    /// A description of the collection in markdown.
+    pub description: MaxStr<65535>,
+
+    /// The URL of a cover image.
+    pub cover_url: Option<MaxStr<2048>>,
+
+    /// The account which created the collection.
+    pub account: AccountUuid,
+}
+
+/// Strongly typed UUID for collections.
+#[derive(Debug, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct CollectionUuid(pub Uuid);
+
+impl Collection {
+    /// Lists all collections of a household ordered by their title.
+    #[instrument(name = "Collection::query_all", skip(exe))]
+    pub async fn query_all(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, CollectionModel)
+            .condition(CollectionModel.household.equals(household.0))
+            .order_asc(CollectionModel.title)
+            .stream()
+            .map_ok(Collection::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Looks up a single collection by its UUID.
+    #[instrument(name = "Collection::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        uuid: &CollectionUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let collection = rorm::query(exe, CollectionModel)
+            .condition(and![
+                CollectionModel.uuid.equals(uuid.0),
+                CollectionModel.household.equals(household.0),
+            ])
+            .optional()
+            .await?;
+        Ok(collection.map(Self::from))
+    }
+
+    /// Looks up a single collection by its title.
+    #[instrument(name = "Collection::query_by_title", skip(exe))]
+    pub async fn query_by_title(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        title: &str,
+    ) -> anyhow::Result<Option<Self>> {
+        let collection = rorm::query(exe, CollectionModel)
+            .condition(and![
+                CollectionModel.title.equals(title),
+                CollectionModel.household.equals(household.0),
+            ])
+            .optional()
+            .await?;
+        Ok(collection.map(Self::from))
+    }
+
+    /// Lists all collections of a household containing a recipe.
+    #[instrument(name = "Collection::query_by_recipe", skip(exe))]
+    pub async fn query_by_recipe(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        recipe_uuid: &RecipeUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(
+            exe,
+            CollectionRecipeModel.collection.query_as(CollectionModel),
+        )
+        .condition(and![
+            CollectionRecipeModel.recipe.equals(recipe_uuid.0),
+            CollectionRecipeModel
+                .collection
+                .household
+                .equals(household.0),
+        ])
+        .order_asc(CollectionRecipeModel.collection.title)
+        .stream()
+        .map_ok(Collection::from)
+        .try_collect()
+        .await?;
+        Ok(result)
+    }
+
+    /// Creates a new, empty collection.
+    #[instrument(name = "Collection::create", skip(exe))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        title: MaxStr<255>,
+        description: MaxStr<65535>,
+        cover_url: Option<MaxStr<2048>>,
+        account: AccountUuid,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Self> {
+        let model = rorm::insert(exe, CollectionModel)
+            .single(&CollectionModel {
+                uuid: Uuid::new_v4(),
+                title,
+                description,
+                cover_url,
+                household: ForeignModelByField(household.0),
+                account: ForeignModelByField(account.get_inner()),
+                created_at: OffsetDateTime::now_utc(),
+            })
+            .await?;
+        Ok(Collection::from(model))
+    }
+
+    /// Update a collection's title, description and cover.
+    #[instrument(name = "Collection::update", skip(exe))]
+    pub async fn update(
+        &self,
+        exe: impl Executor<'_>,
+        title: MaxStr<255>,
+        description: MaxStr<65535>,
+        cover_url: Option<MaxStr<2048>>,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, CollectionModel)
+            .set(CollectionModel.title, title)
+            .set(CollectionModel.description, description)
+            .set(CollectionModel.cover_url, cover_url)
+            .condition(CollectionModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes the collection.
+    ///
+    /// The recipes in the collection are kept.
+    #[instrument(name = "Collection::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, CollectionModel)
+            .condition(CollectionModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Lists the recipes in the collection in their order.
+    #[instrument(name = "Collection::query_recipes", skip(exe))]
+    pub async fn query_recipes(&self, exe: impl Executor<'_>) -> anyhow::Result<Vec<RecipeUuid>> {
+        let result: Vec<_> = rorm::query(exe, CollectionRecipeModel.recipe)
+            .condition(CollectionRecipeModel.collection.equals(self.uuid.0))
+            .order_asc(CollectionRecipeModel.index)
+            .stream()
+            .map_ok(|recipe| RecipeUuid(recipe.0))
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Appends a recipe to the end of the collection.
+    ///
+    /// Returns `false` if the recipe is already in the collection.
+    #[instrument(name = "Collection::add_recipe", skip(exe))]
+    pub async fn add_recipe(
+        &self,
+        exe: impl Executor<'_>,
+        recipe_uuid: &RecipeUuid,
+    ) -> anyhow::Result<bool> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let last_index = rorm::query(guard.get_transaction(), CollectionRecipeModel.index.max())
+            .condition(CollectionRecipeModel.collection.equals(self.uuid.0))
+            .one()
+            .await?;
+
+        let sql = r#"INSERT INTO "collection_recipe" ("uuid", "collection", "recipe", "index") VALUES ($1, $2, $3, $4) ON CONFLICT ("collection", "recipe") DO NOTHING RETURNING "uuid""#;
+        let rows = guard
+            .get_transaction()
+            .execute::<All>(
+                sql.to_string(),
+                vec![
+                    Value::Uuid(Uuid::new_v4()),
+                    Value::Uuid(self.uuid.0),
+                    Value::Uuid(recipe_uuid.0),
+                    Value::I32(last_index.map_or(0, |index| index + 1)),
+                ],
+            )
+            .await?;
+
+        guard.commit().await?;
+        Ok(!rows.is_empty())
+    }
+
+    /// Removes a recipe from the collection.
+    #[instrument(name = "Collection::remove_recipe", skip(exe))]
+    pub async fn remove_recipe(
+        &self,
+        exe: impl Executor<'_>,
+        recipe_uuid: &RecipeUuid,
+    ) -> anyhow::Result<()> {
+        rorm::delete(exe, CollectionRecipeModel)
+            .condition(and![
+                CollectionRecipeModel.collection.equals(self.uuid.0),
+                CollectionRecipeModel.recipe.equals(recipe_uuid.0),
+            ])
+            .await?;
+        Ok(())
+    }
+
+    /// Changes the order of the recipes in the collection.
+    ///
+    /// `recipes` has to contain every recipe of the collection exactly once.
+    #[instrument(name = "Collection::reorder_recipes", skip(exe))]
+    pub async fn reorder_recipes(
+        &self,
+        exe: impl Executor<'_>,
+        recipes: &[RecipeUuid],
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        for (recipe_uuid, index) in recipes.iter().zip(0..) {
+            rorm::update(guard.get_transaction(), CollectionRecipeModel)
+                .set(CollectionRecipeModel.index, index)
+                .condition(and![
+                    CollectionRecipeModel.collection.equals(self.uuid.0),
+                    CollectionRecipeModel.recipe.equals(recipe_uuid.0),
+                ])
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+}
+
+impl From<CollectionModel> for Collection {
+    fn from(model: CollectionModel) -> Self {
+        Self {
+            uuid: CollectionUuid(model.uuid),
+            title: model.title,
+            description: model.description,
+            cover_url: model.cover_url,
+            account: AccountUuid::new_from_model(model.account),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::Model;
+use uuid::Uuid;
+
+/// Represents an entry of a food composition database.
+///
+/// All nutrient values are given per 100 g of the food.
+#[derive(Model)]
+#[rorm(rename = "food")]
+pub struct FoodModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// The id of the food in the USDA FoodData Central dataset.
+    #[rorm(unique)]
+    pub fdc_id: i64,
+
+    /// The description of the food.
+    pub name: MaxStr<255>,
+
+    /// The energy in kcal.
+    pub energy_kcal: Option<f64>,
+
+    /// The protein in g.
+    pub protein: Option<f64>,
+
+    /// The total fat in g.
+    pub fat: Option<f64>,
+
+    /// The carbohydrates in g.
+    pub carbohydrates: Option<f64>,
+
+    /// The dietary fiber in g.
+    pub fiber: Option<f64>,
+
+    /// The total sugars in g.
+    pub sugar: Option<f64>,
+}
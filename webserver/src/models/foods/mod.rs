@@ -0,0 +1,308 @@
+//! Food composition entries and nutrient calculations.
+
+use std::collections::HashMap;
+use std::ops::Add;
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::core::stuff::schema::GetPageRequest;
+use galvyn::rorm::and;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::foods::db::FoodModel;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of a food composition entry.
+#[derive(Debug, Clone)]
+pub struct Food {
+    /// Stable identifier for this food.
+    pub uuid: FoodUuid,
+
+    /// The id of the food in the USDA FoodData Central dataset.
+    pub fdc_id: i64,
+
+    /// The description of the food.
+    pub name: MaxStr<255>,
+
+    /// The nutrients per 100 g of the food.
+    ///
+    /// Nutrients missing in the dataset are `None`.
+    pub per_100g: Nutrients,
+}
+
+/// Strongly typed UUID for foods.
+#[derive(Debug, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct FoodUuid(Uuid);
+
+impl FoodUuid {
+    /// Creates an instance of "*FoodUuid*"
+    pub fn from_model(model: ForeignModel<FoodModel>) -> Self {
+        Self(model.0)
+    }
+    /// Gets underlying UUID
+    pub fn get_inner(&self) -> Uuid {
+        self.0
+    }
+}
+
+/// Nutrient amounts
+///
+/// Depending on the context, the values are given per 100 g, per recipe or per serving.
+/// A value is `None` if it is unknown, e.g. because it is missing in the dataset
+/// for one of the summed up foods.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Nutrients {
+    /// The energy in kcal.
+    pub energy_kcal: Option<f64>,
+
+    /// The protein in g.
+    pub protein: Option<f64>,
+
+    /// The total fat in g.
+    pub fat: Option<f64>,
+
+    /// The carbohydrates in g.
+    pub carbohydrates: Option<f64>,
+
+    /// The dietary fiber in g.
+    pub fiber: Option<f64>,
+
+    /// The total sugars in g.
+    pub sugar: Option<f64>,
+}
+
+impl Nutrients {
+    /// No nutrients at all, the start of a sum.
+    pub const ZERO: Self = Self {
+        energy_kcal: Some(0.0),
+        protein: Some(0.0),
+        fat: Some(0.0),
+        carbohydrates: Some(0.0),
+        fiber: Some(0.0),
+        sugar: Some(0.0),
+    };
+
+    /// Multiplies all nutrients by a factor.
+    pub fn scale(self, factor: f64) -> Self {
+        let scale = |value: Option<f64>| value.map(|value| value * factor);
+        Self {
+            energy_kcal: scale(self.energy_kcal),
+            protein: scale(self.protein),
+            fat: scale(self.fat),
+            carbohydrates: scale(self.carbohydrates),
+            fiber: scale(self.fiber),
+            sugar: scale(self.sugar),
+        }
+    }
+}
+
+impl Add for Nutrients {
+    type Output = Self;
+
+    /// Adds up the nutrients, a nutrient stays unknown if it is unknown in either summand.
+    fn add(self, rhs: Self) -> Self::Output {
+        let add = |lhs: Option<f64>, rhs: Option<f64>| Some(lhs? + rhs?);
+        Self {
+            energy_kcal: add(self.energy_kcal, rhs.energy_kcal),
+            protein: add(self.protein, rhs.protein),
+            fat: add(self.fat, rhs.fat),
+            carbohydrates: add(self.carbohydrates, rhs.carbohydrates),
+            fiber: add(self.fiber, rhs.fiber),
+            sugar: add(self.sugar, rhs.sugar),
+        }
+    }
+}
+
+/// A food read from a dataset which should be imported.
+#[derive(Debug, Clone)]
+pub struct ImportFood {
+    /// The id of the food in the USDA FoodData Central dataset.
+    pub fdc_id: i64,
+
+    /// The description of the food.
+    pub name: MaxStr<255>,
+
+    /// The energy in kcal per 100 g.
+    pub energy_kcal: Option<f64>,
+
+    /// The protein in g per 100 g.
+    pub protein: Option<f64>,
+
+    /// The total fat in g per 100 g.
+    pub fat: Option<f64>,
+
+    /// The carbohydrates in g per 100 g.
+    pub carbohydrates: Option<f64>,
+
+    /// The dietary fiber in g per 100 g.
+    pub fiber: Option<f64>,
+
+    /// The total sugars in g per 100 g.
+    pub sugar: Option<f64>,
+}
+
+impl Food {
+    /// Count all foods matching the optional name filter.
+    #[instrument(name = "Food::query_total", skip(exe))]
+    pub async fn query_total(
+        exe: impl Executor<'_>,
+        filter_name: Option<&str>,
+    ) -> anyhow::Result<i64> {
+        let condition = and![filter_name.map(|name| FoodModel.name.contains_ignore_case(name))];
+
+        Ok(rorm::query(exe, FoodModel.uuid.count())
+            .condition(condition)
+            .one()
+            .await?)
+    }
+
+    /// List foods with optional name filter and pagination.
+    #[instrument(name = "Food::query_all", skip(exe))]
+    pub async fn query_all(
+        exe: impl Executor<'_>,
+        page: &GetPageRequest,
+        filter_name: Option<&str>,
+    ) -> anyhow::Result<Vec<Self>> {
+        let condition = and![filter_name.map(|name| FoodModel.name.contains_ignore_case(name))];
+
+        let result: Vec<_> = rorm::query(exe, FoodModel)
+            .condition(condition)
+            .order_asc(FoodModel.name)
+            .limit(page.limit)
+            .offset(page.offset)
+            .stream()
+            .map_ok(Food::from)
+            .try_collect()
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Looks up a single food by its UUID.
+    #[instrument(name = "Food::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        uuid: &FoodUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let food = rorm::query(exe, FoodModel)
+            .condition(FoodModel.uuid.equals(uuid.0))
+            .optional()
+            .await?;
+        Ok(food.map(Self::from))
+    }
+
+    /// Looks up several foods by their UUIDs.
+    ///
+    /// Foods which don't exist are missing in the map.
+    #[instrument(name = "Food::query_by_uuids", skip(exe))]
+    pub async fn query_by_uuids(
+        exe: impl Executor<'_>,
+        uuids: &[FoodUuid],
+    ) -> anyhow::Result<HashMap<FoodUuid, Self>> {
+        if uuids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let result = rorm::query(exe, FoodModel)
+            .condition(FoodModel.uuid.r#in(uuids.iter().map(|uuid| uuid.0)))
+            .stream()
+            .map_ok(|model| (FoodUuid(model.uuid), Self::from(model)))
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Imports foods from a dataset.
+    ///
+    /// Foods which have already been imported before (identified by their `fdc_id`) are updated,
+    /// so ingredients linked to them keep their link.
+    ///
+    /// Returns the number of inserted and updated foods.
+    #[instrument(name = "Food::import", skip_all)]
+    pub async fn import(
+        exe: impl Executor<'_>,
+        foods: Vec<ImportFood>,
+    ) -> anyhow::Result<(usize, usize)> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let existing: HashMap<i64, Uuid> =
+            rorm::query(guard.get_transaction(), (FoodModel.fdc_id, FoodModel.uuid))
+                .stream()
+                .try_collect()
+                .await?;
+
+        let mut inserts = Vec::new();
+        let mut updated = 0;
+        for food in foods {
+            let model = FoodModel {
+                uuid: existing
+                    .get(&food.fdc_id)
+                    .copied()
+                    .unwrap_or_else(Uuid::new_v4),
+                fdc_id: food.fdc_id,
+                name: food.name,
+                energy_kcal: food.energy_kcal,
+                protein: food.protein,
+                fat: food.fat,
+                carbohydrates: food.carbohydrates,
+                fiber: food.fiber,
+                sugar: food.sugar,
+            };
+
+            if existing.contains_key(&model.fdc_id) {
+                rorm::update(guard.get_transaction(), FoodModel)
+                    .set(FoodModel.name, model.name)
+                    .set(FoodModel.energy_kcal, model.energy_kcal)
+                    .set(FoodModel.protein, model.protein)
+                    .set(FoodModel.fat, model.fat)
+                    .set(FoodModel.carbohydrates, model.carbohydrates)
+                    .set(FoodModel.fiber, model.fiber)
+                    .set(FoodModel.sugar, model.sugar)
+                    .condition(FoodModel.uuid.equals(model.uuid))
+                    .await?;
+                updated += 1;
+            } else {
+                inserts.push(model);
+            }
+        }
+
+        let inserted = inserts.len();
+        for chunk in inserts.chunks(1000) {
+            rorm::insert(guard.get_transaction(), FoodModel)
+                .return_nothing()
+                .bulk(chunk)
+                .await?;
+        }
+
+        guard.commit().await?;
+
+        Ok((inserted, updated))
+    }
+}
+
+impl From<FoodModel> for Food {
+    fn from(model: FoodModel) -> Self {
+        Self {
+            uuid: FoodUuid(model.uuid),
+            fdc_id: model.fdc_id,
+            name: model.name,
+            per_100g: Nutrients {
+                energy_kcal: model.energy_kcal,
+                protein: model.protein,
+                fat: model.fat,
+                carbohydrates: model.carbohydrates,
+                fiber: model.fiber,
+                sugar: model.sugar,
+            },
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::models::account::db::AccountModel;
+
+/// Represents a household sharing recipes, tags and ingredients.
+#[derive(Model)]
+#[rorm(rename = "household")]
+pub struct HouseholdModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// The name of the household.
+    pub name: MaxStr<255>,
+
+    /// The region the household lives in, used to look up seasonal ingredients.
+    pub region: Option<MaxStr<255>>,
+
+    pub created_at: OffsetDateTime,
+}
+
+/// Represents the membership of an account in a household.
+#[derive(Model)]
+#[rorm(rename = "household_member")]
+pub struct HouseholdMemberModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the household.
+    #[rorm(on_delete = "Cascade")]
+    pub household: ForeignModel<HouseholdModel>,
+
+    /// A foreign key referencing the member.
+    ///
+    /// Every account is member of exactly one household.
+    #[rorm(on_delete = "Cascade", unique)]
+    pub account: ForeignModel<AccountModel>,
+}
+
+/// Represents an invitation to join a household.
+#[derive(Model)]
+#[rorm(rename = "household_invitation")]
+pub struct HouseholdInvitationModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the household to join.
+    #[rorm(on_delete = "Cascade")]
+    pub household: ForeignModel<HouseholdModel>,
+
+    /// A foreign key referencing the member who created the invitation.
+    #[rorm(on_delete = "Cascade")]
+    pub invited_by: ForeignModel<AccountModel>,
+
+    /// The point in time after which the invitation can't be accepted anymore.
+    pub expires_at: OffsetDateTime,
+}
@@ -0,0 +1,39 @@
+use galvyn::core::re_exports::axum::extract::FromRequestParts;
+use galvyn::core::re_exports::axum::http::request::Parts;
+use galvyn::core::stuff::api_error::ApiError;
+use galvyn::core::Module;
+use galvyn::rorm::Database;
+
+use crate::models::account::Account;
+use crate::models::households::Household;
+
+impl<S> FromRequestParts<S> for Household
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    /// Retrieves the household of the authenticated user.
+    ///
+    /// Every account gets a household of its own when it's created or removed from its household.
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(CachedHousehold(household)) = parts.extensions.get() {
+            return Ok(household.clone());
+        }
+
+        let account = Account::from_request_parts(parts, state).await?;
+
+        let household = Household::query_by_account(Database::global(), &account.uuid)
+            .await?
+            .ok_or(ApiError::server_error(
+                "Account is not member of any household",
+            ))?;
+
+        parts.extensions.insert(CachedHousehold(household.clone()));
+
+        Ok(household)
+    }
+}
+
+#[derive(Clone)]
+struct CachedHousehold(Household);
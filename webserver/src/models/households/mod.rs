@@ -0,0 +1,325 @@
+//! Households sharing recipes, tags and ingredients between their members.
+//!
+//! Every account is member of exactly one household, all data is scoped to it.
+pub(in crate::models) mod db;
+mod extractor;
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::prelude::ForeignModelByField;
+use time::Duration;
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::account::AccountUuid;
+use crate::models::households::db::HouseholdInvitationModel;
+use crate::models::households::db::HouseholdMemberModel;
+use crate::models::households::db::HouseholdModel;
+use crate::models::recipes::db::RecipeModel;
+
+/// How long an invitation can be accepted
+const INVITATION_VALIDITY: Duration = Duration::days(7);
+
+/// Domain representation of a household.
+#[derive(Debug, Clone)]
+pub struct Household {
+    /// Stable identifier for this household.
+    pub uuid: HouseholdUuid,
+
+    /// The name of the household.
+    pub name: MaxStr<255>,
+
+    /// The region the household lives in.
+    pub region: Option<MaxStr<255>>,
+}
+
+/// Strongly typed UUID for households.
+#[derive(Debug, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct HouseholdUuid(pub Uuid);
+
+impl HouseholdUuid {
+    /// Creates an instance of "*HouseholdUuid*"
+    pub fn from_model(model: ForeignModel<HouseholdModel>) -> Self {
+        Self(model.0)
+    }
+}
+
+/// Domain representation of an invitation to join a household.
+#[derive(Debug, Clone)]
+pub struct HouseholdInvitation {
+    /// Stable identifier for this invitation, shared with the invited person.
+    pub uuid: HouseholdInvitationUuid,
+
+    /// The household to join.
+    pub household: HouseholdUuid,
+
+    /// The member who created the invitation.
+    pub invited_by: AccountUuid,
+
+    /// The point in time after which the invitation can't be accepted anymore.
+    pub expires_at: OffsetDateTime,
+}
+
+/// Strongly typed UUID for household invitations.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct HouseholdInvitationUuid(pub Uuid);
+
+impl Household {
+    /// Looks up a single household by its UUID.
+    #[instrument(name = "Household::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        uuid: &HouseholdUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let household = rorm::query(exe, HouseholdModel)
+            .condition(HouseholdModel.uuid.equals(uuid.0))
+            .optional()
+            .await?;
+        Ok(household.map(Self::from))
+    }
+
+    /// Looks up the household an account is member of.
+    #[instrument(name = "Household::query_by_account", skip(exe))]
+    pub async fn query_by_account(
+        exe: impl Executor<'_>,
+        account_uuid: &AccountUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let household = rorm::query(exe, HouseholdMemberModel.household.query_as(HouseholdModel))
+            .condition(
+                HouseholdMemberModel
+                    .account
+                    .equals(account_uuid.get_inner()),
+            )
+            .optional()
+            .await?;
+        Ok(household.map(Self::from))
+    }
+
+    /// Creates a new household with a single member.
+    ///
+    /// The account must not be member of another household.
+    #[instrument(name = "Household::create", skip(exe))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        name: MaxStr<255>,
+        account_uuid: &AccountUuid,
+    ) -> anyhow::Result<Self> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let model = rorm::insert(guard.get_transaction(), HouseholdModel)
+            .single(&HouseholdModel {
+                uuid: Uuid::new_v4(),
+                name,
+                region: None,
+                created_at: OffsetDateTime::now_utc(),
+            })
+            .await?;
+
+        rorm::insert(guard.get_transaction(), HouseholdMemberModel)
+            .return_nothing()
+            .single(&HouseholdMemberModel {
+                uuid: Uuid::new_v4(),
+                household: ForeignModelByField(model.uuid),
+                account: ForeignModelByField(account_uuid.get_inner()),
+            })
+            .await?;
+
+        guard.commit().await?;
+        Ok(Household::from(model))
+    }
+
+    /// Update a household's name.
+    #[instrument(name = "Household::update", skip(exe))]
+    pub async fn update(&self, exe: impl Executor<'_>, name: MaxStr<255>) -> anyhow::Result<()> {
+        rorm::update(exe, HouseholdModel)
+            .set(HouseholdModel.name, name)
+            .condition(HouseholdModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Set or clear the region of a household.
+    #[instrument(name = "Household::set_region", skip(exe))]
+    pub async fn set_region(
+        &self,
+        exe: impl Executor<'_>,
+        region: Option<MaxStr<255>>,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, HouseholdModel)
+            .set(HouseholdModel.region, region)
+            .condition(HouseholdModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes the household with all its recipes, tags and ingredients.
+    #[instrument(name = "Household::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, HouseholdModel)
+            .condition(HouseholdModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Lists the members of the household.
+    #[instrument(name = "Household::query_members", skip(exe))]
+    pub async fn query_members(&self, exe: impl Executor<'_>) -> anyhow::Result<Vec<AccountUuid>> {
+        let result: Vec<_> = rorm::query(exe, HouseholdMemberModel.account)
+            .condition(HouseholdMemberModel.household.equals(self.uuid.0))
+            .stream()
+            .map_ok(AccountUuid::new_from_model)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Checks whether the household owns any recipes.
+    #[instrument(name = "Household::has_recipes", skip(exe))]
+    pub async fn has_recipes(&self, exe: impl Executor<'_>) -> anyhow::Result<bool> {
+        let count = rorm::query(exe, RecipeModel.uuid.count())
+            .condition(RecipeModel.household.equals(self.uuid.0))
+            .one()
+            .await?;
+        Ok(count > 0)
+    }
+
+    /// Makes an account member of this household, leaving its previous household.
+    #[instrument(name = "Household::add_member", skip(exe))]
+    pub async fn add_member(
+        &self,
+        exe: impl Executor<'_>,
+        account_uuid: &AccountUuid,
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        Self::remove_member(guard.get_transaction(), account_uuid).await?;
+
+        rorm::insert(guard.get_transaction(), HouseholdMemberModel)
+            .return_nothing()
+            .single(&HouseholdMemberModel {
+                uuid: Uuid::new_v4(),
+                household: ForeignModelByField(self.uuid.0),
+                account: ForeignModelByField(account_uuid.get_inner()),
+            })
+            .await?;
+
+        guard.commit().await?;
+        Ok(())
+    }
+
+    /// Removes an account from its household.
+    ///
+    /// The account has to be added to another household or get a new one of its own afterwards.
+    #[instrument(name = "Household::remove_member", skip(exe))]
+    pub async fn remove_member(
+        exe: impl Executor<'_>,
+        account_uuid: &AccountUuid,
+    ) -> anyhow::Result<()> {
+        rorm::delete(exe, HouseholdMemberModel)
+            .condition(
+                HouseholdMemberModel
+                    .account
+                    .equals(account_uuid.get_inner()),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+impl HouseholdInvitation {
+    /// Lists the invitations of a household which haven't expired yet.
+    #[instrument(name = "HouseholdInvitation::query_by_household", skip(exe))]
+    pub async fn query_by_household(
+        exe: impl Executor<'_>,
+        household_uuid: &HouseholdUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, HouseholdInvitationModel)
+            .condition(and![
+                HouseholdInvitationModel.household.equals(household_uuid.0),
+                HouseholdInvitationModel
+                    .expires_at
+                    .greater_than(OffsetDateTime::now_utc()),
+            ])
+            .order_asc(HouseholdInvitationModel.expires_at)
+            .stream()
+            .map_ok(HouseholdInvitation::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Looks up a single invitation by its UUID if it hasn't expired yet.
+    #[instrument(name = "HouseholdInvitation::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        uuid: &HouseholdInvitationUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let invitation = rorm::query(exe, HouseholdInvitationModel)
+            .condition(and![
+                HouseholdInvitationModel.uuid.equals(uuid.0),
+                HouseholdInvitationModel
+                    .expires_at
+                    .greater_than(OffsetDateTime::now_utc()),
+            ])
+            .optional()
+            .await?;
+        Ok(invitation.map(Self::from))
+    }
+
+    /// Creates a new invitation which can be accepted for a week.
+    #[instrument(name = "HouseholdInvitation::create", skip(exe))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        household_uuid: &HouseholdUuid,
+        invited_by: &AccountUuid,
+    ) -> anyhow::Result<Self> {
+        let model = rorm::insert(exe, HouseholdInvitationModel)
+            .single(&HouseholdInvitationModel {
+                uuid: Uuid::new_v4(),
+                household: ForeignModelByField(household_uuid.0),
+                invited_by: ForeignModelByField(invited_by.get_inner()),
+                expires_at: OffsetDateTime::now_utc() + INVITATION_VALIDITY,
+            })
+            .await?;
+        Ok(HouseholdInvitation::from(model))
+    }
+
+    /// Deletes the invitation.
+    #[instrument(name = "HouseholdInvitation::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, HouseholdInvitationModel)
+            .condition(HouseholdInvitationModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+}
+
+impl From<HouseholdModel> for Household {
+    fn from(model: HouseholdModel) -> Self {
+        Self {
+            uuid: HouseholdUuid(model.uuid),
+            name: model.name,
+            region: model.region,
+        }
+    }
+}
+
+impl From<HouseholdInvitationModel> for HouseholdInvitation {
+    fn from(model: HouseholdInvitationModel) -> Self {
+        Self {
+            uuid: HouseholdInvitationUuid(model.uuid),
+            household: HouseholdUuid::from_model(model.household),
+            invited_by: AccountUuid::new_from_model(model.invited_by),
+            expires_at: model.expires_at,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use uuid::Uuid;
+
+use crate::models::ingredient_allergens::Allergen;
+use crate::models::ingredients::db::IngredientModel;
+
+/// Represents an allergen contained in an ingredient.
+#[derive(Model)]
+#[rorm(rename = "ingredient_allergen")]
+pub struct IngredientAllergenModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the ingredient containing the allergen.
+    #[rorm(on_delete = "Cascade")]
+    pub ingredient: ForeignModel<IngredientModel>,
+
+    /// The contained allergen.
+    pub allergen: Allergen,
+}
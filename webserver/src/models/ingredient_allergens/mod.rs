@@ -0,0 +1,197 @@
+//! Allergens contained in ingredients.
+
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::prelude::ForeignModelByField;
+use galvyn::rorm::DbEnum;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredient_allergens::db::IngredientAllergenModel;
+use crate::models::ingredients::IngredientUuid;
+
+pub(in crate::models) mod db;
+
+/// The 14 allergens which have to be declared in the EU (Regulation (EU) No 1169/2011, Annex II).
+#[derive(
+    DbEnum,
+    Debug,
+    Copy,
+    Clone,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    PartialEq,
+    PartialOrd,
+    Eq,
+    Ord,
+)]
+pub enum Allergen {
+    /// Wheat, rye, barley, oats, spelt, kamut and their hybridised strains
+    CerealsContainingGluten = 0,
+    Crustaceans = 1,
+    Eggs = 2,
+    Fish = 3,
+    Peanuts = 4,
+    Soybeans = 5,
+    /// Milk including lactose
+    Milk = 6,
+    /// Tree nuts like almonds, hazelnuts, walnuts, cashews, pecans, pistachios, ...
+    Nuts = 7,
+    Celery = 8,
+    Mustard = 9,
+    SesameSeeds = 10,
+    /// Sulphur dioxide and sulphites above 10 mg/kg
+    Sulphites = 11,
+    Lupin = 12,
+    Molluscs = 13,
+}
+
+/// Helpers for the allergens of ingredients.
+pub struct IngredientAllergen;
+
+impl IngredientAllergen {
+    /// Lists the allergens of an ingredient.
+    #[instrument(name = "IngredientAllergen::query_by_ingredient", skip(exe))]
+    pub async fn query_by_ingredient(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+    ) -> anyhow::Result<Vec<Allergen>> {
+        let mut allergens: Vec<_> = rorm::query(exe, IngredientAllergenModel.allergen)
+            .condition(
+                IngredientAllergenModel
+                    .ingredient
+                    .equals(ingredient_uuid.get_inner()),
+            )
+            .stream()
+            .try_collect()
+            .await?;
+        allergens.sort();
+        Ok(allergens)
+    }
+
+    /// Lists the allergens of several ingredients.
+    ///
+    /// Ingredients without allergens are missing in the map.
+    #[instrument(name = "IngredientAllergen::query_by_ingredients", skip(exe))]
+    pub async fn query_by_ingredients(
+        exe: impl Executor<'_>,
+        ingredients: &[IngredientUuid],
+    ) -> anyhow::Result<HashMap<IngredientUuid, Vec<Allergen>>> {
+        let mut result = HashMap::<_, Vec<_>>::new();
+        if ingredients.is_empty() {
+            return Ok(result);
+        }
+
+        let mut stream = rorm::query(
+            exe,
+            (
+                IngredientAllergenModel.ingredient,
+                IngredientAllergenModel.allergen,
+            ),
+        )
+        .condition(
+            IngredientAllergenModel
+                .ingredient
+                .r#in(ingredients.iter().map(IngredientUuid::get_inner)),
+        )
+        .stream();
+        while let Some((ingredient, allergen)) = stream.try_next().await? {
+            result
+                .entry(IngredientUuid::from_model(ingredient))
+                .or_default()
+                .push(allergen);
+        }
+
+        for allergens in result.values_mut() {
+            allergens.sort();
+        }
+        Ok(result)
+    }
+
+    /// Lists the allergens of all ingredients of a household.
+    ///
+    /// Ingredients without allergens are missing in the map.
+    #[instrument(name = "IngredientAllergen::query_all", skip(exe))]
+    pub async fn query_all(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<HashMap<IngredientUuid, Vec<Allergen>>> {
+        let mut result = HashMap::<_, Vec<_>>::new();
+
+        let mut stream = rorm::query(
+            exe,
+            (
+                IngredientAllergenModel.ingredient,
+                IngredientAllergenModel.allergen,
+            ),
+        )
+        .condition(
+            IngredientAllergenModel
+                .ingredient
+                .household
+                .equals(household.0),
+        )
+        .stream();
+        while let Some((ingredient, allergen)) = stream.try_next().await? {
+            result
+                .entry(IngredientUuid::from_model(ingredient))
+                .or_default()
+                .push(allergen);
+        }
+
+        for allergens in result.values_mut() {
+            allergens.sort();
+        }
+        Ok(result)
+    }
+
+    /// Replaces the allergens of an ingredient.
+    #[instrument(name = "IngredientAllergen::set_for_ingredient", skip(exe))]
+    pub async fn set_for_ingredient(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+        allergens: &[Allergen],
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        rorm::delete(guard.get_transaction(), IngredientAllergenModel)
+            .condition(
+                IngredientAllergenModel
+                    .ingredient
+                    .equals(ingredient_uuid.get_inner()),
+            )
+            .await?;
+
+        let mut allergens = allergens.to_vec();
+        allergens.sort();
+        allergens.dedup();
+
+        if !allergens.is_empty() {
+            rorm::insert(guard.get_transaction(), IngredientAllergenModel)
+                .return_nothing()
+                .bulk(
+                    allergens
+                        .into_iter()
+                        .map(|allergen| IngredientAllergenModel {
+                            uuid: Uuid::new_v4(),
+                            ingredient: ForeignModelByField(ingredient_uuid.get_inner()),
+                            allergen,
+                        }),
+                )
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+}
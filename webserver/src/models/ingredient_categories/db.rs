@@ -0,0 +1,26 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use uuid::Uuid;
+
+use crate::models::households::db::HouseholdModel;
+
+/// Represents a category ingredients are grouped by, e.g. produce or dairy.
+#[derive(Model)]
+#[rorm(rename = "ingredient_category")]
+pub struct IngredientCategoryModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the household which created the category.
+    ///
+    /// The default categories available to every household have no household.
+    #[rorm(on_delete = "Cascade")]
+    pub household: Option<ForeignModel<HouseholdModel>>,
+
+    /// The name of the category.
+    pub name: MaxStr<255>,
+
+    /// The position of the category when ingredients are grouped, lowest first.
+    pub position: i32,
+}
@@ -0,0 +1,172 @@
+//! Categories ingredients are grouped by when shopping or browsing.
+//!
+//! Every household can use a default set of categories and create its own ones.
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::or;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::prelude::ForeignModelByField;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredient_categories::db::IngredientCategoryModel;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of an ingredient category.
+#[derive(Debug, Clone)]
+pub struct IngredientCategory {
+    /// Stable identifier for this category.
+    pub uuid: IngredientCategoryUuid,
+
+    /// The name of the category.
+    pub name: MaxStr<255>,
+
+    /// The position of the category when ingredients are grouped, lowest first.
+    pub position: i32,
+
+    /// Whether the category has been created by the household.
+    ///
+    /// Default categories can't be changed.
+    pub custom: bool,
+}
+
+/// Strongly typed UUID for ingredient categories.
+#[derive(Debug, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct IngredientCategoryUuid(pub Uuid);
+
+impl IngredientCategoryUuid {
+    /// Creates an instance of "*IngredientCategoryUuid*"
+    pub fn from_model(model: ForeignModel<IngredientCategoryModel>) -> Self {
+        Self(model.0)
+    }
+}
+
+/// Builds the condition matching the default categories and the ones of a household.
+macro_rules! available_condition {
+    ($household:expr) => {
+        or![
+            IngredientCategoryModel.household.is_none(),
+            IngredientCategoryModel.household.equals(Some($household.0)),
+        ]
+    };
+}
+
+impl IngredientCategory {
+    /// Lists the default categories and the ones of a household ordered by position.
+    #[instrument(name = "IngredientCategory::query_all", skip(exe))]
+    pub async fn query_all(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, IngredientCategoryModel)
+            .condition(available_condition!(household))
+            .order_asc(IngredientCategoryModel.position)
+            .order_asc(IngredientCategoryModel.name)
+            .stream()
+            .map_ok(IngredientCategory::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Looks up a category available to a household by its UUID.
+    #[instrument(name = "IngredientCategory::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        uuid: &IngredientCategoryUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let category = rorm::query(exe, IngredientCategoryModel)
+            .condition(and![
+                IngredientCategoryModel.uuid.equals(uuid.0),
+                available_condition!(household),
+            ])
+            .optional()
+            .await?;
+        Ok(category.map(Self::from))
+    }
+
+    /// Looks up a category available to a household by its name.
+    #[instrument(name = "IngredientCategory::query_by_name", skip(exe))]
+    pub async fn query_by_name(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        name: &str,
+    ) -> anyhow::Result<Option<Self>> {
+        let category = rorm::query(exe, IngredientCategoryModel)
+            .condition(and![
+                IngredientCategoryModel.name.equals(name),
+                available_condition!(household),
+            ])
+            .optional()
+            .await?;
+        Ok(category.map(Self::from))
+    }
+
+    /// Creates a custom category for a household.
+    #[instrument(name = "IngredientCategory::create", skip(exe))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        name: MaxStr<255>,
+        position: i32,
+    ) -> anyhow::Result<Self> {
+        let model = rorm::insert(exe, IngredientCategoryModel)
+            .single(&IngredientCategoryModel {
+                uuid: Uuid::new_v4(),
+                household: Some(ForeignModelByField(household.0)),
+                name,
+                position,
+            })
+            .await?;
+        Ok(IngredientCategory::from(model))
+    }
+
+    /// Updates the name and position of a custom category.
+    #[instrument(name = "IngredientCategory::update", skip(exe))]
+    pub async fn update(
+        &self,
+        exe: impl Executor<'_>,
+        name: MaxStr<255>,
+        position: i32,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, IngredientCategoryModel)
+            .set(IngredientCategoryModel.name, name)
+            .set(IngredientCategoryModel.position, position)
+            .condition(IngredientCategoryModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes a custom category.
+    ///
+    /// Its ingredients become uncategorized.
+    #[instrument(name = "IngredientCategory::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, IngredientCategoryModel)
+            .condition(IngredientCategoryModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+}
+
+impl From<IngredientCategoryModel> for IngredientCategory {
+    fn from(model: IngredientCategoryModel) -> Self {
+        Self {
+            uuid: IngredientCategoryUuid(model.uuid),
+            name: model.name,
+            position: model.position,
+            custom: model.household.is_some(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use time::Date;
+use uuid::Uuid;
+
+use crate::models::ingredients::db::IngredientModel;
+use crate::models::ingredients::Units;
+
+/// Represents the price an ingredient was bought for.
+#[derive(Model)]
+#[rorm(rename = "ingredient_price")]
+pub struct IngredientPriceModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the priced ingredient.
+    #[rorm(on_delete = "Cascade")]
+    pub ingredient: ForeignModel<IngredientModel>,
+
+    /// The price in the smallest unit of the currency (e.g. cents).
+    pub price: i64,
+
+    /// The quantity the price refers to.
+    pub amount: i64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// The store the ingredient was bought at.
+    pub store: Option<MaxStr<255>>,
+
+    /// The day the price was recorded.
+    pub date: Date,
+}
@@ -0,0 +1,183 @@
+//! Prices ingredients were bought for.
+
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModelByField;
+use time::Date;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredient_prices::db::IngredientPriceModel;
+use crate::models::ingredients::IngredientMeasures;
+use crate::models::ingredients::IngredientUuid;
+use crate::models::ingredients::Units;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of a recorded ingredient price.
+#[derive(Debug, Clone)]
+pub struct IngredientPrice {
+    /// Stable identifier for this price.
+    pub uuid: IngredientPriceUuid,
+
+    /// The priced ingredient.
+    pub ingredient: IngredientUuid,
+
+    /// The price in the smallest unit of the currency (e.g. cents).
+    pub price: i64,
+
+    /// The quantity the price refers to.
+    pub amount: i64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// The store the ingredient was bought at.
+    pub store: Option<MaxStr<255>>,
+
+    /// The day the price was recorded.
+    pub date: Date,
+}
+
+/// Strongly typed UUID for ingredient prices.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct IngredientPriceUuid(pub Uuid);
+
+impl IngredientPrice {
+    /// Lists all prices of an ingredient, most recent first.
+    #[instrument(name = "IngredientPrice::query_by_ingredient", skip(exe))]
+    pub async fn query_by_ingredient(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, IngredientPriceModel)
+            .condition(
+                IngredientPriceModel
+                    .ingredient
+                    .equals(ingredient_uuid.get_inner()),
+            )
+            .order_desc(IngredientPriceModel.date)
+            .stream()
+            .map_ok(IngredientPrice::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Lists all prices of several ingredients, most recent first.
+    ///
+    /// Ingredients without prices are missing in the map.
+    #[instrument(name = "IngredientPrice::query_by_ingredients", skip(exe))]
+    pub async fn query_by_ingredients(
+        exe: impl Executor<'_>,
+        ingredients: &[IngredientUuid],
+    ) -> anyhow::Result<HashMap<IngredientUuid, Vec<Self>>> {
+        let mut result = HashMap::<_, Vec<_>>::new();
+        if ingredients.is_empty() {
+            return Ok(result);
+        }
+
+        let mut stream = rorm::query(exe, IngredientPriceModel)
+            .condition(
+                IngredientPriceModel
+                    .ingredient
+                    .r#in(ingredients.iter().map(IngredientUuid::get_inner)),
+            )
+            .order_desc(IngredientPriceModel.date)
+            .stream();
+        while let Some(model) = stream.try_next().await? {
+            let price = IngredientPrice::from(model);
+            result.entry(price.ingredient).or_default().push(price);
+        }
+        Ok(result)
+    }
+
+    /// Looks up a single price by its UUID.
+    #[instrument(name = "IngredientPrice::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        uuid: &IngredientPriceUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let price = rorm::query(exe, IngredientPriceModel)
+            .condition(and![
+                IngredientPriceModel.uuid.equals(uuid.0),
+                IngredientPriceModel
+                    .ingredient
+                    .household
+                    .equals(household.0),
+            ])
+            .optional()
+            .await?;
+        Ok(price.map(Self::from))
+    }
+
+    /// Records a new price for an ingredient.
+    #[instrument(name = "IngredientPrice::create", skip(exe))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+        price: i64,
+        amount: i64,
+        unit: Units,
+        store: Option<MaxStr<255>>,
+        date: Date,
+    ) -> anyhow::Result<Self> {
+        let model = rorm::insert(exe, IngredientPriceModel)
+            .single(&IngredientPriceModel {
+                uuid: Uuid::new_v4(),
+                ingredient: ForeignModelByField(ingredient_uuid.get_inner()),
+                price,
+                amount,
+                unit,
+                store,
+                date,
+            })
+            .await?;
+        Ok(IngredientPrice::from(model))
+    }
+
+    /// Deletes the price.
+    #[instrument(name = "IngredientPrice::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, IngredientPriceModel)
+            .condition(IngredientPriceModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Estimates the cost of a quantity of the ingredient based on this price.
+    ///
+    /// Returns `None` if the unit can't be converted to the unit of the price.
+    pub fn estimate(&self, amount: f64, unit: Units, measures: IngredientMeasures) -> Option<f64> {
+        if self.amount <= 0 {
+            return None;
+        }
+        let amount = unit.convert(amount, self.unit, measures)?;
+        Some(amount / self.amount as f64 * self.price as f64)
+    }
+}
+
+impl From<IngredientPriceModel> for IngredientPrice {
+    fn from(model: IngredientPriceModel) -> Self {
+        Self {
+            uuid: IngredientPriceUuid(model.uuid),
+            ingredient: IngredientUuid::from_model(model.ingredient),
+            price: model.price,
+            amount: model.amount,
+            unit: model.unit,
+            store: model.store,
+            date: model.date,
+        }
+    }
+}
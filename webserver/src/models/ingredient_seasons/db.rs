@@ -0,0 +1,24 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use uuid::Uuid;
+
+use crate::models::ingredients::db::IngredientModel;
+
+/// Represents a month an ingredient is in season in a region.
+#[derive(Model)]
+#[rorm(rename = "ingredient_season")]
+pub struct IngredientSeasonModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the seasonal ingredient.
+    #[rorm(on_delete = "Cascade")]
+    pub ingredient: ForeignModel<IngredientModel>,
+
+    /// The region the season applies to.
+    pub region: MaxStr<255>,
+
+    /// The month from 1 (January) to 12 (December).
+    pub month: i16,
+}
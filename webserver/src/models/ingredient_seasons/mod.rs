@@ -0,0 +1,158 @@
+//! The months ingredients are in season.
+//!
+//! Seasons differ between regions, so they are stored per region
+//! and looked up with the region of the household.
+
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::rorm::and;
+use galvyn::rorm::db::executor::All;
+use galvyn::rorm::db::sql::value::Value;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModelByField;
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::ingredient_seasons::db::IngredientSeasonModel;
+use crate::models::ingredients::IngredientUuid;
+use crate::models::recipes::RecipeUuid;
+
+pub(in crate::models) mod db;
+
+/// Returns the current month from 1 (January) to 12 (December).
+pub fn current_month() -> i16 {
+    i16::from(u8::from(OffsetDateTime::now_utc().month()))
+}
+
+/// The months an ingredient is in season in a region.
+#[derive(Debug, Clone)]
+pub struct IngredientSeason {
+    /// The region the season applies to.
+    pub region: MaxStr<255>,
+
+    /// The months from 1 (January) to 12 (December) in ascending order.
+    pub months: Vec<i16>,
+}
+
+impl IngredientSeason {
+    /// Lists the seasons of an ingredient in all regions ordered by region.
+    #[instrument(name = "IngredientSeason::query_by_ingredient", skip(exe))]
+    pub async fn query_by_ingredient(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut result: Vec<Self> = Vec::new();
+
+        let mut stream = rorm::query(
+            exe,
+            (IngredientSeasonModel.region, IngredientSeasonModel.month),
+        )
+        .condition(
+            IngredientSeasonModel
+                .ingredient
+                .equals(ingredient_uuid.get_inner()),
+        )
+        .order_asc(IngredientSeasonModel.region)
+        .order_asc(IngredientSeasonModel.month)
+        .stream();
+        while let Some((region, month)) = stream.try_next().await? {
+            match result.last_mut() {
+                Some(season) if season.region == region => season.months.push(month),
+                _ => result.push(Self {
+                    region,
+                    months: vec![month],
+                }),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Replaces the months an ingredient is in season in a region.
+    ///
+    /// No months remove the season of the region.
+    #[instrument(name = "IngredientSeason::set_for_ingredient", skip(exe))]
+    pub async fn set_for_ingredient(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+        region: MaxStr<255>,
+        months: &[i16],
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        rorm::delete(guard.get_transaction(), IngredientSeasonModel)
+            .condition(and![
+                IngredientSeasonModel
+                    .ingredient
+                    .equals(ingredient_uuid.get_inner()),
+                IngredientSeasonModel.region.equals(&region),
+            ])
+            .await?;
+
+        let mut months = months.to_vec();
+        months.sort();
+        months.dedup();
+
+        if !months.is_empty() {
+            rorm::insert(guard.get_transaction(), IngredientSeasonModel)
+                .return_nothing()
+                .bulk(months.into_iter().map(|month| IngredientSeasonModel {
+                    uuid: Uuid::new_v4(),
+                    ingredient: ForeignModelByField(ingredient_uuid.get_inner()),
+                    region: region.clone(),
+                    month,
+                }))
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+
+    /// Calculates how seasonal recipes are in a region and month.
+    ///
+    /// The score is the share of the recipe's ingredients with a season in the region
+    /// which are in season in the month, from 0 to 1.
+    /// Ingredients without a season (e.g. salt) are available all year and don't count.
+    /// Recipes without any seasonal ingredient are missing in the map.
+    ///
+    /// If `recipe` is given, only that recipe is scored.
+    #[instrument(name = "IngredientSeason::query_recipe_scores", skip(exe))]
+    pub async fn query_recipe_scores(
+        exe: impl Executor<'_>,
+        region: &str,
+        month: i16,
+        recipe: Option<&RecipeUuid>,
+    ) -> anyhow::Result<HashMap<RecipeUuid, f64>> {
+        let mut values = vec![Value::String(region), Value::I16(month)];
+
+        let mut sql = String::from(
+            r#"SELECT "recipe_ingredient"."recipe", count(DISTINCT "ingredient_season"."ingredient") FILTER (WHERE "ingredient_season"."month" = $2), count(DISTINCT "ingredient_season"."ingredient") FROM "recipe_ingredient" JOIN "ingredient_season" ON "ingredient_season"."ingredient" = "recipe_ingredient"."ingredients" AND "ingredient_season"."region" = $1"#,
+        );
+
+        if let Some(recipe) = recipe {
+            values.push(Value::Uuid(recipe.0));
+            sql.push_str(&format!(
+                r#" WHERE "recipe_ingredient"."recipe" = ${}"#,
+                values.len()
+            ));
+        }
+
+        sql.push_str(r#" GROUP BY "recipe_ingredient"."recipe""#);
+
+        let mut result = HashMap::new();
+        for row in exe.execute::<All>(sql, values).await? {
+            let recipe = row.get::<Uuid>(0)?;
+            let in_season = row.get::<i64>(1)?;
+            let seasonal = row.get::<i64>(2)?;
+            if seasonal > 0 {
+                result.insert(RecipeUuid(recipe), in_season as f64 / seasonal as f64);
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,70 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use uuid::Uuid;
+
+use crate::models::ingredients::db::IngredientModel;
+use crate::models::ingredients::Units;
+use crate::models::recipes::db::RecipeModel;
+
+/// Represents an ingredient which can be used in place of another one.
+#[derive(Model)]
+#[rorm(rename = "ingredient_substitution")]
+pub struct IngredientSubstitutionModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the ingredient which is replaced.
+    #[rorm(on_delete = "Cascade")]
+    pub ingredient: ForeignModel<IngredientModel>,
+
+    /// A foreign key referencing the ingredient used instead.
+    #[rorm(on_delete = "Cascade")]
+    pub substitute: ForeignModel<IngredientModel>,
+
+    /// A foreign key referencing the recipe the substitution is limited to.
+    ///
+    /// `None` if the substitution applies to all recipes.
+    #[rorm(on_delete = "Cascade")]
+    pub recipe: Option<ForeignModel<RecipeModel>>,
+
+    /// The quantity of the substitute in `substitute_unit` used per `unit` of the replaced ingredient.
+    pub ratio: f64,
+
+    /// The unit of the replaced ingredient the ratio refers to.
+    pub unit: Units,
+
+    /// The unit of the substitute's quantity.
+    pub substitute_unit: Units,
+
+    /// Additional notes like further ingredients needed (e.g. "plus 3 tbsp water").
+    pub note: Option<MaxStr<255>>,
+}
+
+/// Represents a well-known substitution between common ingredients.
+///
+/// Households can use it if they have ingredients with the same normalized names.
+#[derive(Model)]
+#[rorm(rename = "common_substitution")]
+pub struct CommonSubstitutionModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// The normalized name of the ingredient which is replaced.
+    pub ingredient: MaxStr<255>,
+
+    /// The normalized name of the ingredient used instead.
+    pub substitute: MaxStr<255>,
+
+    /// The quantity of the substitute in `substitute_unit` used per `unit` of the replaced ingredient.
+    pub ratio: f64,
+
+    /// The unit of the replaced ingredient the ratio refers to.
+    pub unit: Units,
+
+    /// The unit of the substitute's quantity.
+    pub substitute_unit: Units,
+
+    /// Additional notes like further ingredients needed (e.g. "plus 3 tbsp water").
+    pub note: Option<MaxStr<255>>,
+}
@@ -0,0 +1,269 @@
+//! Ingredients which can be used in place of others.
+//!
+//! Substitutions apply to all recipes of a household or are limited to a single recipe.
+//! Well-known substitutions between common ingredients apply to every household
+//! which has ingredients with the same names.
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::or;
+use galvyn::rorm::prelude::ForeignModelByField;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredient_substitutions::db::CommonSubstitutionModel;
+use crate::models::ingredient_substitutions::db::IngredientSubstitutionModel;
+use crate::models::ingredients::Ingredient;
+use crate::models::ingredients::IngredientUuid;
+use crate::models::ingredients::Units;
+use crate::models::recipes::RecipeUuid;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of an ingredient substitution.
+#[derive(Debug, Clone)]
+pub struct IngredientSubstitution {
+    /// Stable identifier for this substitution.
+    pub uuid: IngredientSubstitutionUuid,
+
+    /// The ingredient which is replaced.
+    pub ingredient: IngredientUuid,
+
+    /// The ingredient used instead.
+    pub substitute: IngredientUuid,
+
+    /// The recipe the substitution is limited to, `None` if it applies to all recipes.
+    pub recipe: Option<RecipeUuid>,
+
+    /// The quantity of the substitute in `substitute_unit` used per `unit` of the replaced ingredient.
+    pub ratio: f64,
+
+    /// The unit of the replaced ingredient the ratio refers to.
+    pub unit: Units,
+
+    /// The unit of the substitute's quantity.
+    pub substitute_unit: Units,
+
+    /// Additional notes like further ingredients needed (e.g. "plus 3 tbsp water").
+    pub note: Option<MaxStr<255>>,
+
+    /// Whether this is a well-known substitution which can't be deleted.
+    pub common: bool,
+}
+
+/// Strongly typed UUID for ingredient substitutions.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct IngredientSubstitutionUuid(pub Uuid);
+
+impl IngredientSubstitution {
+    /// Lists all substitutions of a household's ingredients including the well-known ones.
+    #[instrument(name = "IngredientSubstitution::query_by_household", skip(exe))]
+    pub async fn query_by_household(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let mut result: Vec<_> = rorm::query(guard.get_transaction(), IngredientSubstitutionModel)
+            .condition(
+                IngredientSubstitutionModel
+                    .ingredient
+                    .household
+                    .equals(household.0),
+            )
+            .stream()
+            .map_ok(IngredientSubstitution::from)
+            .try_collect()
+            .await?;
+        let common = Self::query_common(guard.get_transaction(), household).await?;
+        add_common(&mut result, common);
+
+        guard.commit().await?;
+        Ok(result)
+    }
+
+    /// Lists all substitutions of an ingredient, including the ones limited to a recipe.
+    #[instrument(name = "IngredientSubstitution::query_by_ingredient", skip(exe))]
+    pub async fn query_by_ingredient(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, IngredientSubstitutionModel)
+            .condition(
+                IngredientSubstitutionModel
+                    .ingredient
+                    .equals(ingredient_uuid.get_inner()),
+            )
+            .order_asc(IngredientSubstitutionModel.ratio)
+            .stream()
+            .map_ok(IngredientSubstitution::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Lists the substitutions of a household's ingredients which apply to a recipe.
+    ///
+    /// Substitutions limited to the recipe come first, the well-known ones last.
+    #[instrument(name = "IngredientSubstitution::query_by_recipe", skip(exe))]
+    pub async fn query_by_recipe(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        recipe_uuid: &RecipeUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let mut result: Vec<_> = rorm::query(guard.get_transaction(), IngredientSubstitutionModel)
+            .condition(and![
+                IngredientSubstitutionModel
+                    .ingredient
+                    .household
+                    .equals(household.0),
+                or![
+                    IngredientSubstitutionModel.recipe.is_none(),
+                    IngredientSubstitutionModel
+                        .recipe
+                        .equals(Some(recipe_uuid.0)),
+                ],
+            ])
+            .stream()
+            .map_ok(IngredientSubstitution::from)
+            .try_collect()
+            .await?;
+        result.sort_by_key(|substitution| substitution.recipe.is_none());
+        let common = Self::query_common(guard.get_transaction(), household).await?;
+        add_common(&mut result, common);
+
+        guard.commit().await?;
+        Ok(result)
+    }
+
+    /// Lists the well-known substitutions between ingredients of a household.
+    #[instrument(name = "IngredientSubstitution::query_common", skip(exe))]
+    async fn query_common(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let ingredients =
+            Ingredient::query_normalized_names(guard.get_transaction(), household).await?;
+        let models: Vec<_> = rorm::query(guard.get_transaction(), CommonSubstitutionModel)
+            .all()
+            .await?;
+
+        guard.commit().await?;
+
+        Ok(models
+            .into_iter()
+            .filter_map(|model| {
+                Some(Self {
+                    uuid: IngredientSubstitutionUuid(model.uuid),
+                    ingredient: *ingredients.get(&*model.ingredient)?,
+                    substitute: *ingredients.get(&*model.substitute)?,
+                    recipe: None,
+                    ratio: model.ratio,
+                    unit: model.unit,
+                    substitute_unit: model.substitute_unit,
+                    note: model.note,
+                    common: true,
+                })
+            })
+            .collect())
+    }
+
+    /// Looks up a single substitution by its UUID.
+    #[instrument(name = "IngredientSubstitution::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        uuid: &IngredientSubstitutionUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let substitution = rorm::query(exe, IngredientSubstitutionModel)
+            .condition(and![
+                IngredientSubstitutionModel.uuid.equals(uuid.0),
+                IngredientSubstitutionModel
+                    .ingredient
+                    .household
+                    .equals(household.0),
+            ])
+            .optional()
+            .await?;
+        Ok(substitution.map(Self::from))
+    }
+
+    /// Records a new substitute for an ingredient.
+    #[instrument(name = "IngredientSubstitution::create", skip(exe))]
+    #[expect(clippy::too_many_arguments)]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+        substitute_uuid: &IngredientUuid,
+        recipe: Option<RecipeUuid>,
+        ratio: f64,
+        unit: Units,
+        substitute_unit: Units,
+        note: Option<MaxStr<255>>,
+    ) -> anyhow::Result<Self> {
+        let model = rorm::insert(exe, IngredientSubstitutionModel)
+            .single(&IngredientSubstitutionModel {
+                uuid: Uuid::new_v4(),
+                ingredient: ForeignModelByField(ingredient_uuid.get_inner()),
+                substitute: ForeignModelByField(substitute_uuid.get_inner()),
+                recipe: recipe.map(|recipe| ForeignModelByField(recipe.0)),
+                ratio,
+                unit,
+                substitute_unit,
+                note,
+            })
+            .await?;
+        Ok(IngredientSubstitution::from(model))
+    }
+
+    /// Deletes the substitution.
+    #[instrument(name = "IngredientSubstitution::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, IngredientSubstitutionModel)
+            .condition(IngredientSubstitutionModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+}
+
+impl From<IngredientSubstitutionModel> for IngredientSubstitution {
+    fn from(model: IngredientSubstitutionModel) -> Self {
+        Self {
+            uuid: IngredientSubstitutionUuid(model.uuid),
+            ingredient: IngredientUuid::from_model(model.ingredient),
+            substitute: IngredientUuid::from_model(model.substitute),
+            recipe: model.recipe.map(|recipe| RecipeUuid(recipe.0)),
+            ratio: model.ratio,
+            unit: model.unit,
+            substitute_unit: model.substitute_unit,
+            note: model.note,
+            common: false,
+        }
+    }
+}
+
+/// Adds well-known substitutions for which the household has no own substitution.
+fn add_common(
+    substitutions: &mut Vec<IngredientSubstitution>,
+    common: Vec<IngredientSubstitution>,
+) {
+    for substitution in common {
+        if !substitutions.iter().any(|own| {
+            own.ingredient == substitution.ingredient && own.substitute == substitution.substitute
+        }) {
+            substitutions.push(substitution);
+        }
+    }
+}
@@ -1,7 +1,12 @@
 use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
 use galvyn::rorm::Model;
 use uuid::Uuid;
 
+use crate::models::foods::db::FoodModel;
+use crate::models::households::db::HouseholdModel;
+use crate::models::ingredient_categories::db::IngredientCategoryModel;
+
 /// Represents an ingredient with a unique identifier and name.
 ///
 /// This struct is used to store information about individual ingredients.
@@ -12,6 +17,87 @@ pub struct IngredientModel {
     pub uuid: Uuid,
 
     /// The name of the ingredient.
+    pub name: MaxStr<255>,
+
+    /// The normalized name, unique within the household.
+    ///
+    /// `None` if another ingredient of the household has the same normalized name,
+    /// which happens for duplicates created before names were normalized.
+    pub normalized_name: Option<MaxStr<255>>,
+
+    /// A foreign key referencing the household owning the ingredient.
+    #[rorm(on_delete = "Cascade")]
+    pub household: ForeignModel<HouseholdModel>,
+
+    /// The entry of the food composition database used to calculate nutrients.
+    #[rorm(on_delete = "SetNull")]
+    pub food: Option<ForeignModel<FoodModel>>,
+
+    /// The category the ingredient is grouped by.
+    #[rorm(on_delete = "SetNull")]
+    pub category: Option<ForeignModel<IngredientCategoryModel>>,
+
+    /// Whether the dietary properties and allergens of the ingredient have been specified.
+    #[rorm(default = false)]
+    pub classified: bool,
+
+    /// Whether the ingredient is meat, poultry, fish or seafood.
+    #[rorm(default = false)]
+    pub meat: bool,
+
+    /// Whether the ingredient is a dairy product.
+    #[rorm(default = false)]
+    pub dairy: bool,
+
+    /// Whether the ingredient is or contains any other animal product (e.g. eggs, honey, gelatine).
+    #[rorm(default = false)]
+    pub animal_product: bool,
+
+    /// The density in grams per milliliter, used to convert volumes to weights.
+    pub density: Option<f64>,
+
+    /// The weight of a single piece in grams, used to convert pieces to weights.
+    pub piece_weight: Option<f64>,
+}
+
+/// Represents an alternative name of an ingredient.
+///
+/// Aliases are matched like names when looking up ingredients by name.
+#[derive(Model)]
+#[rorm(rename = "ingredient_alias")]
+pub struct IngredientAliasModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the ingredient.
+    #[rorm(on_delete = "Cascade")]
+    pub ingredient: ForeignModel<IngredientModel>,
+
+    /// The alternative name.
+    pub alias: MaxStr<255>,
+
+    /// The normalized alternative name.
+    ///
+    /// `None` for aliases created before names were normalized until the next migration.
+    pub normalized_alias: Option<MaxStr<255>>,
+}
+
+/// Represents the measures of a common ingredient.
+///
+/// New ingredients take over the measures of the common ingredient with the same name.
+#[derive(Model)]
+#[rorm(rename = "ingredient_measure")]
+pub struct IngredientMeasureModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// The normalized name of the ingredient.
     #[rorm(unique)]
     pub name: MaxStr<255>,
+
+    /// The density in grams per milliliter.
+    pub density: Option<f64>,
+
+    /// The weight of a single piece in grams.
+    pub piece_weight: Option<f64>,
 }
@@ -1,19 +1,43 @@
 //! Represents an ingredient in a recipe.
 
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
 use futures_util::TryStreamExt;
 use galvyn::core::re_exports::rorm;
 use galvyn::core::re_exports::schemars;
 use galvyn::core::re_exports::schemars::JsonSchema;
 use galvyn::core::re_exports::serde::Deserialize;
 use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::executor::All;
+use galvyn::rorm::db::executor::One;
+use galvyn::rorm::db::sql::value::Value;
 use galvyn::rorm::db::Executor;
 use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::or;
 use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::prelude::ForeignModelByField;
 use galvyn::rorm::DbEnum;
 use tracing::instrument;
 use uuid::Uuid;
 
+use crate::models::foods::FoodUuid;
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredient_allergens::IngredientAllergen;
+use crate::models::ingredient_categories::IngredientCategoryUuid;
+use crate::models::ingredient_prices::db::IngredientPriceModel;
+use crate::models::ingredient_seasons::db::IngredientSeasonModel;
+use crate::models::ingredient_substitutions::db::IngredientSubstitutionModel;
+use crate::models::ingredients::db::IngredientAliasModel;
+use crate::models::ingredients::db::IngredientMeasureModel;
 use crate::models::ingredients::db::IngredientModel;
+use crate::models::pantry_items::db::PantryItemModel;
+use crate::models::recipe_ingredients::db::RecipeIngredientModel;
+use crate::models::recipes::Recipe;
+use crate::models::shopping_lists::db::ShoppingListItemModel;
+use crate::utils::max_str;
+use crate::utils::normalize;
 
 pub(in crate::models) mod db;
 
@@ -25,10 +49,70 @@ pub struct Ingredient {
 
     /// The name of the ingredient
     pub name: MaxStr<255>,
+
+    /// The linked entry of the food composition database
+    pub food: Option<FoodUuid>,
+
+    /// The category the ingredient is grouped by
+    pub category: Option<IngredientCategoryUuid>,
+
+    /// Whether the dietary properties and allergens have been specified
+    pub classified: bool,
+
+    /// Whether the ingredient is meat, poultry, fish or seafood
+    pub meat: bool,
+
+    /// Whether the ingredient is a dairy product
+    pub dairy: bool,
+
+    /// Whether the ingredient is or contains any other animal product
+    pub animal_product: bool,
+
+    /// The factors to convert amounts between volume, weight and pieces
+    pub measures: IngredientMeasures,
+}
+
+/// An ingredient suggested while typing its name.
+#[derive(Debug, Clone)]
+pub struct IngredientSuggestion {
+    /// The suggested ingredient
+    pub ingredient: Ingredient,
+
+    /// The number of recipes using the ingredient
+    pub usage_count: i64,
+
+    /// The unit the ingredient is measured in most often, `None` if no recipe uses it
+    pub common_unit: Option<Units>,
+}
+
+/// Ingredient-specific factors to convert amounts between units of different dimensions.
+///
+/// E.g. "2 cups flour" can only be converted to grams with the density of flour.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct IngredientMeasures {
+    /// The density in grams per milliliter
+    pub density: Option<f64>,
+
+    /// The weight of a single piece in grams, e.g. about 50 for an egg
+    pub piece_weight: Option<f64>,
+}
+
+impl IngredientMeasures {
+    /// Returns how many grams one base unit of a dimension weighs.
+    ///
+    /// Returns `None` if the factor is unknown.
+    fn grams_per(self, dimension: UnitDimension) -> Option<f64> {
+        match dimension {
+            UnitDimension::Mass => Some(1.0),
+            UnitDimension::Volume => self.density,
+            UnitDimension::Count => self.piece_weight,
+        }
+        .filter(|factor| *factor > 0.0)
+    }
 }
 
 /// Strongly typed UUID for ingredients to avoid mixing IDs across domains.
-#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct IngredientUuid(Uuid);
 
 impl IngredientUuid {
@@ -43,10 +127,14 @@ impl IngredientUuid {
 }
 
 impl Ingredient {
-    /// Fetches all ingredients ordered by name.
+    /// Fetches all ingredients of a household ordered by name.
     #[instrument(name = "Ingredient::query_all", skip(exe))]
-    pub async fn query_all(exe: impl Executor<'_>) -> anyhow::Result<Vec<Self>> {
+    pub async fn query_all(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Vec<Self>> {
         let items: Vec<_> = rorm::query(exe, IngredientModel)
+            .condition(IngredientModel.household.equals(household.0))
             .order_asc(IngredientModel.name)
             .stream()
             .map_ok(|model| Ingredient::from(model))
@@ -56,50 +144,855 @@ impl Ingredient {
         Ok(items)
     }
 
+    /// Fetches the ingredients of a household in a category ordered by name.
+    #[instrument(name = "Ingredient::query_by_category", skip(exe))]
+    pub async fn query_by_category(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        category: &IngredientCategoryUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let items: Vec<_> = rorm::query(exe, IngredientModel)
+            .condition(and![
+                IngredientModel.household.equals(household.0),
+                IngredientModel.category.equals(Some(category.0)),
+            ])
+            .order_asc(IngredientModel.name)
+            .stream()
+            .map_ok(Ingredient::from)
+            .try_collect()
+            .await?;
+
+        Ok(items)
+    }
+
+    /// Suggests ingredients of a household whose name or alias matches a search term.
+    ///
+    /// Ingredients with a word starting with the term come first, followed by ones
+    /// containing it and ones with a similar name (using trigram similarity).
+    /// Similar names are only matched if the `pg_trgm` extension is installed.
+    /// Within each group the ingredients used by the most recipes come first.
+    #[instrument(name = "Ingredient::autocomplete", skip(exe))]
+    pub async fn autocomplete(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        search: &str,
+        limit: i64,
+    ) -> anyhow::Result<Vec<IngredientSuggestion>> {
+        let search = search.trim().to_lowercase();
+        if search.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let escaped = search
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let word_prefix = format!("% {escaped}%");
+        let infix = format!("%{escaped}%");
+
+        let mut guard = exe.ensure_transaction().await?;
+
+        // Similar names are matched with the trigram similarity operator
+        const TRIGRAM_SQL: &str = r#"SELECT "uuid", "usage_count" FROM (SELECT "ingredient"."uuid", "ingredient"."name", (SELECT count(DISTINCT "recipe_ingredient"."recipe") FROM "recipe_ingredient" WHERE "recipe_ingredient"."ingredients" = "ingredient"."uuid") AS "usage_count", (SELECT min(CASE WHEN ' ' || lower("term") LIKE $2 THEN 0 WHEN lower("term") LIKE $3 THEN 1 ELSE 2 END) FROM (SELECT "ingredient"."name" AS "term" UNION ALL SELECT "ingredient_alias"."alias" FROM "ingredient_alias" WHERE "ingredient_alias"."ingredient" = "ingredient"."uuid") AS "terms") AS "rank" FROM "ingredient" WHERE "ingredient"."household" = $1 AND (lower("ingredient"."name") LIKE $3 OR lower("ingredient"."name") % $4 OR EXISTS (SELECT 1 FROM "ingredient_alias" WHERE "ingredient_alias"."ingredient" = "ingredient"."uuid" AND (lower("ingredient_alias"."alias") LIKE $3 OR lower("ingredient_alias"."alias") % $4)))) AS "candidates" ORDER BY "rank", "usage_count" DESC, "name" LIMIT $5"#;
+        // Without trigrams only exact names are added to the substring matches
+        const EXACT_SQL: &str = r#"SELECT "uuid", "usage_count" FROM (SELECT "ingredient"."uuid", "ingredient"."name", (SELECT count(DISTINCT "recipe_ingredient"."recipe") FROM "recipe_ingredient" WHERE "recipe_ingredient"."ingredients" = "ingredient"."uuid") AS "usage_count", (SELECT min(CASE WHEN ' ' || lower("term") LIKE $2 THEN 0 WHEN lower("term") LIKE $3 THEN 1 ELSE 2 END) FROM (SELECT "ingredient"."name" AS "term" UNION ALL SELECT "ingredient_alias"."alias" FROM "ingredient_alias" WHERE "ingredient_alias"."ingredient" = "ingredient"."uuid") AS "terms") AS "rank" FROM "ingredient" WHERE "ingredient"."household" = $1 AND (lower("ingredient"."name") LIKE $3 OR lower("ingredient"."name") = $4 OR EXISTS (SELECT 1 FROM "ingredient_alias" WHERE "ingredient_alias"."ingredient" = "ingredient"."uuid" AND (lower("ingredient_alias"."alias") LIKE $3 OR lower("ingredient_alias"."alias") = $4)))) AS "candidates" ORDER BY "rank", "usage_count" DESC, "name" LIMIT $5"#;
+
+        let trigrams = guard
+            .get_transaction()
+            .execute::<One>(
+                "SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'pg_trgm')".to_string(),
+                Vec::new(),
+            )
+            .await?
+            .get::<bool>(0)?;
+        let sql = if trigrams { TRIGRAM_SQL } else { EXACT_SQL };
+
+        let mut ranking = Vec::new();
+        for row in guard
+            .get_transaction()
+            .execute::<All>(
+                sql.to_string(),
+                vec![
+                    Value::Uuid(household.0),
+                    Value::String(&word_prefix),
+                    Value::String(&infix),
+                    Value::String(&search),
+                    Value::I64(limit),
+                ],
+            )
+            .await?
+        {
+            ranking.push((row.get::<Uuid>(0)?, row.get::<i64>(1)?));
+        }
+
+        if ranking.is_empty() {
+            guard.commit().await?;
+            return Ok(Vec::new());
+        }
+
+        let ingredients: Vec<_> = rorm::query(guard.get_transaction(), IngredientModel)
+            .condition(
+                IngredientModel
+                    .uuid
+                    .r#in(ranking.iter().map(|(uuid, _)| *uuid)),
+            )
+            .stream()
+            .map_ok(Ingredient::from)
+            .try_collect()
+            .await?;
+
+        let recipe_units: Vec<_> = rorm::query(
+            guard.get_transaction(),
+            (
+                RecipeIngredientModel.ingredients,
+                RecipeIngredientModel.unit,
+            ),
+        )
+        .condition(
+            RecipeIngredientModel
+                .ingredients
+                .r#in(ranking.iter().map(|(uuid, _)| *uuid)),
+        )
+        .all()
+        .await?;
+
+        guard.commit().await?;
+
+        let mut units: HashMap<IngredientUuid, BTreeMap<Units, usize>> = HashMap::new();
+        for (ingredient, unit) in recipe_units {
+            *units
+                .entry(IngredientUuid::from_model(ingredient))
+                .or_default()
+                .entry(unit)
+                .or_default() += 1;
+        }
+
+        let mut result: Vec<_> = ingredients
+            .into_iter()
+            .map(|ingredient| {
+                let usage_count = ranking
+                    .iter()
+                    .find(|(uuid, _)| *uuid == ingredient.uuid.0)
+                    .map(|(_, usage_count)| *usage_count)
+                    .unwrap_or_default();
+                let common_unit = units.get(&ingredient.uuid).and_then(|units| {
+                    units
+                        .iter()
+                        .max_by_key(|(_, count)| **count)
+                        .map(|(unit, _)| *unit)
+                });
+                IngredientSuggestion {
+                    ingredient,
+                    usage_count,
+                    common_unit,
+                }
+            })
+            .collect();
+        result.sort_by_key(|suggestion| {
+            ranking
+                .iter()
+                .position(|(uuid, _)| *uuid == suggestion.ingredient.uuid.0)
+        });
+
+        Ok(result)
+    }
+
     /// Looks up a single ingredient by its UUID.
     ///
     /// Returns None if there is no matching record.
     #[instrument(name = "Ingredient::query_by_uuid", skip(exe))]
     pub async fn query_by_uuid(
         exe: impl Executor<'_>,
+        household: &HouseholdUuid,
         uuid: &IngredientUuid,
     ) -> anyhow::Result<Option<Self>> {
         let ingredient = rorm::query(exe, IngredientModel)
-            .condition(IngredientModel.uuid.equals(uuid.0))
+            .condition(and![
+                IngredientModel.uuid.equals(uuid.0),
+                IngredientModel.household.equals(household.0),
+            ])
             .optional()
             .await?;
         Ok(ingredient.map(Self::from))
     }
 
+    /// Looks up several ingredients of a household by their UUIDs.
+    ///
+    /// Ingredients which don't exist are missing in the map.
+    #[instrument(name = "Ingredient::query_by_uuids", skip(exe))]
+    pub async fn query_by_uuids(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        uuids: &[IngredientUuid],
+    ) -> anyhow::Result<HashMap<IngredientUuid, Self>> {
+        if uuids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let result = rorm::query(exe, IngredientModel)
+            .condition(and![
+                IngredientModel.uuid.r#in(uuids.iter().map(|uuid| uuid.0)),
+                IngredientModel.household.equals(household.0),
+            ])
+            .stream()
+            .map_ok(|model| (IngredientUuid(model.uuid), Self::from(model)))
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Looks up an ingredient whose name or one of its aliases matches a name after normalization.
+    ///
+    /// See [`normalize::ingredient_name`] for the rules.
+    #[instrument(name = "Ingredient::query_by_normalized_name", skip(exe))]
+    pub async fn query_by_normalized_name(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        name: &str,
+    ) -> anyhow::Result<Option<IngredientUuid>> {
+        let normalized = normalized_name(name)?;
+
+        let mut guard = exe.ensure_transaction().await?;
+
+        // Names take precedence over aliases
+        let by_name = rorm::query(guard.get_transaction(), IngredientModel.uuid)
+            .condition(and![
+                IngredientModel.household.equals(household.0),
+                IngredientModel.normalized_name.equals(Some(&*normalized)),
+            ])
+            .optional()
+            .await?;
+        if let Some(uuid) = by_name {
+            guard.commit().await?;
+            return Ok(Some(IngredientUuid(uuid)));
+        }
+
+        let by_alias = rorm::query(guard.get_transaction(), IngredientAliasModel.ingredient)
+            .condition(and![
+                IngredientAliasModel
+                    .ingredient
+                    .household
+                    .equals(household.0),
+                IngredientAliasModel
+                    .normalized_alias
+                    .equals(Some(&*normalized)),
+            ])
+            .all()
+            .await?;
+
+        guard.commit().await?;
+        Ok(by_alias.into_iter().next().map(IngredientUuid::from_model))
+    }
+
+    /// Maps the normalized names of a household's ingredients to the ingredients.
+    #[instrument(name = "Ingredient::query_normalized_names", skip(exe))]
+    pub async fn query_normalized_names(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<HashMap<String, IngredientUuid>> {
+        let names = rorm::query(exe, (IngredientModel.normalized_name, IngredientModel.uuid))
+            .condition(and![
+                IngredientModel.household.equals(household.0),
+                IngredientModel.normalized_name.is_some(),
+            ])
+            .all()
+            .await?;
+        Ok(names
+            .into_iter()
+            .filter_map(|(name, uuid)| Some((name?.to_string(), IngredientUuid(uuid))))
+            .collect())
+    }
+
     /// Inserts a new ingredient into the database if one doesn't already exist.
     ///
-    /// This function attempts to retrieve an ingredient by its name from the database.
-    /// If an ingredient with the given name exists, it's returned. Otherwise, a new
-    /// ingredient is inserted with a generated UUID and its name, and the UUID is returned.
+    /// This function attempts to retrieve an ingredient by its name from the household.
+    /// Names are compared after normalization and the aliases of the ingredients are
+    /// consulted as well, so "Onions" finds the ingredient "onion".
+    /// If no ingredient matches, a new ingredient is inserted with a generated UUID
+    /// and its name, and the UUID is returned.
+    /// New ingredients take over the measures of the common ingredient with the same name.
     #[instrument(name = "Ingredient::get_uuid_or_create", skip(exe))]
     pub async fn get_uuid_or_create(
         exe: impl Executor<'_>,
+        household: &HouseholdUuid,
         name: MaxStr<255>,
     ) -> anyhow::Result<IngredientUuid> {
         let mut guard = exe.ensure_transaction().await?;
         let ingredient = rorm::query(guard.get_transaction(), IngredientModel)
-            .condition(IngredientModel.name.equals(&name))
+            .condition(and![
+                IngredientModel.name.equals(&name),
+                IngredientModel.household.equals(household.0),
+            ])
             .optional()
             .await?;
 
         if let Some(ingredient) = ingredient {
+            guard.commit().await?;
             return Ok(IngredientUuid(ingredient.uuid));
         }
 
+        if let Some(uuid) =
+            Self::query_by_normalized_name(guard.get_transaction(), household, &name).await?
+        {
+            guard.commit().await?;
+            return Ok(uuid);
+        }
+
+        let measures = Self::query_default_measures(guard.get_transaction(), &name).await?;
+        let normalized = normalized_name(&name)?;
+
         let ingredient = rorm::insert(guard.get_transaction(), IngredientModel)
             .single(&IngredientModel {
                 uuid: Uuid::new_v4(),
                 name,
+                normalized_name: Some(normalized),
+                household: ForeignModelByField(household.0),
+                food: None,
+                category: None,
+                classified: false,
+                meat: false,
+                dairy: false,
+                animal_product: false,
+                density: measures.density,
+                piece_weight: measures.piece_weight,
             })
             .await?;
 
+        guard.commit().await?;
         Ok(IngredientUuid(ingredient.uuid))
     }
+
+    /// Looks up the measures of common ingredients by name.
+    ///
+    /// Returns empty measures if the ingredient is not a common one.
+    #[instrument(name = "Ingredient::query_default_measures", skip(exe))]
+    pub async fn query_default_measures(
+        exe: impl Executor<'_>,
+        name: &str,
+    ) -> anyhow::Result<IngredientMeasures> {
+        let normalized = normalize::ingredient_name(name);
+
+        let measures = rorm::query(
+            exe,
+            (
+                IngredientMeasureModel.density,
+                IngredientMeasureModel.piece_weight,
+            ),
+        )
+        .condition(IngredientMeasureModel.name.equals(&normalized))
+        .optional()
+        .await?;
+
+        Ok(measures
+            .map(|(density, piece_weight)| IngredientMeasures {
+                density,
+                piece_weight,
+            })
+            .unwrap_or_default())
+    }
+
+    /// Stores the normalized name of the ingredient if it has none yet.
+    ///
+    /// Nothing is stored if another ingredient of the household has the same normalized name.
+    #[instrument(name = "Ingredient::claim_normalized_name", skip(exe))]
+    async fn claim_normalized_name(&self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        let normalized = normalized_name(&self.name)?;
+        exe.execute::<All>(
+            r#"UPDATE "ingredient" SET "normalized_name" = $1 WHERE "uuid" = $2 AND "normalized_name" IS NULL AND NOT EXISTS (SELECT 1 FROM "ingredient" AS "other" WHERE "other"."household" = "ingredient"."household" AND "other"."normalized_name" = $1)"#.to_string(),
+            vec![Value::String(&normalized), Value::Uuid(self.uuid.0)],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Stores the normalized names of ingredients and aliases which don't have one yet.
+    ///
+    /// Of ingredients with the same normalized name only the first by name gets it,
+    /// the others are reported as likely duplicates.
+    /// Returns the number of ingredients and aliases which have been looked at.
+    #[instrument(name = "Ingredient::backfill_normalized_names", skip(exe))]
+    pub async fn backfill_normalized_names(
+        exe: impl Executor<'_>,
+    ) -> anyhow::Result<(usize, usize)> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let ingredients: Vec<_> = rorm::query(guard.get_transaction(), IngredientModel)
+            .condition(IngredientModel.normalized_name.is_none())
+            .order_asc(IngredientModel.name)
+            .stream()
+            .map_ok(Ingredient::from)
+            .try_collect()
+            .await?;
+        for ingredient in &ingredients {
+            ingredient
+                .claim_normalized_name(guard.get_transaction())
+                .await?;
+        }
+
+        let aliases: Vec<_> = rorm::query(
+            guard.get_transaction(),
+            (IngredientAliasModel.uuid, IngredientAliasModel.alias),
+        )
+        .condition(IngredientAliasModel.normalized_alias.is_none())
+        .all()
+        .await?;
+        for (uuid, alias) in &aliases {
+            rorm::update(guard.get_transaction(), IngredientAliasModel)
+                .set(
+                    IngredientAliasModel.normalized_alias,
+                    Some(normalized_name(alias)?),
+                )
+                .condition(IngredientAliasModel.uuid.equals(*uuid))
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok((ingredients.len(), aliases.len()))
+    }
+
+    /// Groups the ingredients of every household which are likely duplicates of each other.
+    ///
+    /// Ingredients are considered duplicates if their names are equal after normalization.
+    /// The groups are ordered by the name of their first ingredient.
+    #[instrument(name = "Ingredient::query_likely_duplicates", skip(exe))]
+    pub async fn query_likely_duplicates(
+        exe: impl Executor<'_>,
+    ) -> anyhow::Result<Vec<(HouseholdUuid, Vec<Self>)>> {
+        let models: Vec<_> = rorm::query(exe, IngredientModel)
+            .order_asc(IngredientModel.name)
+            .all()
+            .await?;
+
+        let mut groups = HashMap::<_, Vec<_>>::new();
+        for model in models {
+            let key = (
+                HouseholdUuid::from_model(model.household),
+                normalize::ingredient_name(&model.name),
+            );
+            groups.entry(key).or_default().push(Ingredient::from(model));
+        }
+
+        let mut result: Vec<_> = groups
+            .into_iter()
+            .filter(|(_, ingredients)| ingredients.len() > 1)
+            .map(|((household, _), ingredients)| (household, ingredients))
+            .collect();
+        result.sort_by(|(_, a), (_, b)| str::cmp(&a[0].name, &b[0].name));
+        Ok(result)
+    }
+
+    /// Lists the aliases of the ingredient ordered alphabetically.
+    #[instrument(name = "Ingredient::query_aliases", skip(exe))]
+    pub async fn query_aliases(&self, exe: impl Executor<'_>) -> anyhow::Result<Vec<MaxStr<255>>> {
+        let aliases: Vec<_> = rorm::query(exe, IngredientAliasModel.alias)
+            .condition(IngredientAliasModel.ingredient.equals(self.uuid.0))
+            .order_asc(IngredientAliasModel.alias)
+            .all()
+            .await?;
+        Ok(aliases)
+    }
+
+    /// Replaces the aliases of the ingredient.
+    #[instrument(name = "Ingredient::set_aliases", skip(exe))]
+    pub async fn set_aliases(
+        &self,
+        exe: impl Executor<'_>,
+        mut aliases: Vec<MaxStr<255>>,
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        rorm::delete(guard.get_transaction(), IngredientAliasModel)
+            .condition(IngredientAliasModel.ingredient.equals(self.uuid.0))
+            .await?;
+
+        aliases.sort_by(|a, b| str::cmp(a, b));
+        aliases.dedup();
+
+        let mut models = Vec::with_capacity(aliases.len());
+        for alias in aliases {
+            models.push(IngredientAliasModel {
+                uuid: Uuid::new_v4(),
+                ingredient: ForeignModelByField(self.uuid.0),
+                normalized_alias: Some(normalized_name(&alias)?),
+                alias,
+            });
+        }
+
+        if !models.is_empty() {
+            rorm::insert(guard.get_transaction(), IngredientAliasModel)
+                .return_nothing()
+                .bulk(models)
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+
+    /// Links the ingredient to an entry of the food composition database.
+    #[instrument(name = "Ingredient::set_food", skip(exe))]
+    pub async fn set_food(
+        &self,
+        exe: impl Executor<'_>,
+        food: Option<FoodUuid>,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, IngredientModel)
+            .set(
+                IngredientModel.food,
+                food.map(|food| ForeignModelByField(food.get_inner())),
+            )
+            .condition(IngredientModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Renames the ingredient.
+    ///
+    /// The caller has to make sure no other ingredient of the household has the same normalized name.
+    #[instrument(name = "Ingredient::rename", skip(exe))]
+    pub async fn rename(&self, exe: impl Executor<'_>, name: MaxStr<255>) -> anyhow::Result<()> {
+        let normalized = normalized_name(&name)?;
+        rorm::update(exe, IngredientModel)
+            .set(IngredientModel.name, name)
+            .set(IngredientModel.normalized_name, Some(normalized))
+            .condition(IngredientModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Checks whether the ingredient is used by any recipe, stocked in the pantry,
+    /// on a shopping list, part of a substitution or has seasons.
+    #[instrument(name = "Ingredient::is_used", skip(exe))]
+    pub async fn is_used(&self, exe: impl Executor<'_>) -> anyhow::Result<bool> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let recipes = rorm::query(guard.get_transaction(), RecipeIngredientModel.uuid.count())
+            .condition(RecipeIngredientModel.ingredients.equals(self.uuid.0))
+            .one()
+            .await?;
+        let pantry_items = rorm::query(guard.get_transaction(), PantryItemModel.uuid.count())
+            .condition(PantryItemModel.ingredient.equals(self.uuid.0))
+            .one()
+            .await?;
+        let shopping_items =
+            rorm::query(guard.get_transaction(), ShoppingListItemModel.uuid.count())
+                .condition(ShoppingListItemModel.ingredient.equals(Some(self.uuid.0)))
+                .one()
+                .await?;
+        let substitutions = rorm::query(
+            guard.get_transaction(),
+            IngredientSubstitutionModel.uuid.count(),
+        )
+        .condition(or![
+            IngredientSubstitutionModel.ingredient.equals(self.uuid.0),
+            IngredientSubstitutionModel.substitute.equals(self.uuid.0),
+        ])
+        .one()
+        .await?;
+        let seasons = rorm::query(guard.get_transaction(), IngredientSeasonModel.uuid.count())
+            .condition(IngredientSeasonModel.ingredient.equals(self.uuid.0))
+            .one()
+            .await?;
+
+        guard.commit().await?;
+        Ok(recipes > 0
+            || pantry_items > 0
+            || shopping_items > 0
+            || substitutions > 0
+            || seasons > 0)
+    }
+
+    /// Deletes the ingredient.
+    ///
+    /// The ingredient must not be used anymore, see [`Ingredient::is_used`].
+    #[instrument(name = "Ingredient::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, IngredientModel)
+            .condition(IngredientModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes all ingredients which are neither used by a recipe, stocked in the pantry,
+    /// on a shopping list nor part of a substitution.
+    ///
+    /// Ingredients the household has curated are kept, i.e. ones with aliases, prices,
+    /// seasons, allergens, measures, a category or a link to the food composition database.
+    ///
+    /// Returns the household of every deleted ingredient.
+    #[instrument(name = "Ingredient::delete_unused", skip(exe))]
+    pub async fn delete_unused(exe: impl Executor<'_>) -> anyhow::Result<Vec<HouseholdUuid>> {
+        let sql = r#"DELETE FROM "ingredient" WHERE NOT EXISTS (SELECT 1 FROM "recipe_ingredient" WHERE "recipe_ingredient"."ingredients" = "ingredient"."uuid") AND NOT EXISTS (SELECT 1 FROM "pantry_item" WHERE "pantry_item"."ingredient" = "ingredient"."uuid") AND NOT EXISTS (SELECT 1 FROM "shopping_list_item" WHERE "shopping_list_item"."ingredient" = "ingredient"."uuid") AND NOT EXISTS (SELECT 1 FROM "ingredient_substitution" WHERE "ingredient_substitution"."ingredient" = "ingredient"."uuid" OR "ingredient_substitution"."substitute" = "ingredient"."uuid") AND NOT EXISTS (SELECT 1 FROM "ingredient_alias" WHERE "ingredient_alias"."ingredient" = "ingredient"."uuid") AND NOT EXISTS (SELECT 1 FROM "ingredient_price" WHERE "ingredient_price"."ingredient" = "ingredient"."uuid") AND NOT EXISTS (SELECT 1 FROM "ingredient_season" WHERE "ingredient_season"."ingredient" = "ingredient"."uuid") AND NOT EXISTS (SELECT 1 FROM "ingredient_allergen" WHERE "ingredient_allergen"."ingredient" = "ingredient"."uuid") AND "ingredient"."density" IS NULL AND "ingredient"."piece_weight" IS NULL AND "ingredient"."category" IS NULL AND "ingredient"."food" IS NULL RETURNING "household""#;
+
+        let mut households = Vec::new();
+        for row in exe.execute::<All>(sql.to_string(), Vec::new()).await? {
+            households.push(HouseholdUuid(row.get::<Uuid>(0)?));
+        }
+        Ok(households)
+    }
+
+    /// Merges the ingredient into another one and deletes it.
+    ///
+    /// Recipes, pantry items, prices, substitutions and shopping list items are moved to `target`.
+    /// Recipes using both ingredients keep a single entry with the amounts added up
+    /// as far as their units can be converted.
+    /// The food link, category, measures, classification and seasons are taken over
+    /// if `target` lacks them.
+    #[instrument(name = "Ingredient::merge_into", skip(exe))]
+    pub async fn merge_into(
+        self,
+        exe: impl Executor<'_>,
+        target: &Ingredient,
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        rorm::update(guard.get_transaction(), RecipeIngredientModel)
+            .set(
+                RecipeIngredientModel.ingredients,
+                ForeignModelByField(target.uuid.0),
+            )
+            .condition(RecipeIngredientModel.ingredients.equals(self.uuid.0))
+            .await?;
+
+        // Recipes which used both ingredients would list the kept one twice
+        let measures = if target.measures == IngredientMeasures::default() {
+            self.measures
+        } else {
+            target.measures
+        };
+        let mut by_recipe: HashMap<Uuid, Vec<RecipeIngredientModel>> = HashMap::new();
+        let mut stream = rorm::query(guard.get_transaction(), RecipeIngredientModel)
+            .condition(RecipeIngredientModel.ingredients.equals(target.uuid.0))
+            .stream();
+        while let Some(model) = stream.try_next().await? {
+            by_recipe.entry(model.recipe.0).or_default().push(model);
+        }
+        drop(stream);
+
+        for models in by_recipe.into_values().filter(|models| models.len() > 1) {
+            let mut entries: Vec<(RecipeIngredientModel, bool)> = Vec::new();
+            for model in models {
+                let combined = entries.iter_mut().find_map(|(kept, changed)| {
+                    let amount = model
+                        .unit
+                        .convert(model.amount as f64, kept.unit, measures)?;
+                    Some((kept, changed, amount))
+                });
+                let Some((kept, changed, amount)) = combined else {
+                    entries.push((model, false));
+                    continue;
+                };
+
+                kept.amount += amount.round() as i64;
+                kept.optional &= model.optional;
+                if kept.preparation.is_none() {
+                    kept.preparation = model.preparation;
+                }
+                *changed = true;
+
+                rorm::delete(guard.get_transaction(), RecipeIngredientModel)
+                    .condition(RecipeIngredientModel.uuid.equals(model.uuid))
+                    .await?;
+            }
+
+            for (kept, _) in entries.into_iter().filter(|(_, changed)| *changed) {
+                rorm::update(guard.get_transaction(), RecipeIngredientModel)
+                    .set(RecipeIngredientModel.amount, kept.amount)
+                    .set(RecipeIngredientModel.optional, kept.optional)
+                    .set(RecipeIngredientModel.preparation, kept.preparation)
+                    .condition(RecipeIngredientModel.uuid.equals(kept.uuid))
+                    .await?;
+            }
+        }
+
+        rorm::update(guard.get_transaction(), PantryItemModel)
+            .set(
+                PantryItemModel.ingredient,
+                ForeignModelByField(target.uuid.0),
+            )
+            .condition(PantryItemModel.ingredient.equals(self.uuid.0))
+            .await?;
+
+        rorm::update(guard.get_transaction(), IngredientPriceModel)
+            .set(
+                IngredientPriceModel.ingredient,
+                ForeignModelByField(target.uuid.0),
+            )
+            .condition(IngredientPriceModel.ingredient.equals(self.uuid.0))
+            .await?;
+
+        rorm::update(guard.get_transaction(), ShoppingListItemModel)
+            .set(
+                ShoppingListItemModel.ingredient,
+                Some(ForeignModelByField(target.uuid.0)),
+            )
+            .condition(ShoppingListItemModel.ingredient.equals(Some(self.uuid.0)))
+            .await?;
+
+        rorm::update(guard.get_transaction(), IngredientSubstitutionModel)
+            .set(
+                IngredientSubstitutionModel.ingredient,
+                ForeignModelByField(target.uuid.0),
+            )
+            .condition(IngredientSubstitutionModel.ingredient.equals(self.uuid.0))
+            .await?;
+
+        rorm::update(guard.get_transaction(), IngredientSubstitutionModel)
+            .set(
+                IngredientSubstitutionModel.substitute,
+                ForeignModelByField(target.uuid.0),
+            )
+            .condition(IngredientSubstitutionModel.substitute.equals(self.uuid.0))
+            .await?;
+
+        // Substitutions between the two ingredients have become pointless
+        rorm::delete(guard.get_transaction(), IngredientSubstitutionModel)
+            .condition(and![
+                IngredientSubstitutionModel.ingredient.equals(target.uuid.0),
+                IngredientSubstitutionModel.substitute.equals(target.uuid.0),
+            ])
+            .await?;
+
+        if target.food.is_none() && self.food.is_some() {
+            target.set_food(guard.get_transaction(), self.food).await?;
+        }
+
+        if target.category.is_none() && self.category.is_some() {
+            target
+                .set_category(guard.get_transaction(), self.category)
+                .await?;
+        }
+
+        if target.measures == IngredientMeasures::default() {
+            target
+                .set_measures(guard.get_transaction(), self.measures)
+                .await?;
+        }
+
+        if !target.classified && self.classified {
+            target
+                .set_dietary(
+                    guard.get_transaction(),
+                    self.meat,
+                    self.dairy,
+                    self.animal_product,
+                )
+                .await?;
+            let allergens =
+                IngredientAllergen::query_by_ingredient(guard.get_transaction(), &self.uuid)
+                    .await?;
+            IngredientAllergen::set_for_ingredient(
+                guard.get_transaction(),
+                &target.uuid,
+                &allergens,
+            )
+            .await?;
+        }
+
+        let target_seasons =
+            rorm::query(guard.get_transaction(), IngredientSeasonModel.uuid.count())
+                .condition(IngredientSeasonModel.ingredient.equals(target.uuid.0))
+                .one()
+                .await?;
+        if target_seasons == 0 {
+            rorm::update(guard.get_transaction(), IngredientSeasonModel)
+                .set(
+                    IngredientSeasonModel.ingredient,
+                    ForeignModelByField(target.uuid.0),
+                )
+                .condition(IngredientSeasonModel.ingredient.equals(self.uuid.0))
+                .await?;
+        }
+
+        // The merged name stays findable as an alias of the kept ingredient
+        rorm::update(guard.get_transaction(), IngredientAliasModel)
+            .set(
+                IngredientAliasModel.ingredient,
+                ForeignModelByField(target.uuid.0),
+            )
+            .condition(IngredientAliasModel.ingredient.equals(self.uuid.0))
+            .await?;
+        let normalized = normalized_name(&self.name)?;
+        if *normalized != *normalized_name(&target.name)? {
+            rorm::insert(guard.get_transaction(), IngredientAliasModel)
+                .return_nothing()
+                .single(&IngredientAliasModel {
+                    uuid: Uuid::new_v4(),
+                    ingredient: ForeignModelByField(target.uuid.0),
+                    alias: self.name.clone(),
+                    normalized_alias: Some(normalized),
+                })
+                .await?;
+        }
+
+        self.delete(guard.get_transaction()).await?;
+
+        // A duplicate without a normalized name can take it over from the merged ingredient
+        target
+            .claim_normalized_name(guard.get_transaction())
+            .await?;
+
+        // The classification of the recipes' ingredients might have changed
+        for recipe_uuid in
+            Recipe::query_uuids_by_ingredient(guard.get_transaction(), &target.uuid).await?
+        {
+            Recipe::refresh_dietary(guard.get_transaction(), &recipe_uuid).await?;
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+
+    /// Assigns the ingredient to a category.
+    #[instrument(name = "Ingredient::set_category", skip(exe))]
+    pub async fn set_category(
+        &self,
+        exe: impl Executor<'_>,
+        category: Option<IngredientCategoryUuid>,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, IngredientModel)
+            .set(
+                IngredientModel.category,
+                category.map(|category| ForeignModelByField(category.0)),
+            )
+            .condition(IngredientModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Sets the factors to convert amounts of the ingredient between volume, weight and pieces.
+    #[instrument(name = "Ingredient::set_measures", skip(exe))]
+    pub async fn set_measures(
+        &self,
+        exe: impl Executor<'_>,
+        measures: IngredientMeasures,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, IngredientModel)
+            .set(IngredientModel.density, measures.density)
+            .set(IngredientModel.piece_weight, measures.piece_weight)
+            .condition(IngredientModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Sets the dietary properties of the ingredient and marks it as classified.
+    #[instrument(name = "Ingredient::set_dietary", skip(exe))]
+    pub async fn set_dietary(
+        &self,
+        exe: impl Executor<'_>,
+        meat: bool,
+        dairy: bool,
+        animal_product: bool,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, IngredientModel)
+            .set(IngredientModel.classified, true)
+            .set(IngredientModel.meat, meat)
+            .set(IngredientModel.dairy, dairy)
+            .set(IngredientModel.animal_product, animal_product)
+            .condition(IngredientModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
 }
 
 /// Represents different units of measurement.
@@ -124,11 +1017,88 @@ pub enum Units {
     None = 7,
 }
 
+/// The physical dimension of a unit.
+///
+/// Amounts can only be converted between units of the same dimension.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnitDimension {
+    /// Base unit is gram
+    Mass,
+    /// Base unit is milliliter
+    Volume,
+    /// Pieces, i.e. [`Units::None`]
+    Count,
+}
+
+impl Units {
+    /// Returns the physical dimension of the unit.
+    pub fn dimension(self) -> UnitDimension {
+        match self {
+            Units::Gram | Units::Kilogram => UnitDimension::Mass,
+            Units::Cup | Units::Liter | Units::Milliliter | Units::Tablespoon | Units::Teaspoon => {
+                UnitDimension::Volume
+            }
+            Units::None => UnitDimension::Count,
+        }
+    }
+
+    /// Returns how many base units of its dimension one of this unit is.
+    ///
+    /// Cups and spoons use their metric sizes.
+    fn base_factor(self) -> f64 {
+        match self {
+            Units::Gram | Units::Milliliter | Units::None => 1.0,
+            Units::Kilogram | Units::Liter => 1000.0,
+            Units::Cup => 250.0,
+            Units::Tablespoon => 15.0,
+            Units::Teaspoon => 5.0,
+        }
+    }
+
+    /// Converts an amount of an ingredient given in this unit to another unit.
+    ///
+    /// Units of different dimensions are converted by weight using the ingredient's measures.
+    /// Returns `None` if the units are of different dimensions and a needed measure is unknown.
+    pub fn convert(self, amount: f64, target: Units, measures: IngredientMeasures) -> Option<f64> {
+        let (source_dimension, target_dimension) = (self.dimension(), target.dimension());
+        if source_dimension == target_dimension {
+            return Some(amount * self.base_factor() / target.base_factor());
+        }
+
+        let grams = amount * self.base_factor() * measures.grams_per(source_dimension)?;
+        Some(grams / measures.grams_per(target_dimension)? / target.base_factor())
+    }
+
+    /// Converts an amount of an ingredient given in this unit to grams.
+    ///
+    /// Returns `None` if the unit is not a unit of mass and a needed measure is unknown.
+    pub fn to_grams(self, amount: f64, measures: IngredientMeasures) -> Option<f64> {
+        self.convert(amount, Units::Gram, measures)
+    }
+}
+
+/// Normalizes an ingredient name to be stored in the database.
+///
+/// See [`normalize::ingredient_name`] for the rules.
+fn normalized_name(name: &str) -> anyhow::Result<MaxStr<255>> {
+    max_str::truncated(normalize::ingredient_name(name))
+}
+
 impl From<IngredientModel> for Ingredient {
     fn from(model: IngredientModel) -> Self {
         Self {
             uuid: IngredientUuid { 0: model.uuid },
             name: model.name,
+            food: model.food.map(FoodUuid::from_model),
+            category: model.category.map(IngredientCategoryUuid::from_model),
+            classified: model.classified,
+            meat: model.meat,
+            dairy: model.dairy,
+            animal_product: model.animal_product,
+            measures: IngredientMeasures {
+                density: model.density,
+                piece_weight: model.piece_weight,
+            },
         }
     }
 }
@@ -0,0 +1,62 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::Model;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::models::jobs::JobKind;
+use crate::models::jobs::JobStatus;
+
+/// Represents a background job waiting to be run or already run.
+#[derive(Model)]
+#[rorm(rename = "job")]
+pub struct JobModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// The kind of work the job does.
+    pub kind: JobKind,
+
+    /// The state of the job.
+    pub status: JobStatus,
+
+    /// How often the job has been started.
+    pub attempts: i32,
+
+    /// How often the job is started before it is given up.
+    pub max_attempts: i32,
+
+    /// The earliest point in time the job may be started.
+    pub run_at: OffsetDateTime,
+
+    /// The point in time a worker has started the job.
+    ///
+    /// Used to detect jobs whose worker died.
+    pub locked_at: Option<OffsetDateTime>,
+
+    /// The error of the last failed attempt.
+    pub last_error: Option<MaxStr<1024>>,
+
+    pub created_at: OffsetDateTime,
+
+    /// The point in time the job has succeeded or has been given up.
+    pub finished_at: Option<OffsetDateTime>,
+}
+
+/// Represents a cron-style schedule enqueueing a job regularly.
+#[derive(Model)]
+#[rorm(rename = "job_schedule")]
+pub struct JobScheduleModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// The kind of job which is enqueued.
+    ///
+    /// Unique by an index created in the migration, rorm doesn't support unique choices.
+    pub kind: JobKind,
+
+    /// The cron expression in UTC.
+    pub cron: MaxStr<255>,
+
+    /// The point in time the job is enqueued next.
+    pub next_run_at: OffsetDateTime,
+}
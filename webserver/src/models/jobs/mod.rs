@@ -0,0 +1,322 @@
+//! Background jobs stored in the database.
+//!
+//! Jobs are claimed with `FOR UPDATE SKIP LOCKED`, so multiple workers
+//! (even on different instances) never run the same job twice at the same time.
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::executor::All;
+use galvyn::rorm::db::sql::value::Value;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::or;
+use galvyn::rorm::DbEnum;
+use time::Duration;
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::jobs::db::JobModel;
+use crate::models::jobs::db::JobScheduleModel;
+use crate::utils::max_str;
+
+pub(in crate::models) mod db;
+
+/// How often a job is started before it is given up
+const MAX_ATTEMPTS: i32 = 5;
+
+/// The kinds of work background jobs do.
+#[derive(DbEnum, Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum JobKind {
+    /// Deletes ingredients which are neither used by a recipe, stocked in the pantry,
+    /// on a shopping list nor part of a substitution
+    PurgeUnusedIngredients = 0,
+}
+
+/// The states of a background job.
+#[derive(DbEnum, Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum JobStatus {
+    /// Waiting to be started, possibly after a failed attempt
+    Pending = 0,
+    /// Currently run by a worker
+    Running = 1,
+    /// Finished successfully
+    Succeeded = 2,
+    /// Given up after too many failed attempts
+    Failed = 3,
+}
+
+/// Domain representation of a claimed background job.
+#[derive(Debug, Clone)]
+pub struct Job {
+    /// Stable identifier for this job.
+    pub uuid: JobUuid,
+
+    /// The kind of work the job does.
+    pub kind: JobKind,
+
+    /// How often the job has been started, including the current attempt.
+    pub attempts: i32,
+
+    /// How often the job is started before it is given up.
+    pub max_attempts: i32,
+}
+
+/// Strongly typed UUID for background jobs.
+#[derive(Debug, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct JobUuid(pub Uuid);
+
+impl Job {
+    /// Adds a job to the queue which may be started immediately.
+    #[instrument(name = "Job::enqueue", skip(exe))]
+    pub async fn enqueue(exe: impl Executor<'_>, kind: JobKind) -> anyhow::Result<JobUuid> {
+        let now = OffsetDateTime::now_utc();
+        let uuid = Uuid::new_v4();
+        rorm::insert(exe, JobModel)
+            .return_nothing()
+            .single(&JobModel {
+                uuid,
+                kind,
+                status: JobStatus::Pending,
+                attempts: 0,
+                max_attempts: MAX_ATTEMPTS,
+                run_at: now,
+                locked_at: None,
+                last_error: None,
+                created_at: now,
+                finished_at: None,
+            })
+            .await?;
+        Ok(JobUuid(uuid))
+    }
+
+    /// Claims the next due job and marks it as running.
+    ///
+    /// Running jobs which have been locked for longer than `lock_timeout` are
+    /// considered abandoned by a crashed worker and are claimed again.
+    /// Abandoned jobs which have run out of attempts are given up.
+    #[instrument(name = "Job::claim_next", skip(exe))]
+    pub async fn claim_next(
+        exe: impl Executor<'_>,
+        lock_timeout: Duration,
+    ) -> anyhow::Result<Option<Self>> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let sql = r#"UPDATE "job" SET "status" = 'Failed', "locked_at" = NULL, "finished_at" = now(), "last_error" = 'The job has been abandoned by its worker' WHERE "status" = 'Running' AND "locked_at" < now() - $1::bigint * interval '1 second' AND "attempts" >= "max_attempts""#;
+        guard
+            .get_transaction()
+            .execute::<All>(
+                sql.to_string(),
+                vec![Value::I64(lock_timeout.whole_seconds())],
+            )
+            .await?;
+
+        let sql = r#"UPDATE "job" SET "status" = 'Running', "attempts" = "attempts" + 1, "locked_at" = now() WHERE "uuid" = (SELECT "uuid" FROM "job" WHERE ("status" = 'Pending' AND "run_at" <= now()) OR ("status" = 'Running' AND "locked_at" < now() - $1::bigint * interval '1 second' AND "attempts" < "max_attempts") ORDER BY "run_at" LIMIT 1 FOR UPDATE SKIP LOCKED) RETURNING "uuid""#;
+        let rows = guard
+            .get_transaction()
+            .execute::<All>(
+                sql.to_string(),
+                vec![Value::I64(lock_timeout.whole_seconds())],
+            )
+            .await?;
+
+        let Some(row) = rows.first() else {
+            guard.commit().await?;
+            return Ok(None);
+        };
+        let uuid = row.get::<Uuid>(0)?;
+
+        let (kind, attempts, max_attempts) = rorm::query(
+            guard.get_transaction(),
+            (JobModel.kind, JobModel.attempts, JobModel.max_attempts),
+        )
+        .condition(JobModel.uuid.equals(uuid))
+        .one()
+        .await?;
+
+        guard.commit().await?;
+        Ok(Some(Self {
+            uuid: JobUuid(uuid),
+            kind,
+            attempts,
+            max_attempts,
+        }))
+    }
+
+    /// Marks the job as succeeded.
+    #[instrument(name = "Job::succeed", skip(exe))]
+    pub async fn succeed(&self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::update(exe, JobModel)
+            .set(JobModel.status, JobStatus::Succeeded)
+            .set(JobModel.locked_at, None)
+            .set(JobModel.finished_at, Some(OffsetDateTime::now_utc()))
+            .condition(JobModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Records a failed attempt of the job.
+    ///
+    /// The job is started again at `retry_at` or given up if there is none.
+    #[instrument(name = "Job::fail", skip(exe))]
+    pub async fn fail(
+        &self,
+        exe: impl Executor<'_>,
+        error: String,
+        retry_at: Option<OffsetDateTime>,
+    ) -> anyhow::Result<()> {
+        let error = max_str::truncated::<1024>(error)?;
+        let update = rorm::update(exe, JobModel)
+            .set(JobModel.locked_at, None)
+            .set(JobModel.last_error, Some(error));
+
+        match retry_at {
+            Some(retry_at) => {
+                update
+                    .set(JobModel.status, JobStatus::Pending)
+                    .set(JobModel.run_at, retry_at)
+                    .condition(JobModel.uuid.equals(self.uuid.0))
+                    .await?
+            }
+            None => {
+                update
+                    .set(JobModel.status, JobStatus::Failed)
+                    .set(JobModel.finished_at, Some(OffsetDateTime::now_utc()))
+                    .condition(JobModel.uuid.equals(self.uuid.0))
+                    .await?
+            }
+        };
+        Ok(())
+    }
+
+    /// Deletes succeeded and failed jobs which have been created before a point in time.
+    #[instrument(name = "Job::delete_finished", skip(exe))]
+    pub async fn delete_finished(
+        exe: impl Executor<'_>,
+        before: OffsetDateTime,
+    ) -> anyhow::Result<()> {
+        rorm::delete(exe, JobModel)
+            .condition(and![
+                or![
+                    JobModel.status.equals(JobStatus::Succeeded),
+                    JobModel.status.equals(JobStatus::Failed),
+                ],
+                JobModel.created_at.less_than(before),
+            ])
+            .await?;
+        Ok(())
+    }
+}
+
+/// Domain representation of a cron-style schedule for a kind of job.
+#[derive(Debug, Clone)]
+pub struct JobSchedule {
+    /// The kind of job which is enqueued.
+    pub kind: JobKind,
+
+    /// The cron expression in UTC.
+    pub cron: MaxStr<255>,
+
+    /// The point in time the job is enqueued next.
+    pub next_run_at: OffsetDateTime,
+}
+
+impl JobSchedule {
+    /// Lists all schedules.
+    #[instrument(name = "JobSchedule::query_all", skip(exe))]
+    pub async fn query_all(exe: impl Executor<'_>) -> anyhow::Result<Vec<Self>> {
+        let schedules: Vec<_> = rorm::query(exe, JobScheduleModel)
+            .stream()
+            .map_ok(JobSchedule::from)
+            .try_collect()
+            .await?;
+        Ok(schedules)
+    }
+
+    /// Creates the schedule of a kind of job or replaces its cron expression.
+    ///
+    /// The next run is only changed if the cron expression has changed.
+    #[instrument(name = "JobSchedule::create_or_update", skip(exe))]
+    pub async fn create_or_update(
+        exe: impl Executor<'_>,
+        kind: JobKind,
+        cron: MaxStr<255>,
+        next_run_at: OffsetDateTime,
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let existing = rorm::query(guard.get_transaction(), JobScheduleModel.cron)
+            .condition(JobScheduleModel.kind.equals(kind))
+            .optional()
+            .await?;
+
+        match existing {
+            Some(existing) if *existing == *cron => {}
+            Some(_) => {
+                rorm::update(guard.get_transaction(), JobScheduleModel)
+                    .set(JobScheduleModel.cron, cron)
+                    .set(JobScheduleModel.next_run_at, next_run_at)
+                    .condition(JobScheduleModel.kind.equals(kind))
+                    .await?;
+            }
+            None => {
+                rorm::insert(guard.get_transaction(), JobScheduleModel)
+                    .return_nothing()
+                    .single(&JobScheduleModel {
+                        uuid: Uuid::new_v4(),
+                        kind,
+                        cron,
+                        next_run_at,
+                    })
+                    .await?;
+            }
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+
+    /// Moves the schedule to its next run and enqueues its job.
+    ///
+    /// Returns `false` without enqueueing anything if another worker has already done so.
+    #[instrument(name = "JobSchedule::enqueue", skip(exe))]
+    pub async fn enqueue(
+        &self,
+        exe: impl Executor<'_>,
+        next_run_at: OffsetDateTime,
+    ) -> anyhow::Result<bool> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let updated = rorm::update(guard.get_transaction(), JobScheduleModel)
+            .set(JobScheduleModel.next_run_at, next_run_at)
+            .condition(and![
+                JobScheduleModel.kind.equals(self.kind),
+                JobScheduleModel.next_run_at.equals(self.next_run_at),
+            ])
+            .await?;
+
+        if updated == 0 {
+            return Ok(false);
+        }
+
+        Job::enqueue(guard.get_transaction(), self.kind).await?;
+
+        guard.commit().await?;
+        Ok(true)
+    }
+}
+
+impl From<JobScheduleModel> for JobSchedule {
+    fn from(model: JobScheduleModel) -> Self {
+        Self {
+            kind: model.kind,
+            cron: model.cron,
+            next_run_at: model.next_run_at,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use time::Date;
+use uuid::Uuid;
+
+use crate::models::account::db::AccountModel;
+use crate::models::households::db::HouseholdModel;
+use crate::models::meal_plans::MealSlot;
+use crate::models::recipes::db::RecipeModel;
+
+/// Represents a meal planned for a specific day.
+///
+/// A meal is either a recipe or a free text (e.g. "leftovers").
+#[derive(Model)]
+#[rorm(rename = "meal_plan_entry")]
+pub struct MealPlanEntryModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// The day the meal is planned for.
+    pub date: Date,
+
+    /// The meal of the day.
+    pub slot: MealSlot,
+
+    /// The planned recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub recipe: Option<ForeignModel<RecipeModel>>,
+
+    /// A free text describing the meal if no recipe is planned.
+    pub text: Option<MaxStr<255>>,
+
+    /// The number of servings to cook.
+    pub servings: i16,
+
+    /// A foreign key referencing the household owning the meal plan.
+    #[rorm(on_delete = "Cascade")]
+    pub household: ForeignModel<HouseholdModel>,
+
+    /// The account which planned the meal.
+    #[rorm(on_delete = "Cascade")]
+    pub account: ForeignModel<AccountModel>,
+}
@@ -0,0 +1,186 @@
+//! Meals planned for specific days.
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModelByField;
+use galvyn::rorm::DbEnum;
+use time::Date;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::account::AccountUuid;
+use crate::models::households::HouseholdUuid;
+use crate::models::meal_plans::db::MealPlanEntryModel;
+use crate::models::recipes::RecipeUuid;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of a planned meal.
+#[derive(Debug, Clone)]
+pub struct MealPlanEntry {
+    /// Stable identifier for this entry.
+    pub uuid: MealPlanEntryUuid,
+
+    /// The day the meal is planned for.
+    pub date: Date,
+
+    /// The meal of the day.
+    pub slot: MealSlot,
+
+    /// The planned recipe.
+    pub recipe: Option<RecipeUuid>,
+
+    /// A free text describing the meal if no recipe is planned.
+    pub text: Option<MaxStr<255>>,
+
+    /// The number of servings to cook.
+    pub servings: i16,
+
+    /// The account which planned the meal.
+    pub account: AccountUuid,
+}
+
+/// Strongly typed UUID for meal plan entries.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct MealPlanEntryUuid(pub Uuid);
+
+/// The meals of a day.
+#[derive(
+    DbEnum, Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, PartialOrd, Eq, Ord,
+)]
+pub enum MealSlot {
+    Breakfast = 0,
+    Lunch = 1,
+    Dinner = 2,
+    Snack = 3,
+}
+
+impl MealPlanEntry {
+    /// Lists all entries of a household planned between two days (both inclusive).
+    ///
+    /// The entries are ordered by day and meal.
+    #[instrument(name = "MealPlanEntry::query_range", skip(exe))]
+    pub async fn query_range(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        from: Date,
+        to: Date,
+        filter_account: Option<AccountUuid>,
+    ) -> anyhow::Result<Vec<Self>> {
+        let condition = and![
+            Some(MealPlanEntryModel.household.equals(household.0)),
+            Some(MealPlanEntryModel.date.greater_equals(from)),
+            Some(MealPlanEntryModel.date.less_equals(to)),
+            filter_account.map(|account| MealPlanEntryModel.account.equals(account.get_inner())),
+        ];
+
+        let mut result: Vec<_> = rorm::query(exe, MealPlanEntryModel)
+            .condition(condition)
+            .stream()
+            .map_ok(MealPlanEntry::from)
+            .try_collect()
+            .await?;
+        result.sort_by_key(|entry| (entry.date, entry.slot));
+        Ok(result)
+    }
+
+    /// Looks up a single entry by its UUID.
+    #[instrument(name = "MealPlanEntry::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        uuid: &MealPlanEntryUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let entry = rorm::query(exe, MealPlanEntryModel)
+            .condition(and![
+                MealPlanEntryModel.uuid.equals(uuid.0),
+                MealPlanEntryModel.household.equals(household.0),
+            ])
+            .optional()
+            .await?;
+        Ok(entry.map(Self::from))
+    }
+
+    /// Plans a new meal.
+    #[expect(clippy::too_many_arguments)]
+    #[instrument(name = "MealPlanEntry::create", skip(exe))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        date: Date,
+        slot: MealSlot,
+        recipe: Option<RecipeUuid>,
+        text: Option<MaxStr<255>>,
+        servings: i16,
+        account: AccountUuid,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Self> {
+        let model = rorm::insert(exe, MealPlanEntryModel)
+            .single(&MealPlanEntryModel {
+                uuid: Uuid::new_v4(),
+                date,
+                slot,
+                recipe: recipe.map(|recipe| ForeignModelByField(recipe.0)),
+                text,
+                servings,
+                household: ForeignModelByField(household.0),
+                account: ForeignModelByField(account.get_inner()),
+            })
+            .await?;
+        Ok(MealPlanEntry::from(model))
+    }
+
+    /// Update the day, meal, recipe or text and servings of an entry.
+    #[instrument(name = "MealPlanEntry::update", skip(exe))]
+    pub async fn update(
+        &self,
+        exe: impl Executor<'_>,
+        date: Date,
+        slot: MealSlot,
+        recipe: Option<RecipeUuid>,
+        text: Option<MaxStr<255>>,
+        servings: i16,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, MealPlanEntryModel)
+            .set(MealPlanEntryModel.date, date)
+            .set(MealPlanEntryModel.slot, slot)
+            .set(
+                MealPlanEntryModel.recipe,
+                recipe.map(|recipe| ForeignModelByField(recipe.0)),
+            )
+            .set(MealPlanEntryModel.text, text)
+            .set(MealPlanEntryModel.servings, servings)
+            .condition(MealPlanEntryModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes the entry.
+    #[instrument(name = "MealPlanEntry::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, MealPlanEntryModel)
+            .condition(MealPlanEntryModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+}
+
+impl From<MealPlanEntryModel> for MealPlanEntry {
+    fn from(model: MealPlanEntryModel) -> Self {
+        Self {
+            uuid: MealPlanEntryUuid(model.uuid),
+            date: model.date,
+            slot: model.slot,
+            recipe: model.recipe.map(|recipe| RecipeUuid(recipe.0)),
+            text: model.text,
+            servings: model.servings,
+            account: AccountUuid::new_from_model(model.account),
+        }
+    }
+}
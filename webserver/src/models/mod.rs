@@ -1,6 +1,22 @@
 pub mod account;
+pub mod collections;
+pub mod foods;
+pub mod households;
+pub mod ingredient_allergens;
+pub mod ingredient_categories;
+pub mod ingredient_prices;
+pub mod ingredient_seasons;
+pub mod ingredient_substitutions;
 pub mod ingredients;
+pub mod jobs;
+pub mod meal_plans;
+pub mod pantry_items;
+pub mod recipe_cooks;
 pub mod recipe_ingredients;
+pub mod recipe_notes;
+pub mod recipe_ratings;
 pub mod recipe_steps;
 pub mod recipes;
+pub mod recommendations;
+pub mod shopping_lists;
 pub mod tags;
@@ -0,0 +1,32 @@
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use time::Date;
+use uuid::Uuid;
+
+use crate::models::ingredients::db::IngredientModel;
+use crate::models::ingredients::Units;
+use crate::models::pantry_items::PantryLocation;
+
+/// Represents a stock of an ingredient in the pantry.
+#[derive(Model)]
+#[rorm(rename = "pantry_item")]
+pub struct PantryItemModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the stored ingredient.
+    #[rorm(on_delete = "Cascade")]
+    pub ingredient: ForeignModel<IngredientModel>,
+
+    /// The stored quantity.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// Where the ingredient is stored.
+    pub location: PantryLocation,
+
+    /// The best-before date of the ingredient.
+    pub best_before: Option<Date>,
+}
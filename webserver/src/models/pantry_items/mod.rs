@@ -0,0 +1,307 @@
+//! Ingredients in stock at home.
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::executor::All;
+use galvyn::rorm::db::sql::value::Value;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::prelude::ForeignModelByField;
+use galvyn::rorm::DbEnum;
+use time::Date;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredients::IngredientMeasures;
+use crate::models::ingredients::IngredientUuid;
+use crate::models::ingredients::Units;
+use crate::models::pantry_items::db::PantryItemModel;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of a stock of an ingredient.
+#[derive(Debug, Clone)]
+pub struct PantryItem {
+    /// Stable identifier for this item.
+    pub uuid: PantryItemUuid,
+
+    /// The stored ingredient.
+    pub ingredient: IngredientUuid,
+
+    /// The stored quantity.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// Where the ingredient is stored.
+    pub location: PantryLocation,
+
+    /// The best-before date of the ingredient.
+    pub best_before: Option<Date>,
+}
+
+/// Strongly typed UUID for pantry items.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct PantryItemUuid(pub Uuid);
+
+/// The places ingredients are stored at.
+#[derive(DbEnum, Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum PantryLocation {
+    Cupboard = 0,
+    Fridge = 1,
+    Freezer = 2,
+}
+
+impl PantryItem {
+    /// Lists all items of a household with optional location filter.
+    ///
+    /// The items are ordered by their best-before date, items without one come last.
+    #[instrument(name = "PantryItem::query_all", skip(exe))]
+    pub async fn query_all(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        filter_location: Option<PantryLocation>,
+    ) -> anyhow::Result<Vec<Self>> {
+        let condition = and![
+            Some(PantryItemModel.ingredient.household.equals(household.0)),
+            filter_location.map(|location| PantryItemModel.location.equals(location)),
+        ];
+
+        let mut result: Vec<_> = rorm::query(exe, PantryItemModel)
+            .condition(condition)
+            .stream()
+            .map_ok(PantryItem::from)
+            .try_collect()
+            .await?;
+        result.sort_by_key(|item| (item.best_before.is_none(), item.best_before));
+        Ok(result)
+    }
+
+    /// Lists all items of a household whose best-before date is on or before a day, earliest first.
+    #[instrument(name = "PantryItem::query_expiring", skip(exe))]
+    pub async fn query_expiring(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        until: Date,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut result: Vec<_> = rorm::query(exe, PantryItemModel)
+            .condition(and![
+                PantryItemModel.ingredient.household.equals(household.0),
+                PantryItemModel.best_before.is_some(),
+            ])
+            .stream()
+            .map_ok(PantryItem::from)
+            .try_collect()
+            .await?;
+        // rorm can't compare nullable columns by order
+        result.retain(|item| {
+            item.best_before
+                .is_some_and(|best_before| best_before <= until)
+        });
+        result.sort_by_key(|item| item.best_before);
+        Ok(result)
+    }
+
+    /// Lists all stocks of an ingredient.
+    ///
+    /// The items are ordered by their best-before date, items without one come last.
+    #[instrument(name = "PantryItem::query_by_ingredient", skip(exe))]
+    pub async fn query_by_ingredient(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut result: Vec<_> = rorm::query(exe, PantryItemModel)
+            .condition(
+                PantryItemModel
+                    .ingredient
+                    .equals(ingredient_uuid.get_inner()),
+            )
+            .stream()
+            .map_ok(PantryItem::from)
+            .try_collect()
+            .await?;
+        result.sort_by_key(|item| (item.best_before.is_none(), item.best_before));
+        Ok(result)
+    }
+
+    /// Looks up a single item by its UUID.
+    #[instrument(name = "PantryItem::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        uuid: &PantryItemUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let item = rorm::query(exe, PantryItemModel)
+            .condition(and![
+                PantryItemModel.uuid.equals(uuid.0),
+                PantryItemModel.ingredient.household.equals(household.0),
+            ])
+            .optional()
+            .await?;
+        Ok(item.map(Self::from))
+    }
+
+    /// Adds a new stock of an ingredient.
+    #[instrument(name = "PantryItem::create", skip(exe))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+        amount: f64,
+        unit: Units,
+        location: PantryLocation,
+        best_before: Option<Date>,
+    ) -> anyhow::Result<Self> {
+        let model = rorm::insert(exe, PantryItemModel)
+            .single(&PantryItemModel {
+                uuid: Uuid::new_v4(),
+                ingredient: ForeignModelByField(ingredient_uuid.get_inner()),
+                amount,
+                unit,
+                location,
+                best_before,
+            })
+            .await?;
+        Ok(PantryItem::from(model))
+    }
+
+    /// Update an item's quantity, location and best-before date.
+    #[instrument(name = "PantryItem::update", skip(exe))]
+    pub async fn update(
+        &self,
+        exe: impl Executor<'_>,
+        amount: f64,
+        unit: Units,
+        location: PantryLocation,
+        best_before: Option<Date>,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, PantryItemModel)
+            .set(PantryItemModel.amount, amount)
+            .set(PantryItemModel.unit, unit)
+            .set(PantryItemModel.location, location)
+            .set(PantryItemModel.best_before, best_before)
+            .condition(PantryItemModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Changes the stored quantity by a delta given in any convertible unit.
+    ///
+    /// The item is deleted once it is used up.
+    ///
+    /// Returns `None` if the unit can't be converted to the unit of the item.
+    #[instrument(name = "PantryItem::adjust", skip(exe))]
+    pub async fn adjust(
+        self,
+        exe: impl Executor<'_>,
+        delta: f64,
+        unit: Units,
+        measures: IngredientMeasures,
+    ) -> anyhow::Result<Option<f64>> {
+        let Some(delta) = unit.convert(delta, self.unit, measures) else {
+            return Ok(None);
+        };
+
+        let mut guard = exe.ensure_transaction().await?;
+
+        // Apply the delta in the database to not lose concurrent adjustments
+        let sql = r#"UPDATE "pantry_item" SET "amount" = "amount" + $1 WHERE "uuid" = $2 RETURNING "amount""#;
+        let rows = guard
+            .get_transaction()
+            .execute::<All>(
+                sql.to_string(),
+                vec![Value::F64(delta), Value::Uuid(self.uuid.0)],
+            )
+            .await?;
+
+        let amount = match rows.first() {
+            Some(row) => row.get::<f64>(0)?,
+            None => 0.0,
+        };
+        if amount <= 0.0 {
+            self.delete(guard.get_transaction()).await?;
+            guard.commit().await?;
+            return Ok(Some(0.0));
+        }
+
+        guard.commit().await?;
+        Ok(Some(amount))
+    }
+
+    /// Removes a quantity of an ingredient from the stock.
+    ///
+    /// The stocks with the earliest best-before date are used first.
+    /// Stocks in units which can't be converted are left untouched.
+    ///
+    /// Returns the quantity which couldn't be covered by the stock (in the given unit).
+    #[instrument(name = "PantryItem::consume", skip(exe))]
+    pub async fn consume(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+        amount: f64,
+        unit: Units,
+        measures: IngredientMeasures,
+    ) -> anyhow::Result<f64> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        // Lock the stocks so concurrent consumptions can't use the same quantity twice
+        let sql = r#"SELECT "uuid" FROM "pantry_item" WHERE "ingredient" = $1 FOR UPDATE"#;
+        guard
+            .get_transaction()
+            .execute::<All>(
+                sql.to_string(),
+                vec![Value::Uuid(ingredient_uuid.get_inner())],
+            )
+            .await?;
+
+        let mut remaining = amount;
+        for item in Self::query_by_ingredient(guard.get_transaction(), ingredient_uuid).await? {
+            if remaining <= 0.0 {
+                break;
+            }
+
+            let Some(available) = item.unit.convert(item.amount, unit, measures) else {
+                continue;
+            };
+
+            if available <= remaining {
+                remaining -= available;
+                item.delete(guard.get_transaction()).await?;
+            } else {
+                item.adjust(guard.get_transaction(), -remaining, unit, measures)
+                    .await?;
+                remaining = 0.0;
+            }
+        }
+
+        guard.commit().await?;
+        Ok(remaining)
+    }
+
+    /// Deletes the item.
+    #[instrument(name = "PantryItem::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, PantryItemModel)
+            .condition(PantryItemModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+}
+
+impl From<PantryItemModel> for PantryItem {
+    fn from(model: PantryItemModel) -> Self {
+        Self {
+            uuid: PantryItemUuid(model.uuid),
+            ingredient: IngredientUuid::from_model(model.ingredient),
+            amount: model.amount,
+            unit: model.unit,
+            location: model.location,
+            best_before: model.best_before,
+        }
+    }
+}
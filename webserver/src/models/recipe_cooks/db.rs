@@ -0,0 +1,30 @@
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::models::account::db::AccountModel;
+use crate::models::households::db::HouseholdModel;
+use crate::models::recipes::db::RecipeModel;
+
+/// Represents a single time a household has cooked a recipe.
+#[derive(Model)]
+#[rorm(rename = "recipe_cook")]
+pub struct RecipeCookModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the cooked recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub recipe: ForeignModel<RecipeModel>,
+
+    /// A foreign key referencing the household which cooked the recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub household: ForeignModel<HouseholdModel>,
+
+    /// A foreign key referencing the account which cooked the recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub account: ForeignModel<AccountModel>,
+
+    pub cooked_at: OffsetDateTime,
+}
@@ -0,0 +1,89 @@
+//! The history of cooked recipes.
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::prelude::ForeignModelByField;
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::account::AccountUuid;
+use crate::models::households::HouseholdUuid;
+use crate::models::recipe_cooks::db::RecipeCookModel;
+use crate::models::recipes::RecipeUuid;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of a single time a recipe has been cooked.
+#[derive(Debug, Clone)]
+pub struct RecipeCook {
+    /// The cooked recipe.
+    pub recipe: RecipeUuid,
+
+    /// The point in time the recipe was cooked.
+    pub cooked_at: OffsetDateTime,
+}
+
+impl RecipeCook {
+    /// Lists all recipes an account has cooked, most recent first.
+    #[instrument(name = "RecipeCook::query_by_account", skip(exe))]
+    pub async fn query_by_account(
+        exe: impl Executor<'_>,
+        account: &AccountUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, RecipeCookModel)
+            .condition(RecipeCookModel.account.equals(account.get_inner()))
+            .order_desc(RecipeCookModel.cooked_at)
+            .stream()
+            .map_ok(RecipeCook::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Lists all recipes a household has cooked, most recent first.
+    #[instrument(name = "RecipeCook::query_by_household", skip(exe))]
+    pub async fn query_by_household(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, RecipeCookModel)
+            .condition(RecipeCookModel.household.equals(household.0))
+            .order_desc(RecipeCookModel.cooked_at)
+            .stream()
+            .map_ok(RecipeCook::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Records that an account has just cooked a recipe.
+    #[instrument(name = "RecipeCook::create", skip(exe))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        recipe: &RecipeUuid,
+        account: AccountUuid,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Self> {
+        let model = rorm::insert(exe, RecipeCookModel)
+            .single(&RecipeCookModel {
+                uuid: Uuid::new_v4(),
+                recipe: ForeignModelByField(recipe.0),
+                household: ForeignModelByField(household.0),
+                account: ForeignModelByField(account.get_inner()),
+                cooked_at: OffsetDateTime::now_utc(),
+            })
+            .await?;
+        Ok(RecipeCook::from(model))
+    }
+}
+
+impl From<RecipeCookModel> for RecipeCook {
+    fn from(model: RecipeCookModel) -> Self {
+        Self {
+            recipe: RecipeUuid(model.recipe.0),
+            cooked_at: model.cooked_at,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use galvyn::rorm::fields::types::MaxStr;
 use galvyn::rorm::prelude::ForeignModel;
 use galvyn::rorm::Model;
 use uuid::Uuid;
@@ -28,4 +29,11 @@ pub struct RecipeIngredientModel {
 
     /// The unit of measurement for the ingredient.
     pub unit: Units,
+
+    /// How the ingredient is prepared for the recipe, e.g. "finely diced".
+    pub preparation: Option<MaxStr<255>>,
+
+    /// Whether the recipe can be made without the ingredient, e.g. a garnish.
+    #[rorm(default = false)]
+    pub optional: bool,
 }
@@ -6,6 +6,7 @@ use galvyn::core::re_exports::schemars::JsonSchema;
 use galvyn::core::re_exports::serde::Deserialize;
 use galvyn::core::re_exports::serde::Serialize;
 use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
 use galvyn::rorm::prelude::ForeignModelByField;
 use tracing::instrument;
 use uuid::Uuid;
@@ -34,6 +35,12 @@ pub struct RecipeIngredient {
 
     /// The unit of measurement for the quantity.
     pub unit: Units,
+
+    /// How the ingredient is prepared for the recipe.
+    pub preparation: Option<MaxStr<255>>,
+
+    /// Whether the recipe can be made without the ingredient.
+    pub optional: bool,
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema)]
@@ -63,6 +70,8 @@ impl RecipeIngredient {
         ingredient_uuid: IngredientUuid,
         amount: i64,
         unit: Units,
+        preparation: Option<MaxStr<255>>,
+        optional: bool,
     ) -> anyhow::Result<RecipeIngredient> {
         let model = rorm::insert(exe, RecipeIngredientModel)
             .single(&RecipeIngredientModel {
@@ -71,6 +80,8 @@ impl RecipeIngredient {
                 ingredients: ForeignModelByField(ingredient_uuid.get_inner()),
                 amount,
                 unit,
+                preparation,
+                optional,
             })
             .await?;
 
@@ -97,6 +108,8 @@ impl From<RecipeIngredientModel> for RecipeIngredient {
             ingredients: IngredientUuid::from_model(model.ingredients),
             unit: model.unit,
             amount: model.amount,
+            preparation: model.preparation,
+            optional: model.optional,
         }
     }
 }
@@ -0,0 +1,37 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::models::account::db::AccountModel;
+use crate::models::recipe_steps::db::RecipeStepModel;
+use crate::models::recipes::db::RecipeModel;
+
+/// Represents a personal note of an account on a recipe or one of its steps.
+#[derive(Model)]
+#[rorm(rename = "recipe_note")]
+pub struct RecipeNoteModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the account which wrote the note.
+    #[rorm(on_delete = "Cascade")]
+    pub account: ForeignModel<AccountModel>,
+
+    /// A foreign key referencing the recipe the note belongs to.
+    #[rorm(on_delete = "Cascade")]
+    pub recipe: ForeignModel<RecipeModel>,
+
+    /// A foreign key referencing the step the note is attached to.
+    ///
+    /// `None` if the note is about the recipe as a whole.
+    /// Step notes are removed together with their step.
+    #[rorm(on_delete = "Cascade")]
+    pub step: Option<ForeignModel<RecipeStepModel>>,
+
+    /// The text of the note in markdown.
+    pub note: MaxStr<65535>,
+
+    pub updated_at: OffsetDateTime,
+}
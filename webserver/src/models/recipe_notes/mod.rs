@@ -0,0 +1,187 @@
+//! Personal notes of accounts on recipes and their steps.
+
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::rorm::and;
+use galvyn::rorm::conditions::Condition;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModelByField;
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::account::AccountUuid;
+use crate::models::recipe_notes::db::RecipeNoteModel;
+use crate::models::recipe_steps::RecipeStepUuid;
+use crate::models::recipes::RecipeUuid;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of a personal note.
+///
+/// Notes are only visible for the account which wrote them.
+#[derive(Debug, Clone)]
+pub struct RecipeNote {
+    /// Stable identifier for this note.
+    pub uuid: Uuid,
+
+    /// The account which wrote the note.
+    pub account: AccountUuid,
+
+    /// The recipe the note belongs to.
+    pub recipe: RecipeUuid,
+
+    /// The step the note is attached to, `None` for a note on the whole recipe.
+    pub step: Option<RecipeStepUuid>,
+
+    /// The text of the note in markdown.
+    pub note: MaxStr<65535>,
+
+    /// The point in time the note was last changed.
+    pub updated_at: OffsetDateTime,
+}
+
+impl RecipeNote {
+    /// Lists all notes an account has written on a recipe.
+    #[instrument(name = "RecipeNote::query_by_recipe", skip(exe))]
+    pub async fn query_by_recipe(
+        exe: impl Executor<'_>,
+        account: &AccountUuid,
+        recipe: &RecipeUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, RecipeNoteModel)
+            .condition(and![
+                RecipeNoteModel.account.equals(account.get_inner()),
+                RecipeNoteModel.recipe.equals(recipe.0),
+            ])
+            .stream()
+            .map_ok(RecipeNote::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Lists the notes of all accounts which are attached to a step of a recipe.
+    #[instrument(name = "RecipeNote::query_step_notes", skip(exe))]
+    pub async fn query_step_notes(
+        exe: impl Executor<'_>,
+        recipe: &RecipeUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, RecipeNoteModel)
+            .condition(and![
+                RecipeNoteModel.recipe.equals(recipe.0),
+                RecipeNoteModel.step.is_some(),
+            ])
+            .stream()
+            .map_ok(RecipeNote::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Sets an account's note on a recipe or one of its steps.
+    ///
+    /// An empty note removes the existing one.
+    #[instrument(name = "RecipeNote::set", skip(exe))]
+    pub async fn set(
+        exe: impl Executor<'_>,
+        account: &AccountUuid,
+        recipe: &RecipeUuid,
+        step: Option<RecipeStepUuid>,
+        note: MaxStr<65535>,
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let existing = rorm::query(guard.get_transaction(), RecipeNoteModel.uuid)
+            .condition(and![
+                RecipeNoteModel.account.equals(account.get_inner()),
+                RecipeNoteModel.recipe.equals(recipe.0),
+                match step {
+                    Some(step) => RecipeNoteModel.step.equals(Some(step.get_inner())).boxed(),
+                    None => RecipeNoteModel.step.is_none().boxed(),
+                },
+            ])
+            .optional()
+            .await?;
+
+        match existing {
+            Some(existing) if note.trim().is_empty() => {
+                rorm::delete(guard.get_transaction(), RecipeNoteModel)
+                    .condition(RecipeNoteModel.uuid.equals(existing))
+                    .await?;
+            }
+            Some(existing) => {
+                rorm::update(guard.get_transaction(), RecipeNoteModel)
+                    .set(RecipeNoteModel.note, note)
+                    .set(RecipeNoteModel.updated_at, OffsetDateTime::now_utc())
+                    .condition(RecipeNoteModel.uuid.equals(existing))
+                    .await?;
+            }
+            None if note.trim().is_empty() => {}
+            None => {
+                rorm::insert(guard.get_transaction(), RecipeNoteModel)
+                    .single(&RecipeNoteModel {
+                        uuid: Uuid::new_v4(),
+                        account: ForeignModelByField(account.get_inner()),
+                        recipe: ForeignModelByField(recipe.0),
+                        step: step.map(|step| ForeignModelByField(step.get_inner())),
+                        note,
+                        updated_at: OffsetDateTime::now_utc(),
+                    })
+                    .await?;
+            }
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+
+    /// Recreates step notes on the steps which replaced their original steps.
+    ///
+    /// The notes are removed together with their original steps,
+    /// so notes whose step has been removed without replacement are dropped.
+    #[instrument(name = "RecipeNote::move_to_steps", skip(exe))]
+    pub async fn move_to_steps(
+        exe: impl Executor<'_>,
+        notes: Vec<Self>,
+        replacements: &HashMap<RecipeStepUuid, RecipeStepUuid>,
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        for note in notes {
+            let Some(step) = note.step.and_then(|step| replacements.get(&step)) else {
+                continue;
+            };
+
+            rorm::insert(guard.get_transaction(), RecipeNoteModel)
+                .single(&RecipeNoteModel {
+                    uuid: note.uuid,
+                    account: ForeignModelByField(note.account.get_inner()),
+                    recipe: ForeignModelByField(note.recipe.0),
+                    step: Some(ForeignModelByField(step.get_inner())),
+                    note: note.note,
+                    updated_at: note.updated_at,
+                })
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+}
+
+impl From<RecipeNoteModel> for RecipeNote {
+    fn from(model: RecipeNoteModel) -> Self {
+        Self {
+            uuid: model.uuid,
+            account: AccountUuid::new_from_model(model.account),
+            recipe: RecipeUuid(model.recipe.0),
+            step: model.step.map(RecipeStepUuid::from_model),
+            note: model.note,
+            updated_at: model.updated_at,
+        }
+    }
+}
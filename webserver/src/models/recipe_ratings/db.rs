@@ -0,0 +1,28 @@
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::models::account::db::AccountModel;
+use crate::models::recipes::db::RecipeModel;
+
+/// Represents the rating of a recipe by an account.
+#[derive(Model)]
+#[rorm(rename = "recipe_rating")]
+pub struct RecipeRatingModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the rated recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub recipe: ForeignModel<RecipeModel>,
+
+    /// A foreign key referencing the account which rated the recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub account: ForeignModel<AccountModel>,
+
+    /// The number of stars from 1 to 5.
+    pub rating: i16,
+
+    pub rated_at: OffsetDateTime,
+}
@@ -0,0 +1,111 @@
+//! Ratings of recipes by accounts.
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::rorm::and;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::prelude::ForeignModelByField;
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::account::AccountUuid;
+use crate::models::recipe_ratings::db::RecipeRatingModel;
+use crate::models::recipes::RecipeUuid;
+
+pub(in crate::models) mod db;
+
+/// The lowest number of stars a recipe can be rated with.
+pub const MIN_RATING: i16 = 1;
+
+/// The highest number of stars a recipe can be rated with.
+pub const MAX_RATING: i16 = 5;
+
+/// Domain representation of the rating of a recipe by an account.
+#[derive(Debug, Clone)]
+pub struct RecipeRating {
+    /// The rated recipe.
+    pub recipe: RecipeUuid,
+
+    /// The number of stars from [`MIN_RATING`] to [`MAX_RATING`].
+    pub rating: i16,
+}
+
+impl RecipeRating {
+    /// Lists all ratings of an account.
+    #[instrument(name = "RecipeRating::query_by_account", skip(exe))]
+    pub async fn query_by_account(
+        exe: impl Executor<'_>,
+        account: &AccountUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, RecipeRatingModel)
+            .condition(RecipeRatingModel.account.equals(account.get_inner()))
+            .stream()
+            .map_ok(RecipeRating::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Looks up the rating of a recipe by an account.
+    #[instrument(name = "RecipeRating::query", skip(exe))]
+    pub async fn query(
+        exe: impl Executor<'_>,
+        account: &AccountUuid,
+        recipe: &RecipeUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let rating = rorm::query(exe, RecipeRatingModel)
+            .condition(and![
+                RecipeRatingModel.account.equals(account.get_inner()),
+                RecipeRatingModel.recipe.equals(recipe.0),
+            ])
+            .optional()
+            .await?;
+        Ok(rating.map(Self::from))
+    }
+
+    /// Rates a recipe for an account replacing an existing rating.
+    ///
+    /// `None` removes the rating.
+    #[instrument(name = "RecipeRating::set", skip(exe))]
+    pub async fn set(
+        exe: impl Executor<'_>,
+        account: &AccountUuid,
+        recipe: &RecipeUuid,
+        rating: Option<i16>,
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        rorm::delete(guard.get_transaction(), RecipeRatingModel)
+            .condition(and![
+                RecipeRatingModel.account.equals(account.get_inner()),
+                RecipeRatingModel.recipe.equals(recipe.0),
+            ])
+            .await?;
+
+        if let Some(rating) = rating {
+            rorm::insert(guard.get_transaction(), RecipeRatingModel)
+                .return_nothing()
+                .single(&RecipeRatingModel {
+                    uuid: Uuid::new_v4(),
+                    recipe: ForeignModelByField(recipe.0),
+                    account: ForeignModelByField(account.get_inner()),
+                    rating,
+                    rated_at: OffsetDateTime::now_utc(),
+                })
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+}
+
+impl From<RecipeRatingModel> for RecipeRating {
+    fn from(model: RecipeRatingModel) -> Self {
+        Self {
+            recipe: RecipeUuid(model.recipe.0),
+            rating: model.rating,
+        }
+    }
+}
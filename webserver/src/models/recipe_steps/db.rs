@@ -18,8 +18,9 @@ pub struct RecipeStepModel {
     #[rorm(on_delete = "Cascade")]
     pub recipe: ForeignModel<RecipeModel>,
 
-    /// The text of the step.
-    pub step: MaxStr<255>,
+    /// The text of the step in markdown.
+    #[rorm(default = "")]
+    pub step: MaxStr<65535>,
 
     /// The order of the step within the recipe.
     pub index: i16,
@@ -7,6 +7,7 @@ use galvyn::core::re_exports::serde::Deserialize;
 use galvyn::core::re_exports::serde::Serialize;
 use galvyn::rorm::db::Executor;
 use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
 use galvyn::rorm::prelude::ForeignModelByField;
 use tracing::instrument;
 use uuid::Uuid;
@@ -18,24 +19,35 @@ pub(in crate::models) mod db;
 
 /// A single instruction step within a recipe.
 ///
-/// Steps are ordered and carry a concise textual description to guide the
+/// Steps are ordered and carry a textual description in markdown to guide the
 /// preparation process.
 #[derive(Debug, Clone)]
 pub struct RecipeStep {
     /// Stable identifier for this recipe step.
     pub uuid: RecipeStepUuid,
 
-    /// The textual content of the step.
-    pub step: MaxStr<255>,
+    /// The textual content of the step in markdown.
+    pub step: MaxStr<65535>,
 
     /// The position of the step within the recipe flow.
     pub index: i16,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 /// Strongly typed UUID wrapper for recipe steps to prevent cross-domain ID mix-ups.
 pub struct RecipeStepUuid(Uuid);
 
+impl RecipeStepUuid {
+    /// Creates an instance of "*RecipeStepUuid*"
+    pub fn from_model(model: ForeignModel<RecipeStepModel>) -> Self {
+        Self(model.0)
+    }
+    /// Gets underlying UUID
+    pub fn get_inner(&self) -> Uuid {
+        self.0
+    }
+}
+
 impl RecipeStep {
     /// Lists all steps belonging to a recipe.
     #[instrument(name = "RecipeStep::query_by_recipe", skip(exe))]
@@ -58,7 +70,7 @@ impl RecipeStep {
     pub async fn create(
         exe: impl Executor<'_>,
         recipe_uuid: RecipeUuid,
-        step: MaxStr<255>,
+        step: MaxStr<65535>,
         index: i16,
     ) -> anyhow::Result<RecipeStep> {
         let model = rorm::insert(exe, RecipeStepModel)
@@ -8,8 +8,11 @@ use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::models::account::db::AccountModel;
+use crate::models::households::db::HouseholdModel;
 use crate::models::recipe_ingredients::db::RecipeIngredientModel;
 use crate::models::recipe_steps::db::RecipeStepModel;
+use crate::models::recipes::RecipeSourceKind;
+use crate::models::recipes::RecipeVisibility;
 use crate::models::tags::db::RecipeTagModel;
 
 /// Represents a recipe model
@@ -22,15 +25,73 @@ pub struct RecipeModel {
     pub uuid: Uuid,
 
     /// The name of the recipe.
-    #[rorm(unique)]
     pub name: MaxStr<255>,
 
-    /// A longer description of the recipe.
-    pub description: MaxStr<255>,
+    /// A longer description of the recipe in markdown.
+    #[rorm(default = "")]
+    pub description: MaxStr<65535>,
+
+    /// Additional notes on the recipe in markdown.
+    #[rorm(default = "")]
+    pub notes: MaxStr<65535>,
+
+    /// The number of servings the recipe yields.
+    #[rorm(default = 1)]
+    pub servings: i16,
+
+    /// The total time it takes to prepare the recipe in minutes.
+    pub time_minutes: Option<i32>,
+
+    /// Whether all ingredients have been classified, i.e. the dietary flags are reliable.
+    #[rorm(default = false)]
+    pub dietary_complete: bool,
+
+    /// Whether the recipe contains neither meat nor fish.
+    #[rorm(default = false)]
+    pub vegetarian: bool,
+
+    /// Whether the recipe contains no animal products.
+    #[rorm(default = false)]
+    pub vegan: bool,
+
+    /// Whether the recipe contains no cereals containing gluten.
+    #[rorm(default = false)]
+    pub gluten_free: bool,
+
+    /// Whether the recipe contains neither nuts nor peanuts.
+    #[rorm(default = false)]
+    pub nut_free: bool,
+
+    /// Whether the recipe contains neither dairy products nor milk.
+    #[rorm(default = false)]
+    pub dairy_free: bool,
 
     /// A foreign key referencing a `User` model.
     pub user: ForeignModel<AccountModel>,
 
+    /// A foreign key referencing the household owning the recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub household: ForeignModel<HouseholdModel>,
+
+    /// Who is allowed to see the recipe.
+    #[rorm(default = "Household")]
+    pub visibility: RecipeVisibility,
+
+    /// The kind of source the recipe has been taken from.
+    pub source_kind: Option<RecipeSourceKind>,
+
+    /// The title of the book or website or the name of the person the recipe is from.
+    pub source_title: Option<MaxStr<255>>,
+
+    /// The author of the source.
+    pub source_author: Option<MaxStr<255>>,
+
+    /// The page of the book the recipe is printed on.
+    pub source_page: Option<i32>,
+
+    /// The address of the website the recipe is published on.
+    pub source_url: Option<MaxStr<2048>>,
+
     /// A back-reference to the `RecipeTag` model
     ///
     /// Representing the tags associated with this recipe.
@@ -54,7 +115,51 @@ pub struct RecipeModel {
 pub struct RecipeModelInsert {
     pub uuid: Uuid,
     pub name: MaxStr<255>,
-    pub description: MaxStr<255>,
+    pub description: MaxStr<65535>,
+    pub notes: MaxStr<65535>,
+    pub servings: i16,
+    pub time_minutes: Option<i32>,
     pub user: ForeignModel<AccountModel>,
+    pub household: ForeignModel<HouseholdModel>,
+    pub visibility: RecipeVisibility,
+    pub source_kind: Option<RecipeSourceKind>,
+    pub source_title: Option<MaxStr<255>>,
+    pub source_author: Option<MaxStr<255>>,
+    pub source_page: Option<i32>,
+    pub source_url: Option<MaxStr<2048>>,
     pub created_at: OffsetDateTime,
 }
+
+/// Represents a recipe shared with a single account.
+///
+/// Only relevant for recipes with [`RecipeVisibility::Shared`].
+#[derive(Model)]
+#[rorm(rename = "recipe_share")]
+pub struct RecipeShareModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the shared recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub recipe: ForeignModel<RecipeModel>,
+
+    /// A foreign key referencing the account the recipe is shared with.
+    #[rorm(on_delete = "Cascade")]
+    pub account: ForeignModel<AccountModel>,
+}
+
+/// Represents a recipe marked as favorite by an account.
+#[derive(Model)]
+#[rorm(rename = "recipe_favorite")]
+pub struct RecipeFavoriteModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the favorite recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub recipe: ForeignModel<RecipeModel>,
+
+    /// A foreign key referencing the account which likes the recipe.
+    #[rorm(on_delete = "Cascade")]
+    pub account: ForeignModel<AccountModel>,
+}
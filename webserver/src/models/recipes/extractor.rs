@@ -0,0 +1,24 @@
+use galvyn::core::re_exports::axum::extract::FromRequestParts;
+use galvyn::core::re_exports::axum::http::request::Parts;
+use galvyn::core::stuff::api_error::ApiError;
+use galvyn::core::Module;
+use galvyn::rorm::Database;
+
+use crate::models::account::Account;
+use crate::models::households::Household;
+use crate::models::recipes::RecipeViewer;
+
+impl<S> FromRequestParts<S> for RecipeViewer
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    /// Collects which recipes the authenticated user is allowed to see.
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let account = Account::from_request_parts(parts, state).await?;
+        let household = Household::from_request_parts(parts, state).await?;
+
+        Ok(RecipeViewer::query(Database::global(), account.uuid, household.uuid).await?)
+    }
+}
@@ -1,5 +1,8 @@
 //! Recipes domain model and database access layer.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+
 use futures_util::TryStreamExt;
 use galvyn::core::re_exports::rorm;
 use galvyn::core::re_exports::schemars;
@@ -8,23 +11,37 @@ use galvyn::core::re_exports::serde::Deserialize;
 use galvyn::core::re_exports::serde::Serialize;
 use galvyn::core::stuff::schema::GetPageRequest;
 use galvyn::rorm::and;
+use galvyn::rorm::conditions::Condition;
 use galvyn::rorm::conditions::DynamicCollection;
 use galvyn::rorm::db::Executor;
 use galvyn::rorm::fields::types::MaxStr;
 use galvyn::rorm::prelude::ForeignModelByField;
+use galvyn::rorm::DbEnum;
+use time::Duration;
 use time::OffsetDateTime;
 use tracing::instrument;
 use uuid::Uuid;
 
 use crate::models::account::AccountUuid;
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredient_allergens::Allergen;
+use crate::models::ingredient_allergens::IngredientAllergen;
+use crate::models::ingredient_substitutions::IngredientSubstitution;
+use crate::models::ingredients::db::IngredientModel;
+use crate::models::ingredients::Ingredient;
 use crate::models::ingredients::IngredientUuid;
+use crate::models::recipe_cooks::db::RecipeCookModel;
 use crate::models::recipe_ingredients::db::RecipeIngredientModel;
+use crate::models::recipes::db::RecipeFavoriteModel;
 use crate::models::recipes::db::RecipeModel;
 use crate::models::recipes::db::RecipeModelInsert;
+use crate::models::recipes::db::RecipeShareModel;
 use crate::models::tags::db::RecipeTagModel;
 use crate::models::tags::TagUuid;
+use crate::utils::normalize;
 
 pub(in crate::models) mod db;
+mod extractor;
 
 /// Domain representation of a recipe.
 ///
@@ -35,35 +52,338 @@ pub struct Recipe {
     /// Recipe UUID
     pub uuid: RecipeUuid,
 
-    /// The name of the recipe. Must be unique.
+    /// The name of the recipe. Must be unique within the household.
     pub name: MaxStr<255>,
 
-    /// A longer description of the recipe.
-    pub description: MaxStr<255>,
+    /// A longer description of the recipe in markdown.
+    pub description: MaxStr<65535>,
+
+    /// Additional notes on the recipe in markdown.
+    pub notes: MaxStr<65535>,
+
+    /// The number of servings the recipe yields.
+    pub servings: i16,
+
+    /// The total time it takes to prepare the recipe in minutes.
+    pub time_minutes: Option<i32>,
+
+    /// The dietary flags derived from the ingredients.
+    pub dietary: DietaryFlags,
 
     /// An optional foreign key referencing a `User` model.
     pub user: AccountUuid,
+
+    /// The household owning the recipe.
+    pub household: HouseholdUuid,
+
+    /// Who is allowed to see the recipe.
+    pub visibility: RecipeVisibility,
+
+    /// Where the recipe has been taken from.
+    pub source: Option<RecipeSource>,
 }
 
 /// Type‑safe new type around Uuid for recipe identifiers.
 #[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct RecipeUuid(pub Uuid);
 
+/// Who is allowed to see a recipe.
+#[derive(
+    DbEnum, Debug, Default, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq,
+)]
+pub enum RecipeVisibility {
+    /// Only the author
+    Private = 0,
+    /// The author and the accounts the recipe is shared with
+    Shared = 1,
+    /// All members of the household owning the recipe
+    #[default]
+    Household = 2,
+    /// Everyone on the instance
+    Everyone = 3,
+}
+
+/// Where a recipe has been taken from.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecipeSource {
+    /// The kind of the source.
+    pub kind: RecipeSourceKind,
+
+    /// The title of the book or website or the name of the person.
+    pub title: MaxStr<255>,
+
+    /// The author of the book or website.
+    pub author: Option<MaxStr<255>>,
+
+    /// The page of the book the recipe is printed on.
+    pub page: Option<i32>,
+
+    /// The address of the website the recipe is published on.
+    pub url: Option<MaxStr<2048>>,
+}
+
+/// The nullable source columns of [`RecipeModel`]: kind, title, author, page and url.
+type SourceColumns = (
+    Option<RecipeSourceKind>,
+    Option<MaxStr<255>>,
+    Option<MaxStr<255>>,
+    Option<i32>,
+    Option<MaxStr<2048>>,
+);
+
+impl RecipeSource {
+    /// Splits the source into the nullable columns of [`RecipeModel`].
+    fn into_columns(self) -> SourceColumns {
+        (
+            Some(self.kind),
+            Some(self.title),
+            self.author,
+            self.page,
+            self.url,
+        )
+    }
+}
+
+/// The kinds of sources recipes are taken from.
+#[derive(DbEnum, Debug, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum RecipeSourceKind {
+    /// A printed cookbook
+    Book = 0,
+    /// A website
+    Url = 1,
+    /// A friend or relative
+    Person = 2,
+}
+
+/// Constraints for picking random recipes.
+#[derive(Debug, Clone, Default)]
+pub struct RandomRecipeFilter {
+    /// Only pick recipes with all of these tags.
+    pub tags: Vec<TagUuid>,
+
+    /// Only pick recipes which can be prepared within this many minutes.
+    pub max_time_minutes: Option<i32>,
+
+    /// Don't pick recipes using any of these ingredients.
+    pub excluded_ingredients: Vec<IngredientUuid>,
+
+    /// Don't pick recipes the household has cooked within this many days.
+    pub not_cooked_within_days: Option<i32>,
+}
+
+/// The account recipes are queried for.
+///
+/// Every query in [`Recipe`] only returns the recipes the viewer is allowed to see.
+#[derive(Debug, Clone)]
+pub struct RecipeViewer {
+    /// The account looking at the recipes.
+    pub account: AccountUuid,
+
+    /// The household of the account.
+    pub household: HouseholdUuid,
+
+    /// The recipes explicitly shared with the account.
+    shared: Vec<Uuid>,
+}
+
+impl RecipeViewer {
+    /// Collects the recipes shared with an account.
+    #[instrument(name = "RecipeViewer::query", skip(exe))]
+    pub async fn query(
+        exe: impl Executor<'_>,
+        account: AccountUuid,
+        household: HouseholdUuid,
+    ) -> anyhow::Result<Self> {
+        let shared: Vec<_> = rorm::query(exe, RecipeShareModel.recipe)
+            .condition(RecipeShareModel.account.equals(account.get_inner()))
+            .stream()
+            .map_ok(|recipe| recipe.0)
+            .try_collect()
+            .await?;
+
+        Ok(Self {
+            account,
+            household,
+            shared,
+        })
+    }
+}
+
+/// Dietary flags of a recipe derived from the classification of its ingredients.
+///
+/// The flags are only set if all ingredients have been classified.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DietaryFlags {
+    /// Whether all ingredients have been classified.
+    pub complete: bool,
+
+    /// Whether the recipe contains neither meat nor fish.
+    pub vegetarian: bool,
+
+    /// Whether the recipe contains no animal products.
+    pub vegan: bool,
+
+    /// Whether the recipe contains no cereals containing gluten.
+    pub gluten_free: bool,
+
+    /// Whether the recipe contains neither nuts nor peanuts.
+    pub nut_free: bool,
+
+    /// Whether the recipe contains neither dairy products nor milk.
+    pub dairy_free: bool,
+}
+
+impl DietaryFlags {
+    /// Derives the flags of a recipe from its classified ingredients and their allergens.
+    ///
+    /// A recipe without any ingredients can't be judged, so none of its flags are set.
+    pub fn derive(ingredients: &[(Ingredient, Vec<Allergen>)]) -> Self {
+        if ingredients.is_empty() {
+            return Self::default();
+        }
+
+        let mut flags = Self {
+            complete: true,
+            vegetarian: true,
+            vegan: true,
+            gluten_free: true,
+            nut_free: true,
+            dairy_free: true,
+        };
+
+        for (ingredient, allergens) in ingredients {
+            let contains = |allergen| allergens.contains(&allergen);
+            let seafood = contains(Allergen::Fish)
+                || contains(Allergen::Crustaceans)
+                || contains(Allergen::Molluscs);
+            let dairy = ingredient.dairy || contains(Allergen::Milk);
+
+            flags.complete &= ingredient.classified;
+            flags.vegetarian &= !ingredient.meat && !seafood;
+            flags.vegan &= !ingredient.animal_product && !dairy && !contains(Allergen::Eggs);
+            flags.gluten_free &= !contains(Allergen::CerealsContainingGluten);
+            flags.nut_free &= !contains(Allergen::Nuts) && !contains(Allergen::Peanuts);
+            flags.dairy_free &= !dairy;
+        }
+        flags.vegan &= flags.vegetarian;
+
+        if flags.complete {
+            flags
+        } else {
+            Self::default()
+        }
+    }
+}
+
+/// Diets a recipe can be filtered for.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum Diet {
+    Vegetarian,
+    Vegan,
+    GlutenFree,
+    NutFree,
+    DairyFree,
+}
+
+/// Builds the condition requiring a recipe to be suitable for all given diets.
+///
+/// `$recipe` is the field path to the recipe model the condition should be applied to.
+/// Without any diets the condition is always true.
+macro_rules! diet_condition {
+    ($recipe:expr, $diets:expr) => {
+        DynamicCollection::and_unchecked(
+            $diets
+                .iter()
+                .map(|diet| {
+                    Some(match diet {
+                        Diet::Vegetarian => $recipe.vegetarian.equals(true).boxed(),
+                        Diet::Vegan => $recipe.vegan.equals(true).boxed(),
+                        Diet::GlutenFree => $recipe.gluten_free.equals(true).boxed(),
+                        Diet::NutFree => $recipe.nut_free.equals(true).boxed(),
+                        Diet::DairyFree => $recipe.dairy_free.equals(true).boxed(),
+                    })
+                })
+                .collect(),
+        )
+    };
+}
+
+/// Builds the condition requiring a recipe to be visible for a viewer.
+///
+/// `$recipe` is the field path to the recipe model the condition should be applied to.
+macro_rules! visibility_condition {
+    ($recipe:expr, $viewer:expr) => {{
+        let viewer: &RecipeViewer = $viewer;
+        let mut conditions = vec![
+            $recipe.user.equals(viewer.account.get_inner()).boxed(),
+            and![
+                $recipe.household.equals(viewer.household.0),
+                $recipe.visibility.equals(RecipeVisibility::Household),
+            ]
+            .boxed(),
+            $recipe.visibility.equals(RecipeVisibility::Everyone).boxed(),
+        ];
+        if !viewer.shared.is_empty() {
+            conditions.push(
+                and![
+                    $recipe.visibility.equals(RecipeVisibility::Shared),
+                    $recipe.uuid.r#in(viewer.shared.iter().copied()),
+                ]
+                .boxed(),
+            );
+        }
+        DynamicCollection::or_unchecked(conditions)
+    }};
+}
+
+/// Hashes a recipe uuid together with the seed of a random pick.
+///
+/// This is FNV-1a instead of std's hasher whose algorithm may change between releases,
+/// so a seed keeps picking the same recipes after an update.
+fn seeded_hash(uuid: &Uuid, seed: i64) -> u64 {
+    seed.to_le_bytes()
+        .iter()
+        .chain(uuid.as_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
 impl Recipe {
-    /// Return the total number of recipes in the database.
+    /// Return the total number of recipes visible for a viewer matching the name and diet filters.
     #[instrument(name = "Recipe::query_total", skip(exe))]
-    pub async fn query_total(exe: impl Executor<'_>) -> anyhow::Result<i64> {
-        Ok(rorm::query(exe, RecipeModel.uuid.count()).one().await?)
+    pub async fn query_total(
+        exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
+        filter_name: Option<&str>,
+        filter_diet: &[Diet],
+    ) -> anyhow::Result<i64> {
+        let condition = and![
+            Some(visibility_condition!(RecipeModel, viewer)),
+            filter_name.map(|name| RecipeModel.name.contains_ignore_case(name)),
+            Some(diet_condition!(RecipeModel, filter_diet)),
+        ];
+
+        Ok(rorm::query(exe, RecipeModel.uuid.count())
+            .condition(&condition)
+            .one()
+            .await?)
     }
 
-    /// List recipes with optional name filter and pagination.
+    /// List recipes with optional name and diet filters and pagination.
     #[instrument(name = "Recipe::query_all", skip(exe))]
     pub async fn query_all(
         exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
         page: &GetPageRequest,
         filter_name: Option<String>,
+        filter_diet: &[Diet],
     ) -> anyhow::Result<Vec<Self>> {
-        let condition = and![filter_name.map(|name| RecipeModel.name.contains_ignore_case(&name))];
+        let condition = and![
+            Some(visibility_condition!(RecipeModel, viewer)),
+            filter_name.map(|name| RecipeModel.name.contains_ignore_case(&name)),
+            Some(diet_condition!(RecipeModel, filter_diet)),
+        ];
 
         let result: Vec<_> = rorm::query(exe, RecipeModel)
             .condition(&condition)
@@ -78,68 +398,372 @@ impl Recipe {
         Ok(result)
     }
 
+    /// List the UUIDs of all recipes with optional name and diet filters ordered by name.
+    ///
+    /// Used when the recipes have to be filtered or sorted further before paginating.
+    #[instrument(name = "Recipe::query_all_uuids", skip(exe))]
+    pub async fn query_all_uuids(
+        exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
+        filter_name: Option<String>,
+        filter_diet: &[Diet],
+    ) -> anyhow::Result<Vec<RecipeUuid>> {
+        let condition = and![
+            Some(visibility_condition!(RecipeModel, viewer)),
+            filter_name.map(|name| RecipeModel.name.contains_ignore_case(&name)),
+            Some(diet_condition!(RecipeModel, filter_diet)),
+        ];
+
+        let result: Vec<_> = rorm::query(exe, RecipeModel.uuid)
+            .condition(&condition)
+            .order_asc(RecipeModel.name)
+            .stream()
+            .map_ok(RecipeUuid)
+            .try_collect()
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Fetch multiple recipes by their UUIDs in the order of the UUIDs.
+    ///
+    /// Recipes which don't exist or aren't visible for the viewer are left out.
+    #[instrument(name = "Recipe::query_by_uuids", skip(exe))]
+    pub async fn query_by_uuids(
+        exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
+        uuids: &[RecipeUuid],
+    ) -> anyhow::Result<Vec<Self>> {
+        if uuids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut models: HashMap<_, _> = rorm::query(exe, RecipeModel)
+            .condition(and![
+                RecipeModel.uuid.r#in(uuids.iter().map(|uuid| uuid.0)),
+                visibility_condition!(RecipeModel, viewer),
+            ])
+            .stream()
+            .map_ok(|model| (model.uuid, model))
+            .try_collect()
+            .await?;
+
+        Ok(uuids
+            .iter()
+            .filter_map(|uuid| models.remove(&uuid.0))
+            .map(Recipe::from)
+            .collect())
+    }
+
     /// Fetch a single recipe by its UUID.
     #[instrument(name = "Recipe::query_uuid", skip(exe))]
     pub async fn query_by_uuid(
         exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
         uuid: &RecipeUuid,
     ) -> anyhow::Result<Option<Self>> {
         let model = rorm::query(exe, RecipeModel)
-            .condition(RecipeModel.uuid.equals(uuid.0))
+            .condition(and![
+                RecipeModel.uuid.equals(uuid.0),
+                visibility_condition!(RecipeModel, viewer),
+            ])
             .optional()
             .await?;
         Ok(model.map(Recipe::from))
     }
 
-    /// List recipes that use any of the given ingredients.
-    #[instrument(name = "Recipe::query_by_ingredient", skip(exe))]
-    pub async fn query_by_ingredient(
+    /// List all recipes visible for a viewer.
+    #[instrument(name = "Recipe::query_visible", skip(exe))]
+    pub async fn query_visible(
         exe: impl Executor<'_>,
-        page: &GetPageRequest,
-        filter_name: Option<String>,
-        ingredient_uuids: &Vec<IngredientUuid>,
+        viewer: &RecipeViewer,
     ) -> anyhow::Result<Vec<Self>> {
-        let condition = DynamicCollection::or(
-            ingredient_uuids
-                .iter()
-                .map(|uuid| RecipeIngredientModel.ingredients.equals(uuid.get_inner()))
-                .collect(),
-        );
-
-        let condition = and![
-            condition,
-            filter_name.map(|name| RecipeIngredientModel.recipe.name.contains_ignore_case(&name)),
-        ];
+        let result: Vec<_> = rorm::query(exe, RecipeModel)
+            .condition(visibility_condition!(RecipeModel, viewer))
+            .order_asc(RecipeModel.name)
+            .stream()
+            .map_ok(Recipe::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
 
+    /// List the ingredients of all recipes visible for a viewer.
+    #[instrument(name = "Recipe::query_visible_ingredients", skip(exe))]
+    pub async fn query_visible_ingredients(
+        exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
+    ) -> anyhow::Result<Vec<(RecipeUuid, IngredientUuid)>> {
         let result: Vec<_> = rorm::query(
             exe,
             (
+                RecipeIngredientModel.recipe,
                 RecipeIngredientModel.ingredients,
-                RecipeIngredientModel.recipe.query_as(RecipeModel),
             ),
         )
-        .condition(&condition)
-        .limit(page.limit)
-        .offset(page.offset)
+        .condition(visibility_condition!(RecipeIngredientModel.recipe, viewer))
         .stream()
-        .map_ok(|(_, recipe)| Recipe::from(recipe))
+        .map_ok(|(recipe, ingredient)| {
+            (RecipeUuid(recipe.0), IngredientUuid::from_model(ingredient))
+        })
         .try_collect()
         .await?;
+        Ok(result)
+    }
+
+    /// List all recipes visible for a viewer which have a source.
+    ///
+    /// The recipes are ordered by the title of their source.
+    #[instrument(name = "Recipe::query_with_source", skip(exe))]
+    pub async fn query_with_source(
+        exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, RecipeModel)
+            .condition(and![
+                visibility_condition!(RecipeModel, viewer),
+                RecipeModel.source_kind.is_some(),
+            ])
+            .order_asc(RecipeModel.source_title)
+            .order_asc(RecipeModel.name)
+            .stream()
+            .map_ok(Recipe::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Pick random recipes visible for a viewer which match all constraints.
+    ///
+    /// The recipes are shuffled by hashing their uuid together with the seed,
+    /// so the same seed picks the same recipes as long as they don't change.
+    ///
+    /// Excluded ingredients are matched by their normalized name,
+    /// so they are excluded from the recipes of other households as well.
+    #[instrument(name = "Recipe::query_random", skip(exe))]
+    pub async fn query_random(
+        exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
+        filter: &RandomRecipeFilter,
+        seed: i64,
+        count: i64,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let recipes = rorm::query(
+            guard.get_transaction(),
+            (RecipeModel.uuid, RecipeModel.time_minutes),
+        )
+        .condition(visibility_condition!(RecipeModel, viewer))
+        .all()
+        .await?;
+        // rorm can't compare nullable columns by order
+        let mut candidates: HashSet<Uuid> = recipes
+            .into_iter()
+            .filter(|(_, time_minutes)| match filter.max_time_minutes {
+                Some(max) => time_minutes.is_some_and(|minutes| minutes <= max),
+                None => true,
+            })
+            .map(|(uuid, _)| uuid)
+            .collect();
+
+        for tag in &filter.tags {
+            let tagged: HashSet<Uuid> = rorm::query(guard.get_transaction(), RecipeTagModel.recipe)
+                .condition(RecipeTagModel.tag.equals(tag.0))
+                .stream()
+                .map_ok(|recipe| recipe.0)
+                .try_collect()
+                .await?;
+            candidates.retain(|uuid| tagged.contains(uuid));
+        }
+
+        if !filter.excluded_ingredients.is_empty() {
+            let excluded: HashSet<String> =
+                rorm::query(guard.get_transaction(), IngredientModel.name)
+                    .condition(and![
+                        IngredientModel.household.equals(viewer.household.0),
+                        IngredientModel.uuid.r#in(
+                            filter
+                                .excluded_ingredients
+                                .iter()
+                                .map(IngredientUuid::get_inner)
+                        ),
+                    ])
+                    .stream()
+                    .map_ok(|name| normalize::ingredient_name(&name))
+                    .try_collect()
+                    .await?;
+
+            let used = rorm::query(
+                guard.get_transaction(),
+                (
+                    RecipeIngredientModel.recipe,
+                    RecipeIngredientModel.ingredients.name,
+                ),
+            )
+            .condition(visibility_condition!(RecipeIngredientModel.recipe, viewer))
+            .all()
+            .await?;
+            for (recipe, name) in used {
+                if excluded.contains(&normalize::ingredient_name(&name)) {
+                    candidates.remove(&recipe.0);
+                }
+            }
+        }
+
+        if let Some(days) = filter.not_cooked_within_days {
+            let cooked = rorm::query(guard.get_transaction(), RecipeCookModel.recipe)
+                .condition(and![
+                    RecipeCookModel.household.equals(viewer.household.0),
+                    RecipeCookModel
+                        .cooked_at
+                        .greater_than(OffsetDateTime::now_utc() - Duration::days(days.into())),
+                ])
+                .all()
+                .await?;
+            for recipe in cooked {
+                candidates.remove(&recipe.0);
+            }
+        }
+
+        let mut uuids: Vec<_> = candidates.into_iter().collect();
+        uuids.sort_by_cached_key(|uuid| (seeded_hash(uuid, seed), *uuid));
+        uuids.truncate(usize::try_from(count).unwrap_or_default());
+
+        if uuids.is_empty() {
+            guard.commit().await?;
+            return Ok(Vec::new());
+        }
+
+        let mut result: Vec<_> = rorm::query(guard.get_transaction(), RecipeModel)
+            .condition(RecipeModel.uuid.r#in(uuids.iter().copied()))
+            .stream()
+            .map_ok(Recipe::from)
+            .try_collect()
+            .await?;
+        result.sort_by_key(|recipe| uuids.iter().position(|uuid| *uuid == recipe.uuid.0));
 
+        guard.commit().await?;
         Ok(result)
     }
 
+    /// List recipes that use any of the given ingredients or an ingredient they can replace.
+    ///
+    /// Optional ingredients of the recipes are only matched if `include_optional` is set.
+    /// Returns the total number of matching recipes and the requested page ordered by name.
+    #[instrument(name = "Recipe::query_by_ingredient", skip(exe))]
+    pub async fn query_by_ingredient(
+        exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
+        page: &GetPageRequest,
+        filter_name: Option<String>,
+        ingredient_uuids: &[IngredientUuid],
+        substitutions: &[IngredientSubstitution],
+        include_optional: bool,
+    ) -> anyhow::Result<(i64, Vec<Self>)> {
+        let mut matches = Vec::new();
+        if !ingredient_uuids.is_empty() {
+            matches.push(
+                RecipeIngredientModel
+                    .ingredients
+                    .r#in(ingredient_uuids.iter().map(IngredientUuid::get_inner))
+                    .boxed(),
+            );
+        }
+        for substitution in substitutions {
+            let ingredient = RecipeIngredientModel
+                .ingredients
+                .equals(substitution.ingredient.get_inner());
+            matches.push(match substitution.recipe {
+                Some(recipe) => {
+                    and![ingredient, RecipeIngredientModel.recipe.equals(recipe.0)].boxed()
+                }
+                None => ingredient.boxed(),
+            });
+        }
+        if matches.is_empty() {
+            return Ok((0, Vec::new()));
+        }
+
+        let mut guard = exe.ensure_transaction().await?;
+
+        let uuids: HashSet<Uuid> =
+            rorm::query(guard.get_transaction(), RecipeIngredientModel.recipe)
+                .condition(and![
+                    Some(DynamicCollection::or_unchecked(matches)),
+                    Some(visibility_condition!(RecipeIngredientModel.recipe, viewer)),
+                    (!include_optional).then(|| RecipeIngredientModel.optional.equals(false)),
+                ])
+                .stream()
+                .map_ok(|recipe| recipe.0)
+                .try_collect()
+                .await?;
+        if uuids.is_empty() {
+            guard.commit().await?;
+            return Ok((0, Vec::new()));
+        }
+
+        let condition = and![
+            Some(RecipeModel.uuid.r#in(uuids)),
+            filter_name.map(|name| RecipeModel.name.contains_ignore_case(&name)),
+        ];
+
+        let total = rorm::query(guard.get_transaction(), RecipeModel.uuid.count())
+            .condition(&condition)
+            .one()
+            .await?;
+
+        let result: Vec<_> = rorm::query(guard.get_transaction(), RecipeModel)
+            .condition(&condition)
+            .order_asc(RecipeModel.name)
+            .limit(page.limit)
+            .offset(page.offset)
+            .stream()
+            .map_ok(Recipe::from)
+            .try_collect()
+            .await?;
+
+        guard.commit().await?;
+        Ok((total, result))
+    }
+
+    /// Return the total number of visible recipes with a tag matching the name and diet filters.
+    #[instrument(name = "Recipe::query_total_by_tag", skip(exe))]
+    pub async fn query_total_by_tag(
+        exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
+        tag_uuid: &TagUuid,
+        filter_name: Option<&str>,
+        filter_diet: &[Diet],
+    ) -> anyhow::Result<i64> {
+        let condition = and![
+            filter_name.map(|name| RecipeTagModel.recipe.name.contains_ignore_case(name)),
+            Some(RecipeTagModel.tag.equals(tag_uuid.0)),
+            Some(visibility_condition!(RecipeTagModel.recipe, viewer)),
+            Some(diet_condition!(RecipeTagModel.recipe, filter_diet)),
+        ];
+
+        Ok(rorm::query(exe, RecipeTagModel.recipe.uuid.count())
+            .condition(&condition)
+            .one()
+            .await?)
+    }
+
     /// List recipes associated with a specific tag.
     #[instrument(name = "Recipe::query_by_tag", skip(exe))]
     pub async fn query_by_tag(
         exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
         tag_uuid: &TagUuid,
         page: &GetPageRequest,
         filter_name: Option<String>,
+        filter_diet: &[Diet],
     ) -> anyhow::Result<Vec<Self>> {
         let condition = and![
             filter_name.map(|name| RecipeTagModel.recipe.name.contains_ignore_case(&name)),
             Some(RecipeTagModel.tag.equals(tag_uuid.0)),
+            Some(visibility_condition!(RecipeTagModel.recipe, viewer)),
+            Some(diet_condition!(RecipeTagModel.recipe, filter_diet)),
         ];
 
         let result: Vec<_> = rorm::query(exe, RecipeTagModel.recipe.query_as(RecipeModel))
@@ -155,52 +779,250 @@ impl Recipe {
         Ok(result)
     }
 
-    /// Fetch a recipe by its unique name.
+    /// Fetch a recipe by its name which is unique within a household.
+    ///
+    /// Recipes are found regardless of their visibility, as the name must be unique anyway.
     #[instrument(name = "Recipe::query_by_name", skip(exe))]
-    pub async fn query_by_name(exe: impl Executor<'_>, name: &str) -> anyhow::Result<Option<Self>> {
+    pub async fn query_by_name(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        name: &str,
+    ) -> anyhow::Result<Option<Self>> {
         let model = rorm::query(exe, RecipeModel)
-            .condition(RecipeModel.name.equals(name))
+            .condition(and![
+                RecipeModel.name.equals(name),
+                RecipeModel.household.equals(household.0),
+            ])
             .optional()
             .await?;
         Ok(model.map(Recipe::from))
     }
 
     /// Create and return a new recipe.
+    #[expect(clippy::too_many_arguments)]
     #[instrument(name = "Recipe::create", skip(exe))]
     pub async fn create(
         exe: impl Executor<'_>,
         name: MaxStr<255>,
-        description: MaxStr<255>,
+        description: MaxStr<65535>,
+        notes: MaxStr<65535>,
+        servings: i16,
+        time_minutes: Option<i32>,
         user: AccountUuid,
+        household: &HouseholdUuid,
+        visibility: RecipeVisibility,
+        source: Option<RecipeSource>,
     ) -> anyhow::Result<Self> {
+        let (source_kind, source_title, source_author, source_page, source_url) =
+            source.map_or_else(Default::default, RecipeSource::into_columns);
+
         let model = rorm::insert(exe, RecipeModel)
             .single(&RecipeModelInsert {
                 uuid: Uuid::new_v4(),
                 user: ForeignModelByField(user.get_inner()),
+                household: ForeignModelByField(household.0),
+                visibility,
+                source_kind,
+                source_title,
+                source_author,
+                source_page,
+                source_url,
                 name,
                 description,
+                notes,
+                servings,
+                time_minutes,
                 created_at: OffsetDateTime::now_utc(),
             })
             .await?;
         Ok(Recipe::from(model))
     }
 
-    /// Update a recipe's name and description.
+    /// Update a recipe's name, description, notes, servings, time, visibility and source.
+    #[expect(clippy::too_many_arguments)]
     #[instrument(name = "Recipe::update", skip(exe))]
     pub async fn update(
         &self,
         exe: impl Executor<'_>,
         name: MaxStr<255>,
-        description: MaxStr<255>,
+        description: MaxStr<65535>,
+        notes: MaxStr<65535>,
+        servings: i16,
+        time_minutes: Option<i32>,
+        visibility: RecipeVisibility,
+        source: Option<RecipeSource>,
     ) -> anyhow::Result<()> {
+        let (kind, title, author, page, url) =
+            source.map_or_else(Default::default, RecipeSource::into_columns);
+
         rorm::update(exe, RecipeModel)
             .set(RecipeModel.name, name)
             .set(RecipeModel.description, description)
+            .set(RecipeModel.notes, notes)
+            .set(RecipeModel.servings, servings)
+            .set(RecipeModel.time_minutes, time_minutes)
+            .set(RecipeModel.visibility, visibility)
+            .set(RecipeModel.source_kind, kind)
+            .set(RecipeModel.source_title, title)
+            .set(RecipeModel.source_author, author)
+            .set(RecipeModel.source_page, page)
+            .set(RecipeModel.source_url, url)
             .condition(RecipeModel.uuid.equals(self.uuid.0))
             .await?;
         Ok(())
     }
 
+    /// List the uuids of all recipes using an ingredient.
+    #[instrument(name = "Recipe::query_uuids_by_ingredient", skip(exe))]
+    pub async fn query_uuids_by_ingredient(
+        exe: impl Executor<'_>,
+        ingredient_uuid: &IngredientUuid,
+    ) -> anyhow::Result<Vec<RecipeUuid>> {
+        let mut result: Vec<_> = rorm::query(exe, RecipeIngredientModel.recipe)
+            .condition(
+                RecipeIngredientModel
+                    .ingredients
+                    .equals(ingredient_uuid.get_inner()),
+            )
+            .stream()
+            .map_ok(|recipe| RecipeUuid(recipe.0))
+            .try_collect()
+            .await?;
+        result.sort_by_key(|uuid| uuid.0);
+        result.dedup();
+        Ok(result)
+    }
+
+    /// Derives the dietary flags of a recipe from its ingredients and stores them.
+    ///
+    /// Has to be called whenever the ingredients of a recipe or their classification change.
+    #[instrument(name = "Recipe::refresh_dietary", skip(exe))]
+    pub async fn refresh_dietary(
+        exe: impl Executor<'_>,
+        recipe_uuid: &RecipeUuid,
+    ) -> anyhow::Result<DietaryFlags> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let models: Vec<_> = rorm::query(
+            guard.get_transaction(),
+            RecipeIngredientModel.ingredients.query_as(IngredientModel),
+        )
+        .condition(RecipeIngredientModel.recipe.equals(recipe_uuid.0))
+        .all()
+        .await?;
+
+        let mut ingredients = Vec::new();
+        for model in models {
+            let ingredient = Ingredient::from(model);
+            let allergens =
+                IngredientAllergen::query_by_ingredient(guard.get_transaction(), &ingredient.uuid)
+                    .await?;
+            ingredients.push((ingredient, allergens));
+        }
+
+        let flags = DietaryFlags::derive(&ingredients);
+
+        rorm::update(guard.get_transaction(), RecipeModel)
+            .set(RecipeModel.dietary_complete, flags.complete)
+            .set(RecipeModel.vegetarian, flags.vegetarian)
+            .set(RecipeModel.vegan, flags.vegan)
+            .set(RecipeModel.gluten_free, flags.gluten_free)
+            .set(RecipeModel.nut_free, flags.nut_free)
+            .set(RecipeModel.dairy_free, flags.dairy_free)
+            .condition(RecipeModel.uuid.equals(recipe_uuid.0))
+            .await?;
+
+        guard.commit().await?;
+        Ok(flags)
+    }
+
+    /// List the accounts the recipe is shared with.
+    #[instrument(name = "Recipe::query_shares", skip(exe))]
+    pub async fn query_shares(&self, exe: impl Executor<'_>) -> anyhow::Result<Vec<AccountUuid>> {
+        let result: Vec<_> = rorm::query(exe, RecipeShareModel.account)
+            .condition(RecipeShareModel.recipe.equals(self.uuid.0))
+            .stream()
+            .map_ok(AccountUuid::new_from_model)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Replace the accounts the recipe is shared with.
+    #[instrument(name = "Recipe::set_shares", skip(exe))]
+    pub async fn set_shares(
+        &self,
+        exe: impl Executor<'_>,
+        accounts: &[AccountUuid],
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        rorm::delete(guard.get_transaction(), RecipeShareModel)
+            .condition(RecipeShareModel.recipe.equals(self.uuid.0))
+            .await?;
+
+        if !accounts.is_empty() {
+            rorm::insert(guard.get_transaction(), RecipeShareModel)
+                .return_nothing()
+                .bulk(accounts.iter().map(|account| RecipeShareModel {
+                    uuid: Uuid::new_v4(),
+                    recipe: ForeignModelByField(self.uuid.0),
+                    account: ForeignModelByField(account.get_inner()),
+                }))
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+
+    /// List the favorite recipes of an account.
+    #[instrument(name = "Recipe::query_favorites", skip(exe))]
+    pub async fn query_favorites(
+        exe: impl Executor<'_>,
+        account: &AccountUuid,
+    ) -> anyhow::Result<Vec<RecipeUuid>> {
+        let result: Vec<_> = rorm::query(exe, RecipeFavoriteModel.recipe)
+            .condition(RecipeFavoriteModel.account.equals(account.get_inner()))
+            .stream()
+            .map_ok(|recipe| RecipeUuid(recipe.0))
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Mark or unmark the recipe as favorite of an account.
+    #[instrument(name = "Recipe::set_favorite", skip(exe))]
+    pub async fn set_favorite(
+        &self,
+        exe: impl Executor<'_>,
+        account: &AccountUuid,
+        favorite: bool,
+    ) -> anyhow::Result<()> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        rorm::delete(guard.get_transaction(), RecipeFavoriteModel)
+            .condition(and![
+                RecipeFavoriteModel.account.equals(account.get_inner()),
+                RecipeFavoriteModel.recipe.equals(self.uuid.0),
+            ])
+            .await?;
+
+        if favorite {
+            rorm::insert(guard.get_transaction(), RecipeFavoriteModel)
+                .return_nothing()
+                .single(&RecipeFavoriteModel {
+                    uuid: Uuid::new_v4(),
+                    recipe: ForeignModelByField(self.uuid.0),
+                    account: ForeignModelByField(account.get_inner()),
+                })
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok(())
+    }
+
     /// Delete a recipe by UUID.
     #[instrument(name = "Recipe::delete", skip(exe))]
     pub async fn delete(&self, exe: impl Executor<'_>) -> anyhow::Result<()> {
@@ -217,7 +1039,30 @@ impl From<RecipeModel> for Recipe {
             uuid: RecipeUuid(model.uuid),
             name: model.name,
             description: model.description,
+            notes: model.notes,
+            servings: model.servings,
+            time_minutes: model.time_minutes,
+            dietary: DietaryFlags {
+                complete: model.dietary_complete,
+                vegetarian: model.vegetarian,
+                vegan: model.vegan,
+                gluten_free: model.gluten_free,
+                nut_free: model.nut_free,
+                dairy_free: model.dairy_free,
+            },
             user: AccountUuid::new_from_model(model.user),
+            household: HouseholdUuid::from_model(model.household),
+            visibility: model.visibility,
+            source: model
+                .source_kind
+                .zip(model.source_title)
+                .map(|(kind, title)| RecipeSource {
+                    kind,
+                    title,
+                    author: model.source_author,
+                    page: model.source_page,
+                    url: model.source_url,
+                }),
         }
     }
 }
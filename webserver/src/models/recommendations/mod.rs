@@ -0,0 +1,326 @@
+//! Personalized recipe recommendations.
+//!
+//! Every recipe an account has marked as favorite, rated or cooked is a signal
+//! of what the account likes. The signals are spread over the tags and
+//! ingredients of those recipes and every visible recipe is scored by how
+//! much the account likes its tags and ingredients.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use time::OffsetDateTime;
+use tracing::instrument;
+
+use crate::models::ingredients::Ingredient;
+use crate::models::ingredients::IngredientUuid;
+use crate::models::recipe_cooks::RecipeCook;
+use crate::models::recipe_ratings::RecipeRating;
+use crate::models::recipes::Recipe;
+use crate::models::recipes::RecipeUuid;
+use crate::models::recipes::RecipeViewer;
+use crate::models::tags::Tag;
+use crate::models::tags::TagUuid;
+
+/// How much marking a recipe as favorite counts.
+const FAVORITE_WEIGHT: f64 = 3.0;
+
+/// How much cooking a recipe once counts.
+const COOK_WEIGHT: f64 = 1.0;
+
+/// Ratings above this count as liking a recipe, ratings below as disliking it.
+const NEUTRAL_RATING: i16 = 3;
+
+/// How much a shared ingredient counts compared to a shared tag.
+const INGREDIENT_FACTOR: f64 = 0.5;
+
+/// The number of days after which cooking a recipe doesn't lower its score anymore.
+const RECENCY_DAYS: i64 = 14;
+
+/// The maximum number of reasons given for a single recommendation.
+const MAX_REASONS: usize = 3;
+
+/// A recipe recommended for an account.
+#[derive(Debug, Clone)]
+pub struct Recommendation {
+    /// The recommended recipe.
+    pub recipe: Recipe,
+
+    /// How well the recipe matches the account's taste, higher is better.
+    pub score: f64,
+
+    /// The most important reasons for the score.
+    pub reasons: Vec<RecommendationReason>,
+}
+
+/// Explains why a recipe has been recommended.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum RecommendationReason {
+    /// The account has marked the recipe as favorite
+    Favorite,
+    /// The account has rated the recipe highly
+    RatedHighly {
+        /// The number of stars
+        rating: i16,
+    },
+    /// The recipe is similar to one the account liked
+    SimilarTo {
+        /// The liked recipe
+        recipe: RecipeUuid,
+        /// The name of the liked recipe
+        name: MaxStr<255>,
+    },
+    /// The recipe has a tag the account likes
+    LikedTag {
+        /// The liked tag
+        tag: TagUuid,
+        /// The name of the tag
+        name: MaxStr<255>,
+    },
+    /// The recipe uses an ingredient the account likes
+    LikedIngredient {
+        /// The liked ingredient
+        ingredient: IngredientUuid,
+        /// The name of the ingredient
+        name: MaxStr<255>,
+    },
+    /// The recipe has been cooked recently which lowers its score
+    CookedRecently {
+        /// The number of days since the recipe was cooked
+        days_ago: i64,
+    },
+}
+
+/// The tags and ingredients of a recipe.
+struct RecipeFeatures {
+    tags: Vec<Tag>,
+    ingredients: Vec<IngredientUuid>,
+}
+
+impl Recommendation {
+    /// Scores all recipes visible for a viewer and returns the best ones.
+    ///
+    /// Recipes the account has rated badly and recipes nothing speaks for are left out.
+    #[instrument(name = "Recommendation::query", skip(exe))]
+    pub async fn query(
+        exe: impl Executor<'_>,
+        viewer: &RecipeViewer,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let favorites: HashSet<_> =
+            Recipe::query_favorites(guard.get_transaction(), &viewer.account)
+                .await?
+                .into_iter()
+                .collect();
+        let ratings: HashMap<_, _> =
+            RecipeRating::query_by_account(guard.get_transaction(), &viewer.account)
+                .await?
+                .into_iter()
+                .map(|rating| (rating.recipe, rating.rating))
+                .collect();
+        let cooks = RecipeCook::query_by_account(guard.get_transaction(), &viewer.account).await?;
+
+        let mut last_cooked = HashMap::new();
+        for cook in
+            RecipeCook::query_by_household(guard.get_transaction(), &viewer.household).await?
+        {
+            // The cooks are ordered by most recent first
+            last_cooked.entry(cook.recipe).or_insert(cook.cooked_at);
+        }
+
+        let ingredient_names: HashMap<_, _> =
+            Ingredient::query_all(guard.get_transaction(), &viewer.household)
+                .await?
+                .into_iter()
+                .map(|ingredient| (ingredient.uuid, ingredient.name))
+                .collect();
+
+        let recipes = Recipe::query_visible(guard.get_transaction(), viewer).await?;
+        let mut features: HashMap<_, _> = recipes
+            .iter()
+            .map(|recipe| {
+                let features = RecipeFeatures {
+                    tags: Vec::new(),
+                    ingredients: Vec::new(),
+                };
+                (recipe.uuid, features)
+            })
+            .collect();
+        for (recipe, tag) in
+            Tag::query_by_recipes(guard.get_transaction(), &viewer.household).await?
+        {
+            if let Some(features) = features.get_mut(&recipe) {
+                features.tags.push(tag);
+            }
+        }
+        for (recipe, ingredient) in
+            Recipe::query_visible_ingredients(guard.get_transaction(), viewer).await?
+        {
+            if let Some(features) = features.get_mut(&recipe) {
+                features.ingredients.push(ingredient);
+            }
+        }
+
+        guard.commit().await?;
+
+        // How much the account likes the recipes it has interacted with
+        let mut liked: HashMap<RecipeUuid, f64> = HashMap::new();
+        for recipe in &favorites {
+            *liked.entry(*recipe).or_default() += FAVORITE_WEIGHT;
+        }
+        for (recipe, rating) in &ratings {
+            *liked.entry(*recipe).or_default() += f64::from(rating - NEUTRAL_RATING);
+        }
+        for cook in &cooks {
+            *liked.entry(cook.recipe).or_default() += COOK_WEIGHT;
+        }
+
+        // Spread the liking of each recipe over its tags and ingredients
+        let mut tag_affinity: HashMap<TagUuid, f64> = HashMap::new();
+        let mut ingredient_affinity: HashMap<IngredientUuid, f64> = HashMap::new();
+        for (recipe, weight) in &liked {
+            let Some(features) = features.get(recipe) else {
+                continue;
+            };
+            for tag in &features.tags {
+                *tag_affinity.entry(tag.uuid).or_default() += weight / features.tags.len() as f64;
+            }
+            for ingredient in &features.ingredients {
+                *ingredient_affinity.entry(*ingredient).or_default() +=
+                    weight / features.ingredients.len() as f64;
+            }
+        }
+
+        let names: HashMap<_, _> = recipes
+            .iter()
+            .map(|recipe| (recipe.uuid, recipe.name.clone()))
+            .collect();
+        let now = OffsetDateTime::now_utc();
+
+        let mut result = Vec::new();
+        for recipe in recipes {
+            let rating = ratings.get(&recipe.uuid).copied();
+            if rating.is_some_and(|rating| rating < NEUTRAL_RATING) {
+                continue;
+            }
+            let Some(own) = features.get(&recipe.uuid) else {
+                continue;
+            };
+
+            // Every contribution to the score together with the reason explaining it
+            let mut contributions = Vec::new();
+
+            if favorites.contains(&recipe.uuid) {
+                contributions.push((FAVORITE_WEIGHT, RecommendationReason::Favorite));
+            }
+            if let Some(rating) = rating.filter(|rating| *rating > NEUTRAL_RATING) {
+                contributions.push((
+                    f64::from(rating - NEUTRAL_RATING),
+                    RecommendationReason::RatedHighly { rating },
+                ));
+            }
+            for tag in &own.tags {
+                if let Some(affinity) = tag_affinity.get(&tag.uuid).filter(|a| **a > 0.0) {
+                    contributions.push((
+                        *affinity,
+                        RecommendationReason::LikedTag {
+                            tag: tag.uuid,
+                            name: tag.name.clone(),
+                        },
+                    ));
+                }
+            }
+            for ingredient in &own.ingredients {
+                let Some(affinity) = ingredient_affinity.get(ingredient).filter(|a| **a > 0.0)
+                else {
+                    continue;
+                };
+                // Ingredients of other households' recipes can't be named
+                let Some(name) = ingredient_names.get(ingredient) else {
+                    continue;
+                };
+                contributions.push((
+                    affinity * INGREDIENT_FACTOR,
+                    RecommendationReason::LikedIngredient {
+                        ingredient: *ingredient,
+                        name: name.clone(),
+                    },
+                ));
+            }
+
+            // Being similar to a recipe the account liked counts towards the score
+            let similar = liked
+                .iter()
+                .filter(|(other, weight)| **other != recipe.uuid && **weight > 0.0)
+                .filter_map(|(other, weight)| {
+                    let other_features = features.get(other)?;
+                    let shared_tags = own
+                        .tags
+                        .iter()
+                        .filter(|tag| other_features.tags.iter().any(|o| o.uuid == tag.uuid))
+                        .count();
+                    let shared_ingredients = own
+                        .ingredients
+                        .iter()
+                        .filter(|ingredient| other_features.ingredients.contains(ingredient))
+                        .count();
+                    let similarity =
+                        shared_tags as f64 + shared_ingredients as f64 * INGREDIENT_FACTOR;
+                    (similarity > 0.0).then_some((*other, weight * similarity))
+                })
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+            if let Some((other, similarity)) = similar
+                && let Some(name) = names.get(&other)
+            {
+                contributions.push((
+                    similarity,
+                    RecommendationReason::SimilarTo {
+                        recipe: other,
+                        name: name.clone(),
+                    },
+                ));
+            }
+
+            let mut score: f64 = contributions.iter().map(|(value, _)| value).sum();
+            if score <= 0.0 {
+                continue;
+            }
+
+            if let Some(cooked_at) = last_cooked.get(&recipe.uuid) {
+                let days_ago = (now - *cooked_at).whole_days();
+                if days_ago < RECENCY_DAYS {
+                    let penalty = score * (RECENCY_DAYS - days_ago) as f64 / RECENCY_DAYS as f64;
+                    score -= penalty;
+                    contributions
+                        .push((-penalty, RecommendationReason::CookedRecently { days_ago }));
+                }
+            }
+
+            if score <= 0.0 {
+                continue;
+            }
+
+            contributions.sort_by(|(a, _), (b, _)| b.abs().total_cmp(&a.abs()));
+            result.push(Recommendation {
+                recipe,
+                score,
+                reasons: contributions
+                    .into_iter()
+                    .take(MAX_REASONS)
+                    .map(|(_, reason)| reason)
+                    .collect(),
+            });
+        }
+
+        result.sort_by(|a, b| b.score.total_cmp(&a.score));
+        result.truncate(limit);
+        Ok(result)
+    }
+}
@@ -0,0 +1,65 @@
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModel;
+use galvyn::rorm::Model;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::models::account::db::AccountModel;
+use crate::models::households::db::HouseholdModel;
+use crate::models::ingredients::db::IngredientModel;
+use crate::models::ingredients::Units;
+
+/// Represents a shopping list.
+#[derive(Model)]
+#[rorm(rename = "shopping_list")]
+pub struct ShoppingListModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// The name of the list.
+    pub name: MaxStr<255>,
+
+    /// A foreign key referencing the household owning the list.
+    #[rorm(on_delete = "Cascade")]
+    pub household: ForeignModel<HouseholdModel>,
+
+    /// The account which created the list.
+    #[rorm(on_delete = "Cascade")]
+    pub account: ForeignModel<AccountModel>,
+
+    pub created_at: OffsetDateTime,
+}
+
+/// Represents an item on a shopping list.
+#[derive(Model)]
+#[rorm(rename = "shopping_list_item")]
+pub struct ShoppingListItemModel {
+    #[rorm(primary_key)]
+    pub uuid: Uuid,
+
+    /// A foreign key referencing the list the item is on.
+    #[rorm(on_delete = "Cascade")]
+    pub list: ForeignModel<ShoppingListModel>,
+
+    /// The position of the item on the list.
+    pub index: i32,
+
+    /// The ingredient to buy.
+    #[rorm(on_delete = "SetNull")]
+    pub ingredient: Option<ForeignModel<IngredientModel>>,
+
+    /// The name of the item.
+    ///
+    /// For items linked to an ingredient, this is the ingredient's name at the time the item was added.
+    pub name: MaxStr<255>,
+
+    /// The quantity to buy.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// Whether the item has already been bought.
+    #[rorm(default = false)]
+    pub checked: bool,
+}
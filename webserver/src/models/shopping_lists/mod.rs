@@ -0,0 +1,360 @@
+//! Shopping lists and their items.
+
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+use galvyn::core::re_exports::rorm;
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::rorm::and;
+use galvyn::rorm::db::Executor;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::prelude::ForeignModelByField;
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::models::account::AccountUuid;
+use crate::models::households::HouseholdUuid;
+use crate::models::ingredients::IngredientMeasures;
+use crate::models::ingredients::IngredientUuid;
+use crate::models::ingredients::Units;
+use crate::models::shopping_lists::db::ShoppingListItemModel;
+use crate::models::shopping_lists::db::ShoppingListModel;
+
+pub(in crate::models) mod db;
+
+/// Domain representation of a shopping list.
+#[derive(Debug, Clone)]
+pub struct ShoppingList {
+    /// Stable identifier for this list.
+    pub uuid: ShoppingListUuid,
+
+    /// The name of the list.
+    pub name: MaxStr<255>,
+
+    /// The account which created the list.
+    pub account: AccountUuid,
+
+    /// The point in time the list was created.
+    pub created_at: OffsetDateTime,
+}
+
+/// Strongly typed UUID for shopping lists.
+#[derive(Debug, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ShoppingListUuid(pub Uuid);
+
+/// Domain representation of an item on a shopping list.
+#[derive(Debug, Clone)]
+pub struct ShoppingListItem {
+    /// Stable identifier for this item.
+    pub uuid: ShoppingListItemUuid,
+
+    /// The list the item is on.
+    pub list: ShoppingListUuid,
+
+    /// The position of the item on the list.
+    pub index: i32,
+
+    /// The ingredient to buy.
+    pub ingredient: Option<IngredientUuid>,
+
+    /// The name of the item.
+    pub name: MaxStr<255>,
+
+    /// The quantity to buy.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+
+    /// Whether the item has already been bought.
+    pub checked: bool,
+}
+
+/// Strongly typed UUID for shopping list items.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ShoppingListItemUuid(pub Uuid);
+
+/// An item which should be added to a shopping list.
+#[derive(Debug, Clone)]
+pub struct NewShoppingListItem {
+    /// The ingredient to buy.
+    pub ingredient: Option<IngredientUuid>,
+
+    /// The name of the item.
+    pub name: MaxStr<255>,
+
+    /// The quantity to buy.
+    pub amount: f64,
+
+    /// The unit of the quantity.
+    pub unit: Units,
+}
+
+impl NewShoppingListItem {
+    /// Merges items of the same ingredient whose units can be converted into each other.
+    ///
+    /// Units of different dimensions are converted using the ingredients' `measures`.
+    /// Merged amounts are given in the unit of the first item.
+    /// Items which can't be merged are kept as separate items.
+    pub fn aggregate(
+        items: impl IntoIterator<Item = Self>,
+        measures: &HashMap<IngredientUuid, IngredientMeasures>,
+    ) -> Vec<Self> {
+        let mut result: Vec<Self> = Vec::new();
+
+        for item in items {
+            let existing = item.ingredient.and_then(|ingredient| {
+                result.iter_mut().find_map(|other| {
+                    if other.ingredient != Some(ingredient) {
+                        return None;
+                    }
+                    let measures = measures.get(&ingredient).copied().unwrap_or_default();
+                    let amount = item.unit.convert(item.amount, other.unit, measures)?;
+                    Some((other, amount))
+                })
+            });
+
+            match existing {
+                Some((other, amount)) => other.amount += amount,
+                None => result.push(item),
+            }
+        }
+
+        result
+    }
+}
+
+impl ShoppingList {
+    /// Lists all shopping lists of a household, most recent first.
+    #[instrument(name = "ShoppingList::query_all", skip(exe))]
+    pub async fn query_all(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, ShoppingListModel)
+            .condition(ShoppingListModel.household.equals(household.0))
+            .order_desc(ShoppingListModel.created_at)
+            .stream()
+            .map_ok(ShoppingList::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Looks up a single shopping list by its UUID.
+    #[instrument(name = "ShoppingList::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        uuid: &ShoppingListUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let list = rorm::query(exe, ShoppingListModel)
+            .condition(and![
+                ShoppingListModel.uuid.equals(uuid.0),
+                ShoppingListModel.household.equals(household.0),
+            ])
+            .optional()
+            .await?;
+        Ok(list.map(Self::from))
+    }
+
+    /// Creates a new shopping list with its items.
+    #[instrument(name = "ShoppingList::create", skip(exe, items))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        name: MaxStr<255>,
+        account: AccountUuid,
+        household: &HouseholdUuid,
+        items: Vec<NewShoppingListItem>,
+    ) -> anyhow::Result<Self> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let model = rorm::insert(guard.get_transaction(), ShoppingListModel)
+            .single(&ShoppingListModel {
+                uuid: Uuid::new_v4(),
+                name,
+                household: ForeignModelByField(household.0),
+                account: ForeignModelByField(account.get_inner()),
+                created_at: OffsetDateTime::now_utc(),
+            })
+            .await?;
+
+        if !items.is_empty() {
+            rorm::insert(guard.get_transaction(), ShoppingListItemModel)
+                .return_nothing()
+                .bulk(items.into_iter().zip(0..).map(|(item, index)| {
+                    ShoppingListItemModel {
+                        uuid: Uuid::new_v4(),
+                        list: ForeignModelByField(model.uuid),
+                        index,
+                        ingredient: item
+                            .ingredient
+                            .map(|ingredient| ForeignModelByField(ingredient.get_inner())),
+                        name: item.name,
+                        amount: item.amount,
+                        unit: item.unit,
+                        checked: false,
+                    }
+                }))
+                .await?;
+        }
+
+        guard.commit().await?;
+        Ok(ShoppingList::from(model))
+    }
+
+    /// Deletes the shopping list with all its items.
+    #[instrument(name = "ShoppingList::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, ShoppingListModel)
+            .condition(ShoppingListModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+}
+
+impl ShoppingListItem {
+    /// Lists all items of a shopping list ordered by their position.
+    #[instrument(name = "ShoppingListItem::query_by_list", skip(exe))]
+    pub async fn query_by_list(
+        exe: impl Executor<'_>,
+        list_uuid: &ShoppingListUuid,
+    ) -> anyhow::Result<Vec<Self>> {
+        let result: Vec<_> = rorm::query(exe, ShoppingListItemModel)
+            .condition(ShoppingListItemModel.list.equals(list_uuid.0))
+            .order_asc(ShoppingListItemModel.index)
+            .stream()
+            .map_ok(ShoppingListItem::from)
+            .try_collect()
+            .await?;
+        Ok(result)
+    }
+
+    /// Looks up a single item by its UUID.
+    #[instrument(name = "ShoppingListItem::query_by_uuid", skip(exe))]
+    pub async fn query_by_uuid(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        uuid: &ShoppingListItemUuid,
+    ) -> anyhow::Result<Option<Self>> {
+        let item = rorm::query(exe, ShoppingListItemModel)
+            .condition(and![
+                ShoppingListItemModel.uuid.equals(uuid.0),
+                ShoppingListItemModel.list.household.equals(household.0),
+            ])
+            .optional()
+            .await?;
+        Ok(item.map(Self::from))
+    }
+
+    /// Appends a new item to the end of a shopping list.
+    #[instrument(name = "ShoppingListItem::create", skip(exe))]
+    pub async fn create(
+        exe: impl Executor<'_>,
+        list_uuid: &ShoppingListUuid,
+        item: NewShoppingListItem,
+    ) -> anyhow::Result<Self> {
+        let mut guard = exe.ensure_transaction().await?;
+
+        let last_index = rorm::query(guard.get_transaction(), ShoppingListItemModel.index.max())
+            .condition(ShoppingListItemModel.list.equals(list_uuid.0))
+            .one()
+            .await?;
+
+        let model = rorm::insert(guard.get_transaction(), ShoppingListItemModel)
+            .single(&ShoppingListItemModel {
+                uuid: Uuid::new_v4(),
+                list: ForeignModelByField(list_uuid.0),
+                index: last_index.map_or(0, |index| index + 1),
+                ingredient: item
+                    .ingredient
+                    .map(|ingredient| ForeignModelByField(ingredient.get_inner())),
+                name: item.name,
+                amount: item.amount,
+                unit: item.unit,
+                checked: false,
+            })
+            .await?;
+
+        guard.commit().await?;
+        Ok(ShoppingListItem::from(model))
+    }
+
+    /// Update an item's name and quantity.
+    ///
+    /// The checked state is left untouched so editing an item doesn't revert
+    /// a concurrent [`ShoppingListItem::set_checked`].
+    #[instrument(name = "ShoppingListItem::update", skip(exe))]
+    pub async fn update(
+        &self,
+        exe: impl Executor<'_>,
+        name: MaxStr<255>,
+        amount: f64,
+        unit: Units,
+    ) -> anyhow::Result<()> {
+        rorm::update(exe, ShoppingListItemModel)
+            .set(ShoppingListItemModel.name, name)
+            .set(ShoppingListItemModel.amount, amount)
+            .set(ShoppingListItemModel.unit, unit)
+            .condition(ShoppingListItemModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+
+    /// Checks or unchecks an item.
+    ///
+    /// The state is set instead of toggled, so two clients checking the same item
+    /// at the same time agree on the result instead of reverting each other.
+    ///
+    /// Returns whether the state has actually changed.
+    #[instrument(name = "ShoppingListItem::set_checked", skip(exe))]
+    pub async fn set_checked(&self, exe: impl Executor<'_>, checked: bool) -> anyhow::Result<bool> {
+        let changed = rorm::update(exe, ShoppingListItemModel)
+            .set(ShoppingListItemModel.checked, checked)
+            .condition(and![
+                ShoppingListItemModel.uuid.equals(self.uuid.0),
+                ShoppingListItemModel.checked.not_equals(checked),
+            ])
+            .await?;
+        Ok(changed > 0)
+    }
+
+    /// Deletes the item.
+    #[instrument(name = "ShoppingListItem::delete", skip(exe))]
+    pub async fn delete(self, exe: impl Executor<'_>) -> anyhow::Result<()> {
+        rorm::delete(exe, ShoppingListItemModel)
+            .condition(ShoppingListItemModel.uuid.equals(self.uuid.0))
+            .await?;
+        Ok(())
+    }
+}
+
+impl From<ShoppingListModel> for ShoppingList {
+    fn from(model: ShoppingListModel) -> Self {
+        Self {
+            uuid: ShoppingListUuid(model.uuid),
+            name: model.name,
+            account: AccountUuid::new_from_model(model.account),
+            created_at: model.created_at,
+        }
+    }
+}
+
+impl From<ShoppingListItemModel> for ShoppingListItem {
+    fn from(model: ShoppingListItemModel) -> Self {
+        Self {
+            uuid: ShoppingListItemUuid(model.uuid),
+            list: ShoppingListUuid(model.list.0),
+            index: model.index,
+            ingredient: model.ingredient.map(IngredientUuid::from_model),
+            name: model.name,
+            amount: model.amount,
+            unit: model.unit,
+            checked: model.checked,
+        }
+    }
+}
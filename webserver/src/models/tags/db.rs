@@ -3,6 +3,7 @@ use galvyn::rorm::prelude::ForeignModel;
 use galvyn::rorm::Model;
 use uuid::Uuid;
 
+use crate::models::households::db::HouseholdModel;
 use crate::models::recipes::db::RecipeModel;
 use crate::models::tags::TagColors;
 
@@ -14,9 +15,12 @@ pub struct TagModel {
     pub uuid: Uuid,
 
     /// The name of the tag.
-    #[rorm(unique)]
     pub name: MaxStr<255>,
 
+    /// A foreign key referencing the household owning the tag.
+    #[rorm(on_delete = "Cascade")]
+    pub household: ForeignModel<HouseholdModel>,
+
     /// An enum representing the color associated with the tag.
     pub color: TagColors,
 }
@@ -15,6 +15,7 @@ use galvyn::rorm::DbEnum;
 use tracing::instrument;
 use uuid::Uuid;
 
+use crate::models::households::HouseholdUuid;
 use crate::models::recipes::RecipeUuid;
 use crate::models::tags::db::RecipeTagModel;
 use crate::models::tags::db::TagModel;
@@ -33,24 +34,34 @@ pub struct Tag {
 }
 
 /// New type wrapper around Uuid to provide type safety for tag identifiers.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct TagUuid(pub Uuid);
 impl Tag {
-    /// Count all tags in the database.
+    /// Count all tags of a household.
     #[instrument(name = "Tag::query_total", skip(exe))]
-    pub async fn query_total(exe: impl Executor<'_>) -> anyhow::Result<i64> {
-        let total = rorm::query(exe, TagModel.uuid.count()).one().await?;
+    pub async fn query_total(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<i64> {
+        let total = rorm::query(exe, TagModel.uuid.count())
+            .condition(TagModel.household.equals(household.0))
+            .one()
+            .await?;
         Ok(total)
     }
 
-    /// List all tags associated with a given recipe.
+    /// List all tags of a household associated with a given recipe.
     #[instrument(name = "Tag::query_by_recipe", skip(exe))]
     pub async fn query_by_recipe(
         exe: impl Executor<'_>,
+        household: &HouseholdUuid,
         recipe_uuid: &RecipeUuid,
     ) -> anyhow::Result<Vec<Self>> {
         let result: Vec<_> = rorm::query(exe, RecipeTagModel.tag.query_as(TagModel))
-            .condition(RecipeTagModel.recipe.equals(recipe_uuid.0))
+            .condition(and![
+                RecipeTagModel.recipe.equals(recipe_uuid.0),
+                RecipeTagModel.tag.household.equals(household.0),
+            ])
             .stream()
             .map_ok(|model| Tag::from(model))
             .try_collect()
@@ -58,13 +69,35 @@ impl Tag {
         Ok(result)
     }
 
+    /// List all tags of a household together with the recipes they are associated with.
+    #[instrument(name = "Tag::query_by_recipes", skip(exe))]
+    pub async fn query_by_recipes(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+    ) -> anyhow::Result<Vec<(RecipeUuid, Self)>> {
+        let result: Vec<_> = rorm::query(
+            exe,
+            (RecipeTagModel.recipe, RecipeTagModel.tag.query_as(TagModel)),
+        )
+        .condition(RecipeTagModel.tag.household.equals(household.0))
+        .stream()
+        .map_ok(|(recipe, model)| (RecipeUuid(recipe.0), Tag::from(model)))
+        .try_collect()
+        .await?;
+        Ok(result)
+    }
+
     /// List tags with optional name filter and pagination support.
     pub async fn query_all(
         exe: impl Executor<'_>,
+        household: &HouseholdUuid,
         page_request: &GetPageRequest,
         filter_name: Option<String>,
     ) -> anyhow::Result<Vec<Self>> {
-        let condition = and![filter_name.map(|name| TagModel.name.contains_ignore_case(&name))];
+        let condition = and![
+            Some(TagModel.household.equals(household.0)),
+            filter_name.map(|name| TagModel.name.contains_ignore_case(&name)),
+        ];
 
         let result: Vec<_> = rorm::query(exe, TagModel)
             .condition(condition)
@@ -81,10 +114,14 @@ impl Tag {
     /// Fetch a tag by its UUID if it exists.
     pub async fn query_by_uuid(
         exe: impl Executor<'_>,
+        household: &HouseholdUuid,
         tag_uuid: &TagUuid,
     ) -> anyhow::Result<Option<Self>> {
         match rorm::query(exe, TagModel)
-            .condition(TagModel.uuid.equals(tag_uuid.0))
+            .condition(and![
+                TagModel.uuid.equals(tag_uuid.0),
+                TagModel.household.equals(household.0),
+            ])
             .optional()
             .await?
         {
@@ -93,10 +130,17 @@ impl Tag {
         }
     }
 
-    /// Find a tag by its unique name.
-    pub async fn query_by_name(exe: impl Executor<'_>, name: &str) -> anyhow::Result<Option<Self>> {
+    /// Find a tag by its name which is unique within a household.
+    pub async fn query_by_name(
+        exe: impl Executor<'_>,
+        household: &HouseholdUuid,
+        name: &str,
+    ) -> anyhow::Result<Option<Self>> {
         match rorm::query(exe, TagModel)
-            .condition(TagModel.name.equals(name))
+            .condition(and![
+                TagModel.name.equals(name),
+                TagModel.household.equals(household.0),
+            ])
             .optional()
             .await?
         {
@@ -109,6 +153,7 @@ impl Tag {
     #[instrument(name = "Tag::create", skip(exe))]
     pub async fn create(
         exe: impl Executor<'_>,
+        household: &HouseholdUuid,
         name: MaxStr<255>,
         color: TagColors,
     ) -> anyhow::Result<Self> {
@@ -116,6 +161,7 @@ impl Tag {
             .single(&TagModel {
                 uuid: Uuid::new_v4(),
                 name,
+                household: ForeignModelByField(household.0),
                 color,
             })
             .await?;
@@ -0,0 +1,236 @@
+//! Server-side state of live cook sessions.
+//!
+//! A cook session is shared by everyone cooking the same recipe at the same time.
+//! It is owned by the [`WebsocketManager`](crate::modules::websockets::WebsocketManager)'s worker,
+//! which applies the client's messages and broadcasts the resulting state to all participants.
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use galvyn::core::re_exports::schemars;
+use galvyn::core::re_exports::schemars::JsonSchema;
+use galvyn::core::re_exports::serde::Deserialize;
+use galvyn::core::re_exports::serde::Serialize;
+use galvyn::core::session::Id;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::http::handler::websockets::schema::CookSessionState;
+use crate::http::handler::websockets::schema::CookTimer;
+use crate::http::handler::websockets::schema::WsClientMsg;
+use crate::models::ingredients::IngredientUuid;
+use crate::models::recipes::RecipeUuid;
+
+/// The maximum number of timers running in a cook session at the same time
+const MAX_TIMERS: usize = 20;
+
+/// The maximum length of a timer's label in characters
+const MAX_TIMER_LABEL_LENGTH: usize = 64;
+
+/// The maximum duration of a timer in seconds
+const MAX_TIMER_DURATION_SECS: u32 = 24 * 60 * 60;
+
+/// Strongly typed UUID for cook sessions.
+#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct CookSessionUuid(pub Uuid);
+
+/// Strongly typed UUID for timers running in a cook session.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct CookTimerUuid(pub Uuid);
+
+/// The parts of a recipe the messages of a cook session are validated against.
+#[derive(Debug, Clone)]
+pub struct CookRecipe {
+    /// The recipe being cooked
+    pub uuid: RecipeUuid,
+
+    /// The number of steps of the recipe
+    pub steps: i16,
+
+    /// The ingredients of the recipe
+    pub ingredients: Vec<IngredientUuid>,
+}
+
+/// All currently open cook sessions.
+#[derive(Default)]
+pub struct CookSessions {
+    /// The open sessions
+    sessions: HashMap<CookSessionUuid, CookSession>,
+}
+
+/// A single cook session and the sessions participating in it.
+struct CookSession {
+    /// The recipe being cooked
+    recipe: CookRecipe,
+
+    /// The http sessions which have joined
+    participants: HashSet<Id>,
+
+    /// The index of the step the cooks are currently at
+    current_step: i16,
+
+    /// The ingredients which have already been checked off
+    checked_ingredients: Vec<IngredientUuid>,
+
+    /// The running timers
+    timers: Vec<CookTimer>,
+}
+
+impl CookSessions {
+    /// Applies a message received from a client.
+    ///
+    /// Returns the cook session whose state has changed and has to be broadcast to its participants.
+    /// Messages concerning a cook session the client has not joined
+    /// or which don't match its recipe are ignored.
+    pub fn apply(&mut self, participant: Id, message: WsClientMsg) -> Option<CookSessionUuid> {
+        match message {
+            // Joining requires the recipe to be looked up, see `CookSessions::join`
+            WsClientMsg::JoinCookSession { .. } => None,
+            WsClientMsg::LeaveCookSession { cook_session } => {
+                self.leave(participant, cook_session);
+                self.sessions
+                    .contains_key(&cook_session)
+                    .then_some(cook_session)
+            }
+            WsClientMsg::SetCookStep { cook_session, step } => {
+                let session = self.get_joined(participant, cook_session)?;
+                if !(0..session.recipe.steps).contains(&step) {
+                    return None;
+                }
+                session.current_step = step;
+                Some(cook_session)
+            }
+            WsClientMsg::CheckCookIngredient {
+                cook_session,
+                ingredient,
+                checked,
+            } => {
+                let session = self.get_joined(participant, cook_session)?;
+                if !session.recipe.ingredients.contains(&ingredient) {
+                    return None;
+                }
+                session.checked_ingredients.retain(|x| *x != ingredient);
+                if checked {
+                    session.checked_ingredients.push(ingredient);
+                }
+                Some(cook_session)
+            }
+            WsClientMsg::StartCookTimer {
+                cook_session,
+                label,
+                duration_secs,
+            } => {
+                let session = self.get_joined(participant, cook_session)?;
+                if session.timers.len() >= MAX_TIMERS
+                    || label.chars().count() > MAX_TIMER_LABEL_LENGTH
+                    || !(1..=MAX_TIMER_DURATION_SECS).contains(&duration_secs)
+                {
+                    return None;
+                }
+                session.timers.push(CookTimer {
+                    uuid: CookTimerUuid(Uuid::new_v4()),
+                    label,
+                    duration_secs,
+                    started_at: OffsetDateTime::now_utc().unix_timestamp(),
+                });
+                Some(cook_session)
+            }
+            WsClientMsg::StopCookTimer {
+                cook_session,
+                timer,
+            } => {
+                let session = self.get_joined(participant, cook_session)?;
+                session.timers.retain(|x| x.uuid != timer);
+                Some(cook_session)
+            }
+        }
+    }
+
+    /// Removes the participant from all cook sessions.
+    ///
+    /// Returns the cook sessions which still have participants and have to be broadcast.
+    pub fn leave_all(&mut self, participant: Id) -> Vec<CookSessionUuid> {
+        let joined: Vec<_> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.participants.contains(&participant))
+            .map(|(uuid, _)| *uuid)
+            .collect();
+
+        for uuid in &joined {
+            self.leave(participant, *uuid);
+        }
+
+        joined
+            .into_iter()
+            .filter(|uuid| self.sessions.contains_key(uuid))
+            .collect()
+    }
+
+    /// Retrieves the state of a cook session and the participants to send it to.
+    pub fn get_state(&self, uuid: CookSessionUuid) -> Option<(CookSessionState, Vec<Id>)> {
+        let session = self.sessions.get(&uuid)?;
+        Some((
+            CookSessionState {
+                uuid,
+                recipe: session.recipe.uuid,
+                participants: session.participants.len(),
+                current_step: session.current_step,
+                checked_ingredients: session.checked_ingredients.clone(),
+                timers: session.timers.clone(),
+            },
+            session.participants.iter().copied().collect(),
+        ))
+    }
+
+    /// Joins the cook session of the recipe, opening a new one if nobody is cooking it yet.
+    ///
+    /// The recipe has to be visible to the participant.
+    /// Returns the cook session which has to be broadcast to its participants.
+    pub fn join(&mut self, participant: Id, recipe: CookRecipe) -> CookSessionUuid {
+        let existing = self
+            .sessions
+            .iter_mut()
+            .find(|(_, session)| session.recipe.uuid == recipe.uuid);
+
+        if let Some((uuid, session)) = existing {
+            session.participants.insert(participant);
+            // The recipe might have been edited since the session has been opened
+            session.current_step = session.current_step.min((recipe.steps - 1).max(0));
+            session
+                .checked_ingredients
+                .retain(|ingredient| recipe.ingredients.contains(ingredient));
+            session.recipe = recipe;
+            return *uuid;
+        }
+
+        let uuid = CookSessionUuid(Uuid::new_v4());
+        self.sessions.insert(
+            uuid,
+            CookSession {
+                recipe,
+                participants: HashSet::from([participant]),
+                current_step: 0,
+                checked_ingredients: Vec::new(),
+                timers: Vec::new(),
+            },
+        );
+        uuid
+    }
+
+    /// Leaves a cook session, closing it when the last participant is gone.
+    fn leave(&mut self, participant: Id, uuid: CookSessionUuid) {
+        if let Some(session) = self.sessions.get_mut(&uuid) {
+            session.participants.remove(&participant);
+            if session.participants.is_empty() {
+                self.sessions.remove(&uuid);
+            }
+        }
+    }
+
+    /// Retrieves a cook session if the participant has joined it.
+    fn get_joined(&mut self, participant: Id, uuid: CookSessionUuid) -> Option<&mut CookSession> {
+        self.sessions
+            .get_mut(&uuid)
+            .filter(|session| session.participants.contains(&participant))
+    }
+}
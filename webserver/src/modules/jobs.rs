@@ -0,0 +1,202 @@
+//! Durable background job queue backed by the database.
+//!
+//! A worker polls the `job` table for due jobs and runs them one at a time.
+//! Failed jobs are retried with exponential backoff until they run out of attempts.
+//! Recurring jobs are enqueued by cron-style schedules stored in the `job_schedule` table.
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use galvyn::core::InitError;
+use galvyn::core::Module;
+use galvyn::core::PreInitError;
+use galvyn::rorm::fields::types::MaxStr;
+use galvyn::rorm::Database;
+use time::OffsetDateTime;
+use tokio::sync::Notify;
+use tracing::error;
+use tracing::info;
+use tracing::instrument;
+use tracing::warn;
+
+use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::ingredients::Ingredient;
+use crate::models::jobs::Job;
+use crate::models::jobs::JobKind;
+use crate::models::jobs::JobSchedule;
+use crate::modules::websockets::WebsocketManager;
+use crate::utils::cron::CronSchedule;
+
+/// How often the worker looks for due jobs and schedules
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How long a job may run before it is considered abandoned by a crashed worker
+const LOCK_TIMEOUT: time::Duration = time::Duration::hours(1);
+
+/// The delay before the first retry of a failed job, doubled on every further attempt
+const RETRY_BASE_DELAY: time::Duration = time::Duration::seconds(30);
+
+/// The maximum delay between two attempts of a failed job
+const RETRY_MAX_DELAY: time::Duration = time::Duration::hours(1);
+
+/// How long finished jobs are kept for inspection
+const RETENTION: time::Duration = time::Duration::days(7);
+
+/// The built-in recurring jobs and their cron expressions in UTC
+const SCHEDULES: &[(JobKind, &str)] = &[(JobKind::PurgeUnusedIngredients, "0 4 * * *")];
+
+/// Runs background jobs stored in the database.
+pub struct JobQueue {
+    /// Wakes up the worker before its next poll
+    notify: Arc<Notify>,
+}
+
+impl JobQueue {
+    /// Wakes up the worker to run newly enqueued jobs without waiting for its next poll.
+    pub fn wake_up(&self) {
+        self.notify.notify_one();
+    }
+}
+
+impl Module for JobQueue {
+    type Setup = ();
+    type PreInit = Vec<(JobKind, &'static str, CronSchedule)>;
+
+    async fn pre_init(_setup: Self::Setup) -> Result<Self::PreInit, PreInitError> {
+        let mut schedules = Vec::new();
+        for (kind, cron) in SCHEDULES {
+            schedules.push((*kind, *cron, CronSchedule::parse(cron)?));
+        }
+        Ok(schedules)
+    }
+
+    // The worker uses the database as soon as it is spawned
+    type Dependencies = (Database,);
+
+    async fn init(
+        pre_init: Self::PreInit,
+        _dependencies: &mut Self::Dependencies,
+    ) -> Result<Self, InitError> {
+        let notify = Arc::new(Notify::new());
+
+        tokio::spawn(
+            JobWorker {
+                notify: notify.clone(),
+                schedules: pre_init,
+            }
+            .run(),
+        );
+
+        Ok(Self { notify })
+    }
+}
+
+/// The task polling for and running due jobs.
+struct JobWorker {
+    /// Wakes up the worker before its next poll
+    notify: Arc<Notify>,
+
+    /// The built-in schedules
+    schedules: Vec<(JobKind, &'static str, CronSchedule)>,
+}
+
+impl JobWorker {
+    /// Polls for due schedules and jobs until the server shuts down.
+    async fn run(self) {
+        if let Err(error) = self.register_schedules().await {
+            error!(error.display = %error, "Failed to register job schedules");
+        }
+
+        loop {
+            if let Err(error) = self.poll().await {
+                error!(error.display = %error, "Failed to poll background jobs");
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                _ = self.notify.notified() => {}
+            }
+        }
+    }
+
+    /// Stores the built-in schedules, updating them if their cron expression has changed.
+    async fn register_schedules(&self) -> anyhow::Result<()> {
+        let now = OffsetDateTime::now_utc();
+        for (kind, cron, schedule) in &self.schedules {
+            let Some(next_run_at) = schedule.next_after(now) else {
+                warn!(?kind, cron, "Job schedule never matches");
+                continue;
+            };
+            let cron = MaxStr::new(cron.to_string())
+                .map_err(|_| anyhow!("Cron expression \"{cron}\" is too long"))?;
+            JobSchedule::create_or_update(Database::global(), *kind, cron, next_run_at).await?;
+        }
+        Ok(())
+    }
+
+    /// Enqueues the jobs of due schedules and runs all due jobs.
+    async fn poll(&self) -> anyhow::Result<()> {
+        let db = Database::global();
+        let now = OffsetDateTime::now_utc();
+
+        for schedule in JobSchedule::query_all(db).await? {
+            if schedule.next_run_at > now {
+                continue;
+            }
+            let Some(next_run_at) = CronSchedule::parse(&schedule.cron)?.next_after(now) else {
+                continue;
+            };
+            schedule.enqueue(db, next_run_at).await?;
+        }
+
+        while let Some(job) = Job::claim_next(db, LOCK_TIMEOUT).await? {
+            match run_job(job.kind).await {
+                Ok(()) => job.succeed(db).await?,
+                Err(error) => {
+                    let retry_at = (job.attempts < job.max_attempts)
+                        .then(|| OffsetDateTime::now_utc() + retry_delay(job.attempts));
+                    warn!(
+                        kind = ?job.kind,
+                        attempts = job.attempts,
+                        error.display = %error,
+                        "Background job failed"
+                    );
+                    job.fail(db, error.to_string(), retry_at).await?;
+                }
+            }
+        }
+
+        Job::delete_finished(db, now - RETENTION).await?;
+
+        Ok(())
+    }
+}
+
+/// Calculates the delay before the next attempt of a job which has failed `attempts` times.
+fn retry_delay(attempts: i32) -> time::Duration {
+    let factor = 2_i32.saturating_pow(attempts.saturating_sub(1).clamp(0, 16) as u32);
+    (RETRY_BASE_DELAY * factor).min(RETRY_MAX_DELAY)
+}
+
+/// Runs a single job.
+#[instrument(name = "JobQueue::run_job")]
+async fn run_job(kind: JobKind) -> anyhow::Result<()> {
+    match kind {
+        JobKind::PurgeUnusedIngredients => purge_unused_ingredients().await,
+    }
+}
+
+/// Deletes ingredients nothing refers to anymore, e.g. after their recipes have been deleted.
+async fn purge_unused_ingredients() -> anyhow::Result<()> {
+    let households = Ingredient::delete_unused(Database::global()).await?;
+    info!(deleted = households.len(), "Purged unused ingredients");
+
+    let households: HashSet<_> = households.into_iter().collect();
+    for household in households {
+        WebsocketManager::global()
+            .send_to_household(household, WsServerMsg::IngredientsChanged {})
+            .await;
+    }
+
+    Ok(())
+}
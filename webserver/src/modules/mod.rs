@@ -1,2 +1,4 @@
+pub mod cook_sessions;
+pub mod jobs;
 pub mod oidc;
 pub mod websockets;
@@ -3,12 +3,27 @@ use galvyn::core::session::Id;
 use galvyn::core::InitError;
 use galvyn::core::Module;
 use galvyn::core::PreInitError;
+use tokio::select;
 use tokio::sync::mpsc::channel;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::mpsc::Receiver;
 use tokio::sync::mpsc::Sender;
+use tracing::debug;
 use tracing::error;
 
+use crate::http::handler::websockets::schema::WsClientMsg;
 use crate::http::handler::websockets::schema::WsServerMsg;
+use crate::models::account::AccountUuid;
+use crate::models::households::HouseholdUuid;
+use crate::modules::cook_sessions::CookRecipe;
+use crate::modules::cook_sessions::CookSessionUuid;
+use crate::modules::cook_sessions::CookSessions;
+
+/// The number of messages a websocket may queue before it is considered too slow
+pub const WEBSOCKET_BUFFER: usize = 64;
+
+/// The number of commands the websockets' tasks may queue for the manager
+const SOCKET_COMMAND_BUFFER: usize = 1024;
 
 /// Represents the WebSocketManager struct, responsible for managing WebSocket connections.
 ///
@@ -16,16 +31,34 @@ use crate::http::handler::websockets::schema::WsServerMsg;
 pub struct WebsocketManager {
     /// An instance of `Sender<WebsocketManagerCommand>` used to send commands
     sender: Sender<WebsocketManagerCommand>,
+
+    /// Channel for the commands of the websockets' own tasks
+    ///
+    /// The manager never waits for a websocket, so the websockets may wait for it.
+    /// Messages of clients are dropped instead if it is lagging behind.
+    socket_sender: Sender<SocketCommand>,
 }
 
 impl WebsocketManager {
     /// Registers a new session with the WebSocket manager.
     ///
     /// This function handles the registration process, sending a command to the
-    /// WebSocket manager to establish a new session.
-    pub async fn register(&self, session: Id, sender: Sender<WsServerMsg>) {
-        self.send(WebsocketManagerCommand::Register { session, sender })
-            .await
+    /// WebSocket manager to establish a new session of an account receiving the messages of
+    /// its household.
+    pub async fn register(
+        &self,
+        session: Id,
+        account: AccountUuid,
+        household: HouseholdUuid,
+        sender: Sender<WsServerMsg>,
+    ) {
+        self.send_from_socket(SocketCommand::Register {
+            session,
+            account,
+            household,
+            sender,
+        })
+        .await
     }
 
     /// Closes a WebSocket session.
@@ -36,11 +69,53 @@ impl WebsocketManager {
             .await
     }
 
-    /// Sends a message to all connected clients via the WebsocketManager.
+    /// Moves the websockets of an account to the household it has become member of.
+    ///
+    /// They leave their cook sessions, as the recipes might not be visible anymore.
+    pub async fn change_household(&self, account: AccountUuid, household: HouseholdUuid) {
+        self.send(WebsocketManagerCommand::ChangeHousehold { account, household })
+            .await
+    }
+
+    /// Notifies the WebSocket manager that a websocket of a session has been closed.
+    ///
+    /// Once the last websocket of the session is gone, it leaves all cook sessions.
+    pub async fn disconnected(&self, session: Id) {
+        self.send_from_socket(SocketCommand::Disconnected { session })
+            .await
+    }
+
+    /// Joins the cook session of a recipe which has been checked to be visible to the session.
+    ///
+    /// Returns `false` if the manager is lagging behind and the command has been dropped.
+    pub fn join_cook_session(&self, session: Id, recipe: CookRecipe) -> bool {
+        self.try_send_from_socket(SocketCommand::JoinCookSession { session, recipe })
+    }
+
+    /// Forwards a message received from a client to the WebSocket manager.
+    ///
+    /// Returns `false` if the manager is lagging behind and the message has been dropped.
+    pub fn handle_client_msg(&self, session: Id, message: WsClientMsg) -> bool {
+        self.try_send_from_socket(SocketCommand::ClientMessage { session, message })
+    }
+
+    /// Sends a message to all connected clients of a household via the WebsocketManager.
     ///
     /// This function takes a `WsServerMsg` and forwards it to the `WebsocketManager`
-    /// using the `SendToAll` command.  The operation is asynchronous, waiting
+    /// using the `SendToHousehold` command.  The operation is asynchronous, waiting
     /// for the `WebsocketManager` to complete the message sending.
+    pub async fn send_to_household(&self, household: HouseholdUuid, message: WsServerMsg) {
+        self.send(WebsocketManagerCommand::SendToHousehold { household, message })
+            .await
+    }
+
+    /// Sends a message to all connected clients of some accounts via the WebsocketManager.
+    pub async fn send_to_accounts(&self, accounts: Vec<AccountUuid>, message: WsServerMsg) {
+        self.send(WebsocketManagerCommand::SendToAccounts { accounts, message })
+            .await
+    }
+
+    /// Sends a message to all connected clients via the WebsocketManager.
     pub async fn send_to_all(&self, message: WsServerMsg) {
         self.send(WebsocketManagerCommand::SendToAll { message })
             .await
@@ -55,6 +130,30 @@ impl WebsocketManager {
             error!("Websocket manager died!");
         }
     }
+
+    /// Sends a command from a websocket's task to the websocket manager.
+    async fn send_from_socket(&self, cmd: SocketCommand) {
+        if self.socket_sender.send(cmd).await.is_err() {
+            error!("Websocket manager died!");
+        }
+    }
+
+    /// Sends a command from a websocket's task to the websocket manager without waiting.
+    ///
+    /// Returns `false` if the command has been dropped because the channel is full.
+    fn try_send_from_socket(&self, cmd: SocketCommand) -> bool {
+        match self.socket_sender.try_send(cmd) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                debug!("Websocket manager is lagging behind, dropped command");
+                false
+            }
+            Err(TrySendError::Closed(_)) => {
+                error!("Websocket manager died!");
+                false
+            }
+        }
+    }
 }
 
 impl Module for WebsocketManager {
@@ -72,16 +171,22 @@ impl Module for WebsocketManager {
         _dependencies: &mut Self::Dependencies,
     ) -> Result<Self, InitError> {
         let (sender, receiver) = channel(1);
+        let (socket_sender, socket_receiver) = channel(SOCKET_COMMAND_BUFFER);
 
         tokio::spawn(
             WebsocketManagerState {
                 receiver,
+                socket_receiver,
                 sockets: Vec::new(),
+                cook_sessions: CookSessions::default(),
             }
             .run(),
         );
 
-        Ok(Self { sender })
+        Ok(Self {
+            sender,
+            socket_sender,
+        })
     }
 }
 
@@ -90,9 +195,13 @@ impl Module for WebsocketManager {
 /// This enum defines the different actions that can be performed by the WebsocketManager.
 /// Each variant represents a specific command with the required data.
 enum WebsocketManagerCommand {
-    Register {
-        sender: Sender<WsServerMsg>,
-        session: Id,
+    SendToHousehold {
+        household: HouseholdUuid,
+        message: WsServerMsg,
+    },
+    SendToAccounts {
+        accounts: Vec<AccountUuid>,
+        message: WsServerMsg,
     },
     SendToAll {
         message: WsServerMsg,
@@ -100,6 +209,33 @@ enum WebsocketManagerCommand {
     CloseSession {
         session: Id,
     },
+    ChangeHousehold {
+        account: AccountUuid,
+        household: HouseholdUuid,
+    },
+}
+
+/// Represents a command sent by the task of a websocket.
+///
+/// They share a channel so they are handled in the order the websocket sent them.
+enum SocketCommand {
+    Register {
+        sender: Sender<WsServerMsg>,
+        session: Id,
+        account: AccountUuid,
+        household: HouseholdUuid,
+    },
+    Disconnected {
+        session: Id,
+    },
+    JoinCookSession {
+        session: Id,
+        recipe: CookRecipe,
+    },
+    ClientMessage {
+        session: Id,
+        message: WsClientMsg,
+    },
 }
 
 /// Represents the state of a WebsocketManager.
@@ -110,29 +246,145 @@ struct WebsocketManagerState {
     /// Channel to receive commands to execute
     receiver: Receiver<WebsocketManagerCommand>,
 
+    /// Channel to receive the commands of the websockets' tasks
+    socket_receiver: Receiver<SocketCommand>,
+
     /// All connected websockets
-    sockets: Vec<(Id, Sender<WsServerMsg>)>,
+    sockets: Vec<ConnectedSocket>,
+
+    /// All open cook sessions
+    cook_sessions: CookSessions,
+}
+
+/// A connected websocket and whose messages it receives.
+struct ConnectedSocket {
+    /// The session which opened the websocket
+    session: Id,
+
+    /// The account logged in to the session
+    account: AccountUuid,
+
+    /// The household of the account
+    household: HouseholdUuid,
+
+    /// Channel to the task writing to the websocket
+    ///
+    /// The manager never waits for a websocket, it is dropped once its channel is full.
+    sender: Sender<WsServerMsg>,
 }
 
 impl WebsocketManagerState {
     /// This function handles incoming WebSocket commands.
     /// It continuously receives commands from the `receiver` and processes them accordingly.
     pub async fn run(mut self) {
-        while let Some(cmd) = self.receiver.recv().await {
-            match cmd {
-                WebsocketManagerCommand::Register { sender, session } => {
-                    self.sockets.push((session, sender))
+        loop {
+            select! {
+                Some(cmd) = self.receiver.recv() => self.handle_command(cmd),
+                Some(cmd) = self.socket_receiver.recv() => self.handle_socket_command(cmd),
+                else => return,
+            }
+        }
+    }
+
+    /// Processes a command sent via the [`WebsocketManager`].
+    fn handle_command(&mut self, cmd: WebsocketManagerCommand) {
+        match cmd {
+            WebsocketManagerCommand::SendToHousehold { household, message } => {
+                self.send_to(|socket| socket.household == household, message)
+            }
+            WebsocketManagerCommand::SendToAccounts { accounts, message } => {
+                self.send_to(|socket| accounts.contains(&socket.account), message)
+            }
+            WebsocketManagerCommand::SendToAll { message } => self.send_to(|_| true, message),
+            WebsocketManagerCommand::CloseSession { session } => {
+                self.sockets.retain(|socket| socket.session != session);
+                self.leave_cook_sessions(session);
+            }
+            WebsocketManagerCommand::ChangeHousehold { account, household } => {
+                let mut sessions = Vec::new();
+                for socket in self
+                    .sockets
+                    .iter_mut()
+                    .filter(|socket| socket.account == account)
+                {
+                    socket.household = household;
+                    sessions.push(socket.session);
                 }
-                WebsocketManagerCommand::SendToAll { message } => {
-                    for (_, socket) in self.sockets.iter_mut() {
-                        let _ = socket.send(message.clone()).await;
-                    }
-                    self.sockets.retain(|(_, socket)| !socket.is_closed());
+                for session in sessions {
+                    self.leave_cook_sessions(session);
+                }
+            }
+        }
+    }
+
+    /// Processes a command sent by the task of a websocket.
+    fn handle_socket_command(&mut self, cmd: SocketCommand) {
+        match cmd {
+            SocketCommand::Register {
+                sender,
+                session,
+                account,
+                household,
+            } => self.sockets.push(ConnectedSocket {
+                session,
+                account,
+                household,
+                sender,
+            }),
+            SocketCommand::Disconnected { session } => {
+                self.sockets.retain(|socket| !socket.sender.is_closed());
+                if !self.sockets.iter().any(|socket| socket.session == session) {
+                    self.leave_cook_sessions(session);
                 }
-                WebsocketManagerCommand::CloseSession { session } => {
-                    self.sockets.retain(|(id, _)| *id != session)
+            }
+            SocketCommand::JoinCookSession { session, recipe } => {
+                let cook_session = self.cook_sessions.join(session, recipe);
+                self.send_cook_session(cook_session);
+            }
+            SocketCommand::ClientMessage { session, message } => {
+                if let Some(cook_session) = self.cook_sessions.apply(session, message) {
+                    self.send_cook_session(cook_session);
                 }
             }
         }
     }
+
+    /// Sends a message to all connected websockets matching a filter.
+    ///
+    /// Closed websockets and ones which can't keep up with their messages are dropped,
+    /// which closes the latter.
+    fn send_to(&mut self, filter: impl Fn(&ConnectedSocket) -> bool, message: WsServerMsg) {
+        self.sockets.retain(|socket| {
+            if !filter(socket) {
+                return !socket.sender.is_closed();
+            }
+            match socket.sender.try_send(message.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    debug!(session = ?socket.session, "Websocket is lagging behind, dropped it");
+                    false
+                }
+                Err(TrySendError::Closed(_)) => false,
+            }
+        });
+    }
+
+    /// Removes a session from all cook sessions and notifies the remaining participants.
+    fn leave_cook_sessions(&mut self, session: Id) {
+        for cook_session in self.cook_sessions.leave_all(session) {
+            self.send_cook_session(cook_session);
+        }
+    }
+
+    /// Sends the current state of a cook session to all its participants.
+    fn send_cook_session(&mut self, cook_session: CookSessionUuid) {
+        let Some((state, participants)) = self.cook_sessions.get_state(cook_session) else {
+            return;
+        };
+
+        self.send_to(
+            |socket| participants.contains(&socket.session),
+            WsServerMsg::CookSessionChanged { state },
+        );
+    }
 }
@@ -0,0 +1,155 @@
+//! Cron-style schedules for recurring background jobs.
+//!
+//! The usual five fields `minute hour day-of-month month day-of-week` are supported
+//! with `*`, single values, ranges (`1-5`), lists (`1,15`) and steps (`*/10`, `0-30/5`).
+//! Times are evaluated in UTC.
+
+use anyhow::bail;
+use time::Date;
+use time::Duration;
+use time::Month;
+use time::OffsetDateTime;
+use time::Time;
+use time::UtcOffset;
+
+/// Number of days searched for the next matching time before giving up
+const SEARCH_DAYS: i64 = 366 * 5;
+
+/// A parsed cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    /// The matching minutes, bit `n` for minute `n`
+    minutes: u64,
+    /// The matching hours, bit `n` for hour `n`
+    hours: u64,
+    /// The matching days of the month, bit `n` for day `n`
+    days: u64,
+    /// The matching months, bit `n` for month `n`
+    months: u64,
+    /// The matching days of the week, bit `0` for sunday
+    weekdays: u64,
+    /// Whether the day-of-month field has been restricted
+    days_restricted: bool,
+    /// Whether the day-of-week field has been restricted
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parses a cron expression like `30 4 * * 1-5`.
+    pub fn parse(expression: &str) -> anyhow::Result<Self> {
+        let fields: Vec<_> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields.as_slice() else {
+            bail!("Expected 5 fields in cron expression \"{expression}\"");
+        };
+
+        // Both 0 and 7 mean sunday
+        let mut weekdays_bits = parse_field(weekdays, 0, 7)?;
+        if weekdays_bits & (1 << 7) != 0 {
+            weekdays_bits = (weekdays_bits | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days: parse_field(days, 1, 31)?,
+            months: parse_field(months, 1, 12)?,
+            weekdays: weekdays_bits,
+            days_restricted: *days != "*",
+            weekdays_restricted: *weekdays != "*",
+        })
+    }
+
+    /// Finds the first matching minute after `after`.
+    ///
+    /// Returns `None` if the schedule never matches, e.g. for the 31st of February.
+    pub fn next_after(&self, after: OffsetDateTime) -> Option<OffsetDateTime> {
+        let after = after.to_offset(UtcOffset::UTC);
+        let mut next = after.replace_time(Time::from_hms(after.hour(), after.minute(), 0).ok()?)
+            + Duration::minutes(1);
+        let limit = next + Duration::days(SEARCH_DAYS);
+
+        while next < limit {
+            if !has_bit(self.months, u8::from(next.month())) {
+                next = first_of_next_month(next.date())?.midnight().assume_utc();
+                continue;
+            }
+            if !self.matches_day(next.date()) {
+                next = next.date().next_day()?.midnight().assume_utc();
+                continue;
+            }
+            if !has_bit(self.hours, next.hour()) {
+                next =
+                    next.replace_time(Time::from_hms(next.hour(), 0, 0).ok()?) + Duration::hours(1);
+                continue;
+            }
+            if !has_bit(self.minutes, next.minute()) {
+                next += Duration::minutes(1);
+                continue;
+            }
+            return Some(next);
+        }
+
+        None
+    }
+
+    /// Checks the day-of-month and day-of-week fields.
+    ///
+    /// Like in cron, a day matches either field if both have been restricted.
+    fn matches_day(&self, date: Date) -> bool {
+        let day = has_bit(self.days, date.day());
+        let weekday = has_bit(self.weekdays, date.weekday().number_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+/// Parses a single field of a cron expression into a bit set.
+fn parse_field(field: &str, min: u8, max: u8) -> anyhow::Result<u64> {
+    let mut bits = 0;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u8>()?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            bail!("Invalid step in cron field \"{field}\"");
+        }
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (start.parse()?, end.parse()?)
+        } else {
+            let value = range.parse()?;
+            // A single value with a step runs until the end of the range
+            (value, if part.contains('/') { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            bail!("Value out of range in cron field \"{field}\"");
+        }
+
+        for value in (start..=end).step_by(usize::from(step)) {
+            bits |= 1 << value;
+        }
+    }
+
+    Ok(bits)
+}
+
+/// Checks whether bit `n` is set.
+fn has_bit(bits: u64, n: u8) -> bool {
+    bits & (1 << n) != 0
+}
+
+/// Returns the first day of the month following the date's one.
+fn first_of_next_month(date: Date) -> Option<Date> {
+    let (year, month) = match date.month() {
+        Month::December => (date.year() + 1, Month::January),
+        month => (date.year(), month.next()),
+    };
+    Date::from_calendar_date(year, month, 1).ok()
+}
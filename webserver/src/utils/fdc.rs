@@ -0,0 +1,92 @@
+//! Reader for the CSV dumps of the USDA FoodData Central dataset.
+//!
+//! The dumps can be downloaded from <https://fdc.nal.usda.gov/download-datasets>.
+//! Only `food.csv` and `food_nutrient.csv` of the extracted directory are read.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use galvyn::core::re_exports::serde::Deserialize;
+
+use crate::models::foods::ImportFood;
+use crate::utils::max_str;
+
+/// Nutrient id of the energy in kcal
+const ENERGY_KCAL: i64 = 1008;
+/// Nutrient id of the energy in kcal (Atwater general factors), used by foundation foods
+const ENERGY_ATWATER_KCAL: i64 = 2047;
+/// Nutrient id of the protein
+const PROTEIN: i64 = 1003;
+/// Nutrient id of the total fat
+const FAT: i64 = 1004;
+/// Nutrient id of the carbohydrates
+const CARBOHYDRATES: i64 = 1005;
+/// Nutrient id of the dietary fiber
+const FIBER: i64 = 1079;
+/// Nutrient id of the total sugars
+const SUGAR: i64 = 2000;
+
+/// A row of `food.csv`
+#[derive(Deserialize)]
+struct FoodRow {
+    /// The id of the food
+    fdc_id: i64,
+    /// The name of the food
+    description: String,
+}
+
+/// A row of `food_nutrient.csv`
+#[derive(Deserialize)]
+struct FoodNutrientRow {
+    /// The id of the food
+    fdc_id: i64,
+    /// The id of the nutrient
+    nutrient_id: i64,
+    /// The amount of the nutrient per 100 g
+    amount: Option<f64>,
+}
+
+/// Reads all foods and their nutrients from an extracted dataset directory.
+pub fn read_dataset(dir: &Path) -> anyhow::Result<Vec<ImportFood>> {
+    let mut foods = HashMap::new();
+
+    for row in csv::Reader::from_path(dir.join("food.csv"))?.into_deserialize() {
+        let row: FoodRow = row?;
+        let name = max_str::truncated(row.description)?;
+
+        foods.insert(
+            row.fdc_id,
+            ImportFood {
+                fdc_id: row.fdc_id,
+                name,
+                energy_kcal: None,
+                protein: None,
+                fat: None,
+                carbohydrates: None,
+                fiber: None,
+                sugar: None,
+            },
+        );
+    }
+
+    for row in csv::Reader::from_path(dir.join("food_nutrient.csv"))?.into_deserialize() {
+        let row: FoodNutrientRow = row?;
+        let Some(food) = foods.get_mut(&row.fdc_id) else {
+            continue;
+        };
+
+        let field = match row.nutrient_id {
+            ENERGY_KCAL => &mut food.energy_kcal,
+            ENERGY_ATWATER_KCAL if food.energy_kcal.is_none() => &mut food.energy_kcal,
+            PROTEIN => &mut food.protein,
+            FAT => &mut food.fat,
+            CARBOHYDRATES => &mut food.carbohydrates,
+            FIBER => &mut food.fiber,
+            SUGAR => &mut food.sugar,
+            _ => continue,
+        };
+        *field = row.amount;
+    }
+
+    Ok(foods.into_values().collect())
+}
@@ -0,0 +1,15 @@
+//! Fitting strings of arbitrary length into length limited columns.
+
+use galvyn::rorm::fields::types::MaxStr;
+
+/// Cuts a string to the length a [`MaxStr`] accepts.
+///
+/// [`MaxStr`] counts bytes, so the string is cut at the last character boundary within the limit.
+pub fn truncated<const N: usize>(mut string: String) -> anyhow::Result<MaxStr<N>> {
+    let mut end = string.len().min(N);
+    while !string.is_char_boundary(end) {
+        end -= 1;
+    }
+    string.truncate(end);
+    Ok(MaxStr::new(string)?)
+}
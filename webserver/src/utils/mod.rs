@@ -0,0 +1,7 @@
+//! Helpers which are shared between multiple domains
+
+pub mod cron;
+pub mod fdc;
+pub mod markdown;
+pub mod max_str;
+pub mod normalize;
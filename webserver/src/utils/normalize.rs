@@ -0,0 +1,91 @@
+//! Normalization of ingredient names.
+//!
+//! Names which only differ in case, whitespace, accents or singular and plural
+//! (e.g. "Onion", " onions" and "ONIONS") are considered to be the same ingredient.
+
+/// Normalizes an ingredient name for comparison.
+///
+/// The name is lowercased, accents are removed, whitespace is collapsed
+/// and the last word is reduced to its singular form.
+pub fn ingredient_name(name: &str) -> String {
+    let mut folded = String::with_capacity(name.len());
+    for letter in name.chars().flat_map(char::to_lowercase) {
+        match fold_char(letter) {
+            Some(replacement) => folded.push_str(replacement),
+            None => folded.push(letter),
+        }
+    }
+
+    let mut words: Vec<_> = folded.split_whitespace().map(str::to_string).collect();
+    if let Some(last) = words.last_mut() {
+        *last = singular(last);
+    }
+    words.join(" ")
+}
+
+/// Replaces a lowercase latin letter with diacritics by its base letters.
+fn fold_char(letter: char) -> Option<&'static str> {
+    Some(match letter {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+        'ł' | 'ľ' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ś' | 'š' | 'ş' => "s",
+        'ß' => "ss",
+        'ť' | 'ţ' => "t",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        // Typographic variants of punctuation
+        '\u{2018}' | '\u{2019}' => "'",
+        '\u{2010}' | '\u{2011}' | '\u{2013}' | '\u{2014}' => "-",
+        _ => return None,
+    })
+}
+
+/// Words which end like plurals but are singular.
+const SINGULAR_WORDS: &[&str] = &["molasses", "series", "species"];
+
+/// Reduces an english word to its singular form using the common plural suffixes.
+///
+/// Short words and words which only look like plurals (e.g. "asparagus") are kept.
+/// Every suffix leaves a stem of at least three letters, so "pies" becomes "pie".
+fn singular(word: &str) -> String {
+    if word.chars().count() <= 3 || SINGULAR_WORDS.contains(&word) {
+        return word.to_string();
+    }
+
+    let stem = |suffix: &str| {
+        word.strip_suffix(suffix)
+            .filter(|stem| stem.chars().count() >= 3)
+    };
+
+    if let Some(stem) = stem("ies") {
+        return format!("{stem}y");
+    }
+
+    if let Some(stem) = stem("oes") {
+        return format!("{stem}o");
+    }
+
+    let sibilant = ["ches", "shes", "sses", "xes", "zes"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix));
+    if let Some(stem) = stem("es").filter(|_| sibilant) {
+        return stem.to_string();
+    }
+
+    if ["ss", "us", "is"].iter().any(|end| word.ends_with(end)) {
+        return word.to_string();
+    }
+
+    stem("s").unwrap_or(word).to_string()
+}